@@ -1,5 +1,6 @@
 use anchor_lang::prelude::*;
 use anchor_spl::token::{self, Mint, MintTo, Token, TokenAccount};
+use cryptarena_svm::{read_fresh_pyth_price, AssetConfig, Queued, MAX_ASSETS};
 use pyth_solana_receiver_sdk::price_update::PriceUpdateV2;
 
 declare_id!("9ZaAhicfWbLmdJUzXk2ZT1o5CTdaW6VE8mF9sju15D5E");
@@ -10,84 +11,7 @@ declare_id!("9ZaAhicfWbLmdJUzXk2ZT1o5CTdaW6VE8mF9sju15D5E");
 
 pub const CLAIM_COOLDOWN: i64 = 21600; // 6 hours in seconds
 pub const FAUCET_USD_VALUE: u64 = 15_000_000; // $15 with 6 decimals
-pub const PYTH_PRICE_MAX_AGE: u64 = 60; // 60 seconds
-pub const TOTAL_ASSETS: usize = 14;
-
-// Asset indices (same as main program)
-pub const ASSET_SOL: u8 = 0;
-pub const ASSET_TRUMP: u8 = 1;
-pub const ASSET_PUMP: u8 = 2;
-pub const ASSET_BONK: u8 = 3;
-pub const ASSET_JUP: u8 = 4;
-pub const ASSET_PENGU: u8 = 5;
-pub const ASSET_PYTH: u8 = 6;
-pub const ASSET_HNT: u8 = 7;
-pub const ASSET_FARTCOIN: u8 = 8;
-pub const ASSET_RAY: u8 = 9;
-pub const ASSET_JTO: u8 = 10;
-pub const ASSET_KMNO: u8 = 11;
-pub const ASSET_MET: u8 = 12;
-pub const ASSET_W: u8 = 13;
-
-// Pyth Price Feed IDs
-pub const PYTH_FEED_SOL: [u8; 32] = hex_to_bytes("de87506dabfadbef89af2d5d796ebae80ddaea240fc7667aa808fce3629cd8fb");
-pub const PYTH_FEED_TRUMP: [u8; 32] = hex_to_bytes("879551021853eec7a7dc827578e8e69da7e4fa8148339aa0d3d5296405be4b1a");
-pub const PYTH_FEED_PUMP: [u8; 32] = hex_to_bytes("7a01fca212788bba7c5bf8c9efd576a8a722f070d2c17596ff7bb609b8d5c3b9");
-pub const PYTH_FEED_BONK: [u8; 32] = hex_to_bytes("72b021217ca3fe68922a19aaf990109cb9d84e9ad004b4d2025ad6f529314419");
-pub const PYTH_FEED_JUP: [u8; 32] = hex_to_bytes("0a0408d619e9380abad35060f9192039ed5042fa6f82301d0e48bb52be830996");
-pub const PYTH_FEED_PENGU: [u8; 32] = hex_to_bytes("bed3097008b9b5e3c93bec20be79cb43986b85a996475589351a21e67bae9b61");
-pub const PYTH_FEED_PYTH: [u8; 32] = hex_to_bytes("0bbf28e9a841a1cc788f6a361b17ca072d0ea3098a1e5df1c3922d06719579ff");
-pub const PYTH_FEED_HNT: [u8; 32] = hex_to_bytes("649fdd7ec08e8e2a20f425729854e90293dcbe2376abc47197a14da6ff339756");
-pub const PYTH_FEED_FARTCOIN: [u8; 32] = hex_to_bytes("058cd29ef0e714c5affc44f269b2c1899a52da416d7acc147b9da692e6953608");
-pub const PYTH_FEED_RAY: [u8; 32] = hex_to_bytes("91568baa8beb53db23eb3fb7f22c6e8bd303d103919e19733f2bb642d3e7987a");
-pub const PYTH_FEED_JTO: [u8; 32] = hex_to_bytes("b43660a5f790c69354b0729a5ef9d50d68f1df92107540210b9cccba1f947cc2");
-pub const PYTH_FEED_KMNO: [u8; 32] = hex_to_bytes("b17e5bc5de742a8a378b54c9c75442b7d51e30ada63f28d9bd28d3c0e26511a0");
-pub const PYTH_FEED_MET: [u8; 32] = hex_to_bytes("0292e0f405bcd4a496d34e48307f6787349ad2bcd8505c3d3a9f77d81a67a682");
-pub const PYTH_FEED_W: [u8; 32] = hex_to_bytes("eff7446475e218517566ea99e72a4abec2e1bd8498b43b7d8331e29dcb059389");
-
-/// Convert hex string to bytes at compile time
-const fn hex_to_bytes(hex: &str) -> [u8; 32] {
-    let bytes = hex.as_bytes();
-    let mut result = [0u8; 32];
-    let mut i = 0;
-    while i < 32 {
-        let high = hex_char_to_nibble(bytes[i * 2]);
-        let low = hex_char_to_nibble(bytes[i * 2 + 1]);
-        result[i] = (high << 4) | low;
-        i += 1;
-    }
-    result
-}
-
-const fn hex_char_to_nibble(c: u8) -> u8 {
-    match c {
-        b'0'..=b'9' => c - b'0',
-        b'a'..=b'f' => c - b'a' + 10,
-        b'A'..=b'F' => c - b'A' + 10,
-        _ => 0,
-    }
-}
-
-/// Get Pyth feed ID for an asset index
-pub fn get_pyth_feed_id(asset_index: u8) -> [u8; 32] {
-    match asset_index {
-        ASSET_SOL => PYTH_FEED_SOL,
-        ASSET_TRUMP => PYTH_FEED_TRUMP,
-        ASSET_PUMP => PYTH_FEED_PUMP,
-        ASSET_BONK => PYTH_FEED_BONK,
-        ASSET_JUP => PYTH_FEED_JUP,
-        ASSET_PENGU => PYTH_FEED_PENGU,
-        ASSET_PYTH => PYTH_FEED_PYTH,
-        ASSET_HNT => PYTH_FEED_HNT,
-        ASSET_FARTCOIN => PYTH_FEED_FARTCOIN,
-        ASSET_RAY => PYTH_FEED_RAY,
-        ASSET_JTO => PYTH_FEED_JTO,
-        ASSET_KMNO => PYTH_FEED_KMNO,
-        ASSET_MET => PYTH_FEED_MET,
-        ASSET_W => PYTH_FEED_W,
-        _ => [0u8; 32],
-    }
-}
+pub const DEFAULT_SETTINGS_TIMELOCK: i64 = 0; // Raise with `update_settings_timelock` once the faucet is live
 
 // ============================================================================
 // PROGRAM
@@ -97,31 +21,20 @@ pub fn get_pyth_feed_id(asset_index: u8) -> [u8; 32] {
 pub mod cryptarena_faucet {
     use super::*;
 
-    /// Initialize the faucet with admin and test token mints
+    /// Initialize the faucet with its admin
     pub fn initialize(ctx: Context<Initialize>) -> Result<()> {
         let faucet_state = &mut ctx.accounts.faucet_state;
         faucet_state.admin = ctx.accounts.admin.key();
         faucet_state.is_active = true;
+        faucet_state.pending_admin = None;
+        faucet_state.settings_timelock = DEFAULT_SETTINGS_TIMELOCK;
+        faucet_state.pending_settings = None;
         faucet_state.bump = ctx.bumps.faucet_state;
         
         msg!("Faucet initialized");
         Ok(())
     }
 
-    /// Register a test token mint for an asset
-    pub fn register_token(
-        ctx: Context<RegisterToken>,
-        asset_index: u8,
-    ) -> Result<()> {
-        require!(asset_index < TOTAL_ASSETS as u8, FaucetError::InvalidAsset);
-
-        let faucet_state = &mut ctx.accounts.faucet_state;
-        faucet_state.token_mints[asset_index as usize] = ctx.accounts.token_mint.key();
-
-        msg!("Registered token mint for asset {}", asset_index);
-        Ok(())
-    }
-
     /// Claim test tokens from the faucet
     pub fn claim(
         ctx: Context<Claim>,
@@ -134,9 +47,6 @@ pub mod cryptarena_faucet {
         // Validate faucet is active
         require!(faucet_state.is_active, FaucetError::FaucetInactive);
 
-        // Validate asset index
-        require!(asset_index < TOTAL_ASSETS as u8, FaucetError::InvalidAsset);
-
         // Check cooldown for this specific asset
         let last_claim = user_faucet_state.last_claim_times[asset_index as usize];
         require!(
@@ -144,19 +54,15 @@ pub mod cryptarena_faucet {
             FaucetError::CooldownNotComplete
         );
 
-        // Get price from Pyth oracle
-        let price_update = &ctx.accounts.price_update;
-        let expected_feed_id = get_pyth_feed_id(asset_index);
-        let price = price_update.get_price_no_older_than(
-            &clock,
-            PYTH_PRICE_MAX_AGE,
-            &expected_feed_id,
-        )?;
+        // Get price from Pyth oracle, through the arena program's oracle checks
+        let asset_config = &ctx.accounts.asset_config;
+        let price = read_fresh_pyth_price(&ctx.accounts.price_update, asset_config, &clock)?;
 
         // Calculate token amount for $15 USD value
         let price_value = price.price as u64;
         let expo = price.exponent;
-        let token_amount = calculate_token_amount(FAUCET_USD_VALUE, price_value, expo)?;
+        let token_amount =
+            calculate_token_amount(FAUCET_USD_VALUE, price_value, expo, asset_config.decimals)?;
 
         // Mint tokens to user
         let seeds = &[
@@ -180,6 +86,18 @@ pub mod cryptarena_faucet {
         user_faucet_state.last_claim_times[asset_index as usize] = clock.unix_timestamp;
         user_faucet_state.total_claims[asset_index as usize] += 1;
 
+        emit_cpi!(TokensClaimed {
+            user: ctx.accounts.user.key(),
+            asset_index,
+            mint: ctx.accounts.token_mint.key(),
+            amount: token_amount,
+            usd_value: FAUCET_USD_VALUE,
+            price: price_value,
+            expo,
+            total_claims: user_faucet_state.total_claims[asset_index as usize],
+            claimed_at: clock.unix_timestamp,
+        });
+
         msg!(
             "User {} claimed {} tokens of asset {} (${} USD)",
             ctx.accounts.user.key(),
@@ -195,20 +113,98 @@ pub mod cryptarena_faucet {
     pub fn init_user_state(ctx: Context<InitUserState>) -> Result<()> {
         let user_faucet_state = &mut ctx.accounts.user_faucet_state;
         user_faucet_state.user = ctx.accounts.user.key();
-        user_faucet_state.last_claim_times = [0i64; TOTAL_ASSETS];
-        user_faucet_state.total_claims = [0u64; TOTAL_ASSETS];
+        user_faucet_state.last_claim_times = [0i64; MAX_ASSETS];
+        user_faucet_state.total_claims = [0u64; MAX_ASSETS];
         user_faucet_state.bump = ctx.bumps.user_faucet_state;
 
         msg!("User faucet state initialized for {}", ctx.accounts.user.key());
         Ok(())
     }
 
-    /// Admin: Pause or unpause the faucet
+    /// Admin: Queue pausing or unpausing the faucet
     pub fn set_active(ctx: Context<AdminAction>, is_active: bool) -> Result<()> {
+        queue_settings(&mut ctx.accounts.faucet_state, |pending, executable_at| {
+            pending.is_active = Some(Queued { value: is_active, executable_at });
+        })?;
+
+        msg!("Faucet active status change to {} queued", is_active);
+        Ok(())
+    }
+
+    /// Admin: Queue a change to the settings timelock itself
+    pub fn update_settings_timelock(ctx: Context<AdminAction>, settings_timelock: i64) -> Result<()> {
+        require!(settings_timelock >= 0, FaucetError::InvalidTimelock);
+        queue_settings(&mut ctx.accounts.faucet_state, |pending, executable_at| {
+            pending.settings_timelock = Some(Queued { value: settings_timelock, executable_at });
+        })?;
+
+        msg!("Settings timelock change to {} seconds queued", settings_timelock);
+        Ok(())
+    }
+
+    /// Admin: Apply the queued settings changes whose timelock has elapsed
+    pub fn execute_settings(ctx: Context<AdminAction>) -> Result<()> {
         let faucet_state = &mut ctx.accounts.faucet_state;
-        faucet_state.is_active = is_active;
-        
-        msg!("Faucet active status set to: {}", is_active);
+        let clock = Clock::get()?;
+
+        let mut pending = faucet_state
+            .pending_settings
+            .ok_or(FaucetError::NoPendingSettings)?;
+        let is_ready = |executable_at: i64| clock.unix_timestamp >= executable_at;
+        let is_active = pending.is_active.filter(|queued| is_ready(queued.executable_at));
+        let settings_timelock = pending
+            .settings_timelock
+            .filter(|queued| is_ready(queued.executable_at));
+        require!(
+            is_active.is_some() || settings_timelock.is_some(),
+            FaucetError::TimelockNotElapsed
+        );
+
+        if let Some(is_active) = is_active {
+            faucet_state.is_active = is_active.value;
+            pending.is_active = None;
+        }
+        if let Some(settings_timelock) = settings_timelock {
+            faucet_state.settings_timelock = settings_timelock.value;
+            pending.settings_timelock = None;
+        }
+        // Changes still inside their own timelock stay queued
+        faucet_state.pending_settings = (pending.is_active.is_some()
+            || pending.settings_timelock.is_some())
+        .then_some(pending);
+
+        msg!("Faucet settings updated");
+        Ok(())
+    }
+
+    /// Admin: Discard the queued settings changes
+    pub fn cancel_settings(ctx: Context<AdminAction>) -> Result<()> {
+        let faucet_state = &mut ctx.accounts.faucet_state;
+        require!(
+            faucet_state.pending_settings.is_some(),
+            FaucetError::NoPendingSettings
+        );
+        faucet_state.pending_settings = None;
+
+        msg!("Pending faucet settings changes canceled");
+        Ok(())
+    }
+
+    /// Admin: Nominate a new admin, who takes over by calling `accept_admin`
+    pub fn propose_admin(ctx: Context<AdminAction>, new_admin: Pubkey) -> Result<()> {
+        ctx.accounts.faucet_state.pending_admin = Some(new_admin);
+
+        msg!("Faucet admin transfer proposed to {}", new_admin);
+        Ok(())
+    }
+
+    /// Accept a proposed admin transfer (pending admin only)
+    pub fn accept_admin(ctx: Context<AcceptAdmin>) -> Result<()> {
+        let faucet_state = &mut ctx.accounts.faucet_state;
+        faucet_state.admin = ctx.accounts.new_admin.key();
+        faucet_state.pending_admin = None;
+
+        msg!("Faucet admin transferred to {}", faucet_state.admin);
         Ok(())
     }
 
@@ -220,7 +216,7 @@ pub mod cryptarena_faucet {
         symbol: String,
         decimals: u8,
     ) -> Result<()> {
-        require!(asset_index < TOTAL_ASSETS as u8, FaucetError::InvalidAsset);
+        require!(asset_index < MAX_ASSETS as u8, FaucetError::InvalidAsset);
         require!(name.len() <= 32, FaucetError::NameTooLong);
         require!(symbol.len() <= 10, FaucetError::SymbolTooLong);
 
@@ -232,9 +228,13 @@ pub mod cryptarena_faucet {
         token_metadata.decimals = decimals;
         token_metadata.bump = ctx.bumps.token_metadata;
 
-        // Register in faucet state
-        let faucet_state = &mut ctx.accounts.faucet_state;
-        faucet_state.token_mints[asset_index as usize] = ctx.accounts.token_mint.key();
+        emit_cpi!(TestTokenCreated {
+            asset_index,
+            mint: token_metadata.mint,
+            name: name.clone(),
+            symbol: symbol.clone(),
+            decimals,
+        });
 
         msg!("Created test token: {} ({}) for asset {}", name, symbol, asset_index);
         Ok(())
@@ -245,22 +245,31 @@ pub mod cryptarena_faucet {
 // HELPER FUNCTIONS
 // ============================================================================
 
-fn calculate_token_amount(usd_value: u64, price: u64, expo: i32) -> Result<u64> {
+/// Merge a change into the pending settings; only the field it queues gets the new timelock
+fn queue_settings(
+    faucet_state: &mut FaucetState,
+    change: impl FnOnce(&mut PendingSettings, i64),
+) -> Result<()> {
+    let executable_at = Clock::get()?.unix_timestamp + faucet_state.settings_timelock;
+    let pending = faucet_state.pending_settings.get_or_insert_with(PendingSettings::default);
+    change(pending, executable_at);
+    Ok(())
+}
+
+fn calculate_token_amount(usd_value: u64, price: u64, expo: i32, decimals: u8) -> Result<u64> {
     // Calculate how many tokens are worth $15 at current price
     let usd_u128 = usd_value as u128;
     let price_u128 = price as u128;
-    
-    // Adjust for exponent
-    let token_amount = if expo < 0 {
-        let multiplier = 10u128.pow((-expo) as u32);
-        (usd_u128 * multiplier) / price_u128
+
+    // usd / 10^6 / (price * 10^expo), expressed in base units of a `decimals` token
+    let scale = decimals as i32 - 6 - expo;
+    let token_amount = if scale < 0 {
+        usd_u128 / (price_u128 * 10u128.pow((-scale) as u32))
     } else {
-        let divisor = 10u128.pow(expo as u32);
-        usd_u128 / (price_u128 * divisor)
+        (usd_u128 * 10u128.pow(scale as u32)) / price_u128
     };
 
-    // Scale for token decimals (assuming 9 decimals like most SPL tokens)
-    Ok((token_amount * 1_000_000_000 / 1_000_000) as u64)
+    u64::try_from(token_amount).map_err(|_| error!(FaucetError::InvalidTokenAmount))
 }
 
 // ============================================================================
@@ -284,22 +293,7 @@ pub struct Initialize<'info> {
     pub system_program: Program<'info, System>,
 }
 
-#[derive(Accounts)]
-#[instruction(asset_index: u8)]
-pub struct RegisterToken<'info> {
-    #[account(
-        mut,
-        seeds = [b"faucet_state"],
-        bump = faucet_state.bump,
-        has_one = admin
-    )]
-    pub faucet_state: Account<'info, FaucetState>,
-
-    pub token_mint: Account<'info, Mint>,
-
-    pub admin: Signer<'info>,
-}
-
+#[event_cpi]
 #[derive(Accounts)]
 #[instruction(asset_index: u8)]
 pub struct Claim<'info> {
@@ -316,9 +310,17 @@ pub struct Claim<'info> {
     )]
     pub user_faucet_state: Account<'info, UserFaucetState>,
 
+    #[account(
+        seeds = [b"asset_config", asset_index.to_le_bytes().as_ref()],
+        bump = asset_config.bump,
+        seeds::program = cryptarena_svm::ID,
+        constraint = asset_config.enabled @ FaucetError::AssetDisabled
+    )]
+    pub asset_config: Account<'info, AssetConfig>,
+
     #[account(
         mut,
-        constraint = token_mint.key() == faucet_state.token_mints[asset_index as usize] @ FaucetError::InvalidTokenMint
+        constraint = token_mint.key() == asset_config.mint @ FaucetError::InvalidTokenMint
     )]
     pub token_mint: Account<'info, Mint>,
 
@@ -366,6 +368,20 @@ pub struct AdminAction<'info> {
     pub admin: Signer<'info>,
 }
 
+#[derive(Accounts)]
+pub struct AcceptAdmin<'info> {
+    #[account(
+        mut,
+        seeds = [b"faucet_state"],
+        bump = faucet_state.bump,
+        constraint = faucet_state.pending_admin == Some(new_admin.key()) @ FaucetError::Unauthorized
+    )]
+    pub faucet_state: Account<'info, FaucetState>,
+
+    pub new_admin: Signer<'info>,
+}
+
+#[event_cpi]
 #[derive(Accounts)]
 #[instruction(asset_index: u8)]
 pub struct CreateTestToken<'info> {
@@ -403,17 +419,28 @@ pub struct CreateTestToken<'info> {
 #[derive(InitSpace)]
 pub struct FaucetState {
     pub admin: Pubkey,
-    pub token_mints: [Pubkey; TOTAL_ASSETS],
     pub is_active: bool,
+    /// Admin nominated by `propose_admin`, waiting to accept
+    pub pending_admin: Option<Pubkey>,
+    /// Seconds queued settings changes wait before they can be executed
+    pub settings_timelock: i64,
+    pub pending_settings: Option<PendingSettings>,
     pub bump: u8,
 }
 
+/// Settings changes queued by the admin setters, each with its own timelock; `None` fields are left unchanged
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, InitSpace)]
+pub struct PendingSettings {
+    pub is_active: Option<Queued<bool>>,
+    pub settings_timelock: Option<Queued<i64>>,
+}
+
 #[account]
 #[derive(InitSpace)]
 pub struct UserFaucetState {
     pub user: Pubkey,
-    pub last_claim_times: [i64; TOTAL_ASSETS],
-    pub total_claims: [u64; TOTAL_ASSETS],
+    pub last_claim_times: [i64; MAX_ASSETS],
+    pub total_claims: [u64; MAX_ASSETS],
     pub bump: u8,
 }
 
@@ -430,6 +457,33 @@ pub struct TestTokenMetadata {
     pub bump: u8,
 }
 
+// ============================================================================
+// EVENTS
+// ============================================================================
+
+/// Emitted on every faucet claim; `usd_value` uses 6 decimals
+#[event]
+pub struct TokensClaimed {
+    pub user: Pubkey,
+    pub asset_index: u8,
+    pub mint: Pubkey,
+    pub amount: u64,
+    pub usd_value: u64,
+    pub price: u64,
+    pub expo: i32,
+    pub total_claims: u64,
+    pub claimed_at: i64,
+}
+
+#[event]
+pub struct TestTokenCreated {
+    pub asset_index: u8,
+    pub mint: Pubkey,
+    pub name: String,
+    pub symbol: String,
+    pub decimals: u8,
+}
+
 // ============================================================================
 // ERRORS
 // ============================================================================
@@ -450,4 +504,16 @@ pub enum FaucetError {
     NameTooLong,
     #[msg("Token symbol too long (max 10 chars)")]
     SymbolTooLong,
+    #[msg("Asset is not enabled in the registry")]
+    AssetDisabled,
+    #[msg("Token amount does not fit in u64")]
+    InvalidTokenAmount,
+    #[msg("Unauthorized")]
+    Unauthorized,
+    #[msg("Settings timelock cannot be negative")]
+    InvalidTimelock,
+    #[msg("No pending settings change")]
+    NoPendingSettings,
+    #[msg("Settings timelock has not elapsed")]
+    TimelockNotElapsed,
 }
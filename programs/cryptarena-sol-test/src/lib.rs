@@ -7,8 +7,8 @@ declare_id!("J1HvjpKh1tUQQFJ3Fm5ZM7h4GSrvivdWTwzQ3UALfT9T");
 // CONSTANTS
 // ============================================================================
 
-pub const TREASURY_FEE_BPS: u64 = 1000; // 10%
-pub const WINNER_SHARE_BPS: u64 = 9000; // 90%
+pub const DEFAULT_TREASURY_FEE_BPS: u64 = 1000; // 10%, waived for single-player arenas by default
+pub const MAX_FEE_OVERRIDES: usize = 4;
 pub const DEFAULT_ENTRY_FEE: u64 = 50_000_000; // 0.05 SOL in lamports
 pub const DEFAULT_ARENA_DURATION: i64 = 180; // 3 minutes for testing
 pub const MIN_ARENA_DURATION: i64 = 180; // 3 minutes minimum
@@ -39,6 +39,9 @@ pub mod cryptarena_sol_test {
         };
         global_state.entry_fee = if entry_fee > 0 { entry_fee } else { DEFAULT_ENTRY_FEE };
         global_state.current_arena_id = 0;
+        global_state.fee_schedule = FeeSchedule::flat(DEFAULT_TREASURY_FEE_BPS);
+        // Single-player arenas pay the winner the whole pool
+        global_state.fee_schedule.player_count_fees[0] = PlayerCountFee { player_count: 1, fee_bps: 0 };
         global_state.is_paused = false;
         global_state.bump = ctx.bumps.global_state;
         
@@ -111,6 +114,26 @@ pub mod cryptarena_sol_test {
         Ok(())
     }
 
+    /// Update the treasury fee schedule (admin only)
+    /// Only arenas created afterwards use it; running arenas keep their snapshot.
+    pub fn update_fee_schedule(
+        ctx: Context<AdminOnly>,
+        fee_schedule: FeeSchedule,
+    ) -> Result<()> {
+        require!(
+            ctx.accounts.admin.key() == ctx.accounts.global_state.admin,
+            CryptarenaError::Unauthorized
+        );
+        require!(
+            fee_schedule.is_valid(),
+            CryptarenaError::InvalidFeeSchedule
+        );
+        ctx.accounts.global_state.fee_schedule = fee_schedule;
+        msg!("Fee schedule updated: {} bps base, {} bps cap",
+            fee_schedule.base_fee_bps, fee_schedule.max_fee_bps);
+        Ok(())
+    }
+
     /// Add token to whitelist (admin only)
     /// chain_type: 0 = Solana, 1 = EVM (Ethereum)
     /// token_address: 32 bytes for Solana, 20 bytes (left-padded to 32) for EVM
@@ -139,6 +162,14 @@ pub mod cryptarena_sol_test {
         whitelisted_token.symbol = symbol;
         whitelisted_token.is_active = true;
         whitelisted_token.bump = ctx.bumps.whitelisted_token;
+
+        emit_cpi!(TokenWhitelistUpdated {
+            asset_index,
+            chain_type,
+            token_address,
+            symbol,
+            is_active: true,
+        });
         
         msg!("Token whitelisted at index {} | Chain: {} | Symbol: {:?}", 
             asset_index, chain_type, symbol);
@@ -155,7 +186,15 @@ pub mod cryptarena_sol_test {
             CryptarenaError::Unauthorized
         );
         
-        ctx.accounts.whitelisted_token.is_active = false;
+        let whitelisted_token = &mut ctx.accounts.whitelisted_token;
+        whitelisted_token.is_active = false;
+        emit_cpi!(TokenWhitelistUpdated {
+            asset_index,
+            chain_type: whitelisted_token.chain_type,
+            token_address: whitelisted_token.token_address,
+            symbol: whitelisted_token.symbol,
+            is_active: false,
+        });
         msg!("Token at index {} removed from whitelist", asset_index);
         Ok(())
     }
@@ -190,10 +229,20 @@ pub mod cryptarena_sol_test {
             arena.end_timestamp = 0;
             arena.token_slots = [255u8; 10];
             arena.player_addresses = [Pubkey::default(); 10];
+            arena.rent_payer = ctx.accounts.player.key();
+            arena.open_entries = 0;
+            arena.outstanding_claims = 0;
+            arena.fee_schedule = global_state.fee_schedule.snapshot(clock.unix_timestamp);
+            arena.treasury_fee_bps = 0;
             
             // Initialize arena vault
             arena_vault.arena_id = global_state.current_arena_id;
             arena_vault.bump = ctx.bumps.arena_vault;
+
+            emit_cpi!(ArenaCreated {
+                arena: arena.key(),
+                arena_id: arena.id,
+            });
             
             msg!("New arena {} created", arena.id);
         }
@@ -258,8 +307,20 @@ pub mod cryptarena_sol_test {
         arena.token_slots[player_idx] = asset_index;
         arena.player_addresses[player_idx] = ctx.accounts.player.key();
         arena.player_count += 1;
+        arena.open_entries += 1;
         arena.total_pool += entry_fee;
 
+        emit_cpi!(PlayerEntered {
+            arena: arena.key(),
+            arena_id: arena.id,
+            player: player_entry.player,
+            asset_index,
+            player_index: player_entry.player_index,
+            entry_fee,
+            player_count: arena.player_count,
+            total_pool: arena.total_pool,
+        });
+
         msg!("Player {} entered arena {} | Token: {} | Entry Fee: {} | Players: {}/{}", 
             ctx.accounts.player.key(), arena.id, asset_index, entry_fee,
             arena.player_count, MAX_PLAYERS_PER_ARENA);
@@ -302,6 +363,15 @@ pub mod cryptarena_sol_test {
         // Increment arena ID so next player entering creates a new arena
         global_state.current_arena_id += 1;
 
+        emit_cpi!(ArenaStarted {
+            arena: arena.key(),
+            arena_id: arena.id,
+            player_count: arena.player_count,
+            total_pool: arena.total_pool,
+            start_timestamp: arena.start_timestamp,
+            end_timestamp: arena.end_timestamp,
+        });
+
         msg!("Arena {} STARTED! {} players | Ends at {}", 
             arena.id, arena.player_count, arena.end_timestamp);
 
@@ -329,6 +399,16 @@ pub mod cryptarena_sol_test {
 
         player_entry.start_price = price;
 
+        emit_cpi!(PriceSet {
+            arena: arena.key(),
+            arena_id: arena.id,
+            player: player_entry.player,
+            asset_index: player_entry.asset_index,
+            price,
+            is_start: true,
+            price_movement: 0,
+        });
+
         msg!("Player {} token {} start price: {}", 
             player_entry.player_index, player_entry.asset_index, price);
 
@@ -374,6 +454,16 @@ pub mod cryptarena_sol_test {
             player_entry.price_movement = movement as i64;
         }
 
+        emit_cpi!(PriceSet {
+            arena: arena.key(),
+            arena_id: arena.id,
+            player: player_entry.player,
+            asset_index: player_entry.asset_index,
+            price,
+            is_start: false,
+            price_movement: player_entry.price_movement,
+        });
+
         msg!("Player {} token {} end price: {} | Movement: {} (12 decimals)", 
             player_entry.player_index, player_entry.asset_index, 
             price, player_entry.price_movement);
@@ -465,21 +555,45 @@ pub mod cryptarena_sol_test {
             // Cancel arena - users can claim back their SOL
             arena.is_canceled = true;
             arena.status = ArenaStatus::Canceled as u8;
+            arena.outstanding_claims = arena.player_count;
+            emit_cpi!(ArenaEnded {
+                arena: arena.key(),
+                arena_id: arena.id,
+                status: arena.status,
+                winning_asset: 255,
+                winning_movement: best_movement,
+                total_pool: arena.total_pool,
+                treasury_fee_bps: arena.treasury_fee_bps,
+            });
             msg!("Arena {} CANCELED due to tie! Users can claim refunds.", arena.id);
             return Ok(());
         }
 
         arena.winning_asset = winning_asset;
         arena.status = ArenaStatus::Ended as u8;
+        // The fee rate comes from the arena's own schedule now that the player count is final
+        arena.treasury_fee_bps = arena.fee_schedule.fee_bps(arena.player_count);
+        // Tokens are unique per arena, so there is one winner; the treasury is owed a claim if it has a fee
+        arena.outstanding_claims = if arena.treasury_fee() > 0 { 2 } else { 1 };
+
+        emit_cpi!(ArenaEnded {
+            arena: arena.key(),
+            arena_id: arena.id,
+            status: arena.status,
+            winning_asset,
+            winning_movement: best_movement,
+            total_pool: arena.total_pool,
+            treasury_fee_bps: arena.treasury_fee_bps,
+        });
 
         msg!("Arena {} ENDED! Winner token: {} with {} (8 decimals)", 
             arena.id, winning_asset, best_movement);
         Ok(())
     }
 
-    /// Winner claims rewards (100% for single-player arenas, 90% for multi-player arenas)
+    /// Winner claims the pool less the treasury fee set by the arena's fee schedule
     pub fn claim_winner_rewards(ctx: Context<ClaimWinnerRewards>) -> Result<()> {
-        let arena = &ctx.accounts.arena;
+        let arena = &mut ctx.accounts.arena;
         let player_entry = &mut ctx.accounts.player_entry;
 
         require!(
@@ -497,15 +611,8 @@ pub mod cryptarena_sol_test {
             CryptarenaError::RewardAlreadyClaimed
         );
 
-        // Calculate winner's reward
-        // If only 1 player, winner gets 100% of pool. Otherwise, 90% (normal split)
-        let total_pool = arena.total_pool;
-        let winner_share_bps = if arena.player_count == 1 {
-            10000 // 100% for single player arenas
-        } else {
-            WINNER_SHARE_BPS // 90% for multi-player arenas
-        };
-        let winner_reward = (total_pool * winner_share_bps) / 10000;
+        // Calculate winner's reward: everything but the treasury fee
+        let winner_reward = arena.total_pool - arena.treasury_fee();
 
         // Transfer SOL from arena vault to winner
         **ctx.accounts.arena_vault.to_account_info().try_borrow_mut_lamports()? -= winner_reward;
@@ -513,16 +620,22 @@ pub mod cryptarena_sol_test {
 
         player_entry.is_winner = true;
         player_entry.has_claimed = true;
-
-        if arena.player_count == 1 {
-            msg!("Single player winner claimed {} lamports ({} SOL) - 100% of pool", winner_reward, winner_reward as f64 / 1_000_000_000.0);
-        } else {
-            msg!("Winner claimed {} lamports ({} SOL) - 90% of pool", winner_reward, winner_reward as f64 / 1_000_000_000.0);
-        }
+        arena.outstanding_claims -= 1;
+
+        emit_cpi!(WinnerRewardClaimed {
+            arena: arena.key(),
+            arena_id: arena.id,
+            player: player_entry.player,
+            asset_index: player_entry.asset_index,
+            amount: winner_reward,
+        });
+
+        msg!("Winner claimed {} lamports ({} SOL) - {} bps treasury fee",
+            winner_reward, winner_reward as f64 / 1_000_000_000.0, arena.treasury_fee_bps);
         Ok(())
     }
 
-    /// Admin claims treasury fee (10% of total pool, 0% for single-player arenas)
+    /// Admin claims the treasury fee set by the arena's fee schedule
     pub fn claim_treasury_fee(ctx: Context<ClaimTreasuryFee>) -> Result<()> {
         require!(
             ctx.accounts.admin.key() == ctx.accounts.global_state.admin,
@@ -541,33 +654,33 @@ pub mod cryptarena_sol_test {
             CryptarenaError::TreasuryFeeAlreadyClaimed
         );
 
-        // Calculate treasury fee
-        // For single-player arenas, treasury gets 0% (winner gets 100%)
-        // For multi-player arenas, treasury gets 10%
-        let total_pool = arena.total_pool;
-        let treasury_fee = if arena.player_count == 1 {
-            0 // No treasury fee for single-player arenas
-        } else {
-            (total_pool * TREASURY_FEE_BPS) / 10000
-        };
+        let treasury_fee = arena.treasury_fee();
 
         // Only transfer if there's a fee to claim
         if treasury_fee > 0 {
             // Transfer SOL from arena vault to treasury
             **ctx.accounts.arena_vault.to_account_info().try_borrow_mut_lamports()? -= treasury_fee;
             **ctx.accounts.treasury_wallet.to_account_info().try_borrow_mut_lamports()? += treasury_fee;
+            arena.outstanding_claims -= 1;
             msg!("Treasury claimed {} lamports ({} SOL)", treasury_fee, treasury_fee as f64 / 1_000_000_000.0);
         } else {
-            msg!("Treasury fee is 0 for single-player arena - nothing to claim");
+            msg!("Treasury fee is 0 for this arena - nothing to claim");
         }
 
         arena.treasury_claimed = true;
+
+        emit_cpi!(TreasuryFeeClaimed {
+            arena: arena.key(),
+            arena_id: arena.id,
+            treasury_wallet: ctx.accounts.treasury_wallet.key(),
+            amount: treasury_fee,
+        });
         Ok(())
     }
 
     /// Claim refund for canceled arena (tie scenario)
     pub fn claim_refund(ctx: Context<ClaimRefund>) -> Result<()> {
-        let arena = &ctx.accounts.arena;
+        let arena = &mut ctx.accounts.arena;
         let player_entry = &mut ctx.accounts.player_entry;
 
         require!(
@@ -593,10 +706,82 @@ pub mod cryptarena_sol_test {
         **ctx.accounts.player.to_account_info().try_borrow_mut_lamports()? += refund_amount;
 
         player_entry.has_claimed = true;
+        arena.outstanding_claims -= 1;
+
+        emit_cpi!(RefundClaimed {
+            arena: arena.key(),
+            arena_id: arena.id,
+            player: player_entry.player,
+            asset_index: player_entry.asset_index,
+            amount: refund_amount,
+        });
 
         msg!("Player refunded {} lamports ({} SOL)", refund_amount, refund_amount as f64 / 1_000_000_000.0);
         Ok(())
     }
+
+    /// Close a settled player entry and return its rent to the player (permissionless)
+    /// The loser of an ended arena can close right away; everyone else once they have claimed.
+    pub fn close_player_entry(ctx: Context<ClosePlayerEntry>) -> Result<()> {
+        let arena = &mut ctx.accounts.arena;
+        let player_entry = &ctx.accounts.player_entry;
+
+        require!(
+            arena.is_finished(),
+            CryptarenaError::ArenaNotFinished
+        );
+
+        let settled = player_entry.has_claimed
+            || (arena.status == ArenaStatus::Ended as u8
+                && player_entry.asset_index != arena.winning_asset);
+        require!(
+            settled,
+            CryptarenaError::EntryNotSettled
+        );
+
+        arena.open_entries -= 1;
+
+        emit_cpi!(PlayerEntryClosed {
+            arena: arena.key(),
+            arena_id: arena.id,
+            player: player_entry.player,
+        });
+
+        msg!("Closed entry of player {} in arena {} | Open entries: {}",
+            player_entry.player, arena.id, arena.open_entries);
+        Ok(())
+    }
+
+    /// Close a finished arena and its vault once every claim is paid and all entries are closed
+    /// Permissionless so a crank can sweep finished arenas; rent (and any rounding dust left in
+    /// the vault) always goes to the arena's rent payer.
+    pub fn close_arena(ctx: Context<CloseArena>) -> Result<()> {
+        let arena = &ctx.accounts.arena;
+
+        require!(
+            arena.is_finished(),
+            CryptarenaError::ArenaNotFinished
+        );
+        require!(
+            arena.outstanding_claims == 0,
+            CryptarenaError::OutstandingClaims
+        );
+        require!(
+            arena.open_entries == 0,
+            CryptarenaError::EntriesStillOpen
+        );
+
+        emit_cpi!(ArenaClosed {
+            arena: arena.key(),
+            arena_id: arena.id,
+            status: arena.status,
+            rent_payer: arena.rent_payer,
+            vault_lamports: ctx.accounts.arena_vault.to_account_info().lamports(),
+        });
+
+        msg!("Closed arena {} | Rent returned to {}", arena.id, arena.rent_payer);
+        Ok(())
+    }
 }
 
 // ============================================================================
@@ -631,6 +816,7 @@ pub struct AdminOnly<'info> {
     pub admin: Signer<'info>,
 }
 
+#[event_cpi]
 #[derive(Accounts)]
 #[instruction(asset_index: u8)]
 pub struct AddWhitelistedToken<'info> {
@@ -652,6 +838,7 @@ pub struct AddWhitelistedToken<'info> {
     pub system_program: Program<'info, System>,
 }
 
+#[event_cpi]
 #[derive(Accounts)]
 #[instruction(asset_index: u8)]
 pub struct RemoveWhitelistedToken<'info> {
@@ -668,6 +855,7 @@ pub struct RemoveWhitelistedToken<'info> {
     pub admin: Signer<'info>,
 }
 
+#[event_cpi]
 #[derive(Accounts)]
 #[instruction(asset_index: u8)]
 pub struct EnterArena<'info> {
@@ -714,6 +902,7 @@ pub struct EnterArena<'info> {
     pub system_program: Program<'info, System>,
 }
 
+#[event_cpi]
 #[derive(Accounts)]
 pub struct StartArena<'info> {
     #[account(mut, seeds = [b"global_state"], bump = global_state.bump)]
@@ -729,6 +918,7 @@ pub struct StartArena<'info> {
     pub admin: Signer<'info>,
 }
 
+#[event_cpi]
 #[derive(Accounts)]
 pub struct SetPlayerPrice<'info> {
     #[account(seeds = [b"global_state"], bump = global_state.bump)]
@@ -750,6 +940,7 @@ pub struct SetPlayerPrice<'info> {
     pub admin: Signer<'info>,
 }
 
+#[event_cpi]
 #[derive(Accounts)]
 pub struct EndArena<'info> {
     #[account(seeds = [b"global_state"], bump = global_state.bump)]
@@ -766,9 +957,11 @@ pub struct EndArena<'info> {
     // PlayerEntry accounts passed as remaining_accounts
 }
 
+#[event_cpi]
 #[derive(Accounts)]
 pub struct ClaimWinnerRewards<'info> {
     #[account(
+        mut,
         seeds = [b"arena", arena.id.to_le_bytes().as_ref()],
         bump = arena.bump
     )]
@@ -794,6 +987,7 @@ pub struct ClaimWinnerRewards<'info> {
     pub system_program: Program<'info, System>,
 }
 
+#[event_cpi]
 #[derive(Accounts)]
 pub struct ClaimTreasuryFee<'info> {
     #[account(seeds = [b"global_state"], bump = global_state.bump)]
@@ -826,9 +1020,11 @@ pub struct ClaimTreasuryFee<'info> {
     pub system_program: Program<'info, System>,
 }
 
+#[event_cpi]
 #[derive(Accounts)]
 pub struct ClaimRefund<'info> {
     #[account(
+        mut,
         seeds = [b"arena", arena.id.to_le_bytes().as_ref()],
         bump = arena.bump
     )]
@@ -854,6 +1050,61 @@ pub struct ClaimRefund<'info> {
     pub system_program: Program<'info, System>,
 }
 
+#[event_cpi]
+#[derive(Accounts)]
+pub struct ClosePlayerEntry<'info> {
+    #[account(
+        mut,
+        seeds = [b"arena", arena.id.to_le_bytes().as_ref()],
+        bump = arena.bump
+    )]
+    pub arena: Account<'info, Arena>,
+
+    #[account(
+        mut,
+        close = player,
+        seeds = [b"player_entry", arena.key().as_ref(), player.key().as_ref()],
+        bump = player_entry.bump,
+        has_one = player
+    )]
+    pub player_entry: Account<'info, PlayerEntry>,
+
+    /// CHECK: The entry's player, who paid its rent
+    #[account(mut)]
+    pub player: UncheckedAccount<'info>,
+
+    /// CHECK: Anyone can close settled entries; rent always goes to the player
+    pub caller: Signer<'info>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct CloseArena<'info> {
+    #[account(
+        mut,
+        close = rent_payer,
+        seeds = [b"arena", arena.id.to_le_bytes().as_ref()],
+        bump = arena.bump,
+        has_one = rent_payer
+    )]
+    pub arena: Account<'info, Arena>,
+
+    #[account(
+        mut,
+        close = rent_payer,
+        seeds = [b"arena_vault", arena.id.to_le_bytes().as_ref()],
+        bump = arena_vault.bump
+    )]
+    pub arena_vault: Account<'info, ArenaVault>,
+
+    /// CHECK: Paid the arena and vault rent
+    #[account(mut)]
+    pub rent_payer: UncheckedAccount<'info>,
+
+    /// CHECK: Anyone can close finished arenas
+    pub caller: Signer<'info>,
+}
+
 // ============================================================================
 // STATE
 // ============================================================================
@@ -866,10 +1117,67 @@ pub struct GlobalState {
     pub arena_duration: i64,
     pub entry_fee: u64,
     pub current_arena_id: u64,
+    pub fee_schedule: FeeSchedule, // Treasury fee rates new arenas snapshot when they are created
     pub is_paused: bool,
     pub bump: u8,
 }
 
+/// FeeSchedule - Treasury fee rates, snapshotted by each arena at creation
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, InitSpace)]
+pub struct FeeSchedule {
+    pub base_fee_bps: u64, // Fee when no override matches the player count
+    pub player_count_fees: [PlayerCountFee; MAX_FEE_OVERRIDES], // Zero player count = unused slot
+    pub max_fee_bps: u64, // Ceiling on the fee whatever the base rate or overrides say
+    pub promo_start: i64, // Arenas created within [promo_start, promo_end) charge no fee
+    pub promo_end: i64,
+}
+
+/// PlayerCountFee - Fee override for arenas that end with exactly `player_count` players
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, InitSpace)]
+pub struct PlayerCountFee {
+    pub player_count: u8,
+    pub fee_bps: u64,
+}
+
+impl FeeSchedule {
+    /// Same fee for every arena, with no overrides or promotion
+    pub fn flat(fee_bps: u64) -> Self {
+        Self {
+            base_fee_bps: fee_bps,
+            max_fee_bps: fee_bps,
+            ..Default::default()
+        }
+    }
+
+    /// Copy stored on an arena created at `now`; a running promotion caps its fee at zero
+    pub fn snapshot(&self, now: i64) -> Self {
+        let mut snapshot = *self;
+        if (self.promo_start..self.promo_end).contains(&now) {
+            snapshot.max_fee_bps = 0;
+        }
+        snapshot
+    }
+
+    /// Fee in bps for an arena ending with `player_count` players
+    pub fn fee_bps(&self, player_count: u8) -> u64 {
+        self.player_count_fees
+            .iter()
+            .find(|fee| fee.player_count != 0 && fee.player_count == player_count)
+            .map_or(self.base_fee_bps, |fee| fee.fee_bps)
+            .min(self.max_fee_bps)
+    }
+
+    /// Rates fit in a pool, overrides name reachable player counts and the promotion window is ordered
+    pub fn is_valid(&self) -> bool {
+        self.max_fee_bps <= 10000
+            && self.promo_start <= self.promo_end
+            && self
+                .player_count_fees
+                .iter()
+                .all(|fee| fee.player_count <= MAX_PLAYERS_PER_ARENA)
+    }
+}
+
 /// ArenaVault - PDA that holds SOL for an arena
 #[account]
 #[derive(InitSpace)]
@@ -899,6 +1207,28 @@ pub struct Arena {
     // Player addresses in order
     #[max_len(10)]
     pub player_addresses: [Pubkey; 10],
+    // First entrant, paid the arena and vault rent
+    pub rent_payer: Pubkey,
+    // Player entries not yet closed
+    pub open_entries: u8,
+    // Claims and refunds still owed; the arena can only close at zero
+    pub outstanding_claims: u8,
+    // Global fee schedule as it stood when the arena was created; later changes never apply
+    pub fee_schedule: FeeSchedule,
+    // Treasury fee rate resolved from the schedule once the arena ends, in bps
+    pub treasury_fee_bps: u64,
+}
+
+impl Arena {
+    /// Ended and canceled arenas only pay out what they owe
+    pub fn is_finished(&self) -> bool {
+        self.status == ArenaStatus::Ended as u8 || self.status == ArenaStatus::Canceled as u8
+    }
+
+    /// Lamports owed to the treasury once the arena has ended
+    pub fn treasury_fee(&self) -> u64 {
+        (self.total_pool * self.treasury_fee_bps) / 10000
+    }
 }
 
 /// PlayerEntry - One per player in an arena
@@ -934,6 +1264,115 @@ pub struct WhitelistedToken {
     pub symbol: [u8; 10],         // Token symbol (e.g., "PYTH", "ETH")
 }
 
+// ============================================================================
+// EVENTS
+// ============================================================================
+
+/// Emitted when a token is whitelisted or removed from the whitelist
+#[event]
+pub struct TokenWhitelistUpdated {
+    pub asset_index: u8,
+    pub chain_type: u8,
+    pub token_address: [u8; 32],
+    pub symbol: [u8; 10],
+    pub is_active: bool,
+}
+
+#[event]
+pub struct ArenaCreated {
+    pub arena: Pubkey,
+    pub arena_id: u64,
+}
+
+#[event]
+pub struct PlayerEntered {
+    pub arena: Pubkey,
+    pub arena_id: u64,
+    pub player: Pubkey,
+    pub asset_index: u8,
+    pub player_index: u8,
+    pub entry_fee: u64,
+    pub player_count: u8,
+    pub total_pool: u64,
+}
+
+#[event]
+pub struct ArenaStarted {
+    pub arena: Pubkey,
+    pub arena_id: u64,
+    pub player_count: u8,
+    pub total_pool: u64,
+    pub start_timestamp: i64,
+    pub end_timestamp: i64,
+}
+
+/// Emitted by `set_start_price` and `set_end_price`; movement is only meaningful for end prices
+#[event]
+pub struct PriceSet {
+    pub arena: Pubkey,
+    pub arena_id: u64,
+    pub player: Pubkey,
+    pub asset_index: u8,
+    pub price: u64,
+    pub is_start: bool,
+    pub price_movement: i64,
+}
+
+/// Emitted by `end_arena`; a tie leaves the arena `Canceled` with no winner
+#[event]
+pub struct ArenaEnded {
+    pub arena: Pubkey,
+    pub arena_id: u64,
+    pub status: u8,
+    pub winning_asset: u8,
+    pub winning_movement: i64,
+    pub total_pool: u64,
+    pub treasury_fee_bps: u64, // Rate resolved from the arena's fee schedule; 0 if canceled
+}
+
+#[event]
+pub struct WinnerRewardClaimed {
+    pub arena: Pubkey,
+    pub arena_id: u64,
+    pub player: Pubkey,
+    pub asset_index: u8,
+    pub amount: u64,
+}
+
+#[event]
+pub struct TreasuryFeeClaimed {
+    pub arena: Pubkey,
+    pub arena_id: u64,
+    pub treasury_wallet: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct RefundClaimed {
+    pub arena: Pubkey,
+    pub arena_id: u64,
+    pub player: Pubkey,
+    pub asset_index: u8,
+    pub amount: u64,
+}
+
+#[event]
+pub struct PlayerEntryClosed {
+    pub arena: Pubkey,
+    pub arena_id: u64,
+    pub player: Pubkey,
+}
+
+/// Emitted by `close_arena`; `vault_lamports` (rent plus any dust) goes to the rent payer
+#[event]
+pub struct ArenaClosed {
+    pub arena: Pubkey,
+    pub arena_id: u64,
+    pub status: u8,
+    pub rent_payer: Pubkey,
+    pub vault_lamports: u64,
+}
+
 // ============================================================================
 // ENUMS & ERRORS
 // ============================================================================
@@ -989,5 +1428,15 @@ pub enum CryptarenaError {
     InvalidTreasury,
     #[msg("Invalid chain type - must be 0 (Solana) or 1 (EVM)")]
     InvalidChainType,
+    #[msg("Arena has not finished")]
+    ArenaNotFinished,
+    #[msg("Arena still has outstanding claims")]
+    OutstandingClaims,
+    #[msg("Player entry still has an unclaimed reward or refund")]
+    EntryNotSettled,
+    #[msg("Arena still has open player entries")]
+    EntriesStillOpen,
+    #[msg("Fee schedule is invalid")]
+    InvalidFeeSchedule,
 }
 
@@ -1,5 +1,6 @@
 use anchor_lang::prelude::*;
 use anchor_lang::system_program::{transfer, Transfer};
+use solana_sha256_hasher::hash;
 
 declare_id!("GX4gVWUtVgq6XxL8oHYy6psoN9KFdJhwnds2T3NHe5na");
 
@@ -7,13 +8,18 @@ declare_id!("GX4gVWUtVgq6XxL8oHYy6psoN9KFdJhwnds2T3NHe5na");
 // CONSTANTS
 // ============================================================================
 
-pub const TREASURY_FEE_BPS: u64 = 1000; // 10%
-pub const WINNER_SHARE_BPS: u64 = 9000; // 90%
+pub const DEFAULT_TREASURY_FEE_BPS: u64 = 1000; // 10%, waived for single-player arenas by default
+pub const MAX_FEE_OVERRIDES: usize = 4;
+pub const DEFAULT_REFERRAL_SHARE_BPS: u64 = 2000; // 20% of a referred entry's treasury fee
 pub const DEFAULT_ENTRY_FEE: u64 = 50_000_000; // 0.05 SOL in lamports
 pub const DEFAULT_ARENA_DURATION: i64 = 180; // 3 minutes for testing
 pub const MIN_ARENA_DURATION: i64 = 180; // 3 minutes minimum
 pub const MIN_PLAYERS_PER_ARENA: u8 = 1;
 pub const MAX_PLAYERS_PER_ARENA: u8 = 10;
+pub const MAX_ASSET_INDICES: usize = 256; // Whitelist slots are keyed by a u8 asset index
+pub const PRIVATE_ARENA_ID_OFFSET: u64 = 1 << 63; // Private arena ids never collide with the public sequence
+pub const MAX_ALLOWLIST_SIZE: usize = 32;
+pub const DEFAULT_SETTINGS_TIMELOCK: i64 = 0; // Raise with `update_settings_timelock` once the protocol is live
 
 // ============================================================================
 // PROGRAM
@@ -39,6 +45,14 @@ pub mod cryptarena_sol {
         };
         global_state.entry_fee = if entry_fee > 0 { entry_fee } else { DEFAULT_ENTRY_FEE };
         global_state.current_arena_id = 0;
+        global_state.private_arena_count = 0;
+        global_state.referral_share_bps = DEFAULT_REFERRAL_SHARE_BPS;
+        global_state.fee_schedule = FeeSchedule::flat(DEFAULT_TREASURY_FEE_BPS);
+        // Single-player arenas pay the winner the whole pool
+        global_state.fee_schedule.player_count_fees[0] = PlayerCountFee { player_count: 1, fee_bps: 0 };
+        global_state.pending_admin = None;
+        global_state.settings_timelock = DEFAULT_SETTINGS_TIMELOCK;
+        global_state.pending_settings = None;
         global_state.is_paused = false;
         global_state.bump = ctx.bumps.global_state;
         
@@ -47,7 +61,7 @@ pub mod cryptarena_sol {
         Ok(())
     }
 
-    /// Update treasury wallet (admin only)
+    /// Queue a treasury wallet change (admin only)
     pub fn update_treasury_wallet(
         ctx: Context<AdminOnly>,
         new_treasury: Pubkey,
@@ -56,12 +70,14 @@ pub mod cryptarena_sol {
             ctx.accounts.admin.key() == ctx.accounts.global_state.admin,
             CryptarenaError::Unauthorized
         );
-        ctx.accounts.global_state.treasury_wallet = new_treasury;
-        msg!("Treasury updated to {}", new_treasury);
+        queue_settings(&mut ctx.accounts.global_state, |pending, executable_at| {
+            pending.treasury_wallet = Some(Queued { value: new_treasury, executable_at });
+        })?;
+        msg!("Treasury change to {} queued", new_treasury);
         Ok(())
     }
 
-    /// Update arena duration (admin only) - minimum 10 minutes
+    /// Queue an arena duration change (admin only) - minimum 3 minutes
     pub fn update_arena_duration(
         ctx: Context<AdminOnly>,
         arena_duration: i64,
@@ -74,12 +90,14 @@ pub mod cryptarena_sol {
             arena_duration >= MIN_ARENA_DURATION,
             CryptarenaError::InvalidDuration
         );
-        ctx.accounts.global_state.arena_duration = arena_duration;
-        msg!("Arena duration updated to {} seconds", arena_duration);
+        queue_settings(&mut ctx.accounts.global_state, |pending, executable_at| {
+            pending.arena_duration = Some(Queued { value: arena_duration, executable_at });
+        })?;
+        msg!("Arena duration change to {} seconds queued", arena_duration);
         Ok(())
     }
 
-    /// Update entry fee (admin only)
+    /// Queue an entry fee change (admin only)
     pub fn update_entry_fee(
         ctx: Context<AdminOnly>,
         entry_fee: u64,
@@ -92,12 +110,14 @@ pub mod cryptarena_sol {
             entry_fee > 0,
             CryptarenaError::InvalidEntryFee
         );
-        ctx.accounts.global_state.entry_fee = entry_fee;
-        msg!("Entry fee updated to {} lamports ({} SOL)", entry_fee, entry_fee as f64 / 1_000_000_000.0);
+        queue_settings(&mut ctx.accounts.global_state, |pending, executable_at| {
+            pending.entry_fee = Some(Queued { value: entry_fee, executable_at });
+        })?;
+        msg!("Entry fee change to {} lamports ({} SOL) queued", entry_fee, entry_fee as f64 / 1_000_000_000.0);
         Ok(())
     }
 
-    /// Pause/unpause the protocol (admin only)
+    /// Queue pausing/unpausing the protocol (admin only)
     pub fn set_paused(
         ctx: Context<AdminOnly>,
         paused: bool,
@@ -106,8 +126,157 @@ pub mod cryptarena_sol {
             ctx.accounts.admin.key() == ctx.accounts.global_state.admin,
             CryptarenaError::Unauthorized
         );
-        ctx.accounts.global_state.is_paused = paused;
-        msg!("Protocol paused: {}", paused);
+        queue_settings(&mut ctx.accounts.global_state, |pending, executable_at| {
+            pending.is_paused = Some(Queued { value: paused, executable_at });
+        })?;
+        msg!("Protocol paused: {} queued", paused);
+        Ok(())
+    }
+
+    /// Queue a change to the referrers' share of the treasury fee (admin only)
+    pub fn update_referral_share(
+        ctx: Context<AdminOnly>,
+        referral_share_bps: u64,
+    ) -> Result<()> {
+        require!(
+            ctx.accounts.admin.key() == ctx.accounts.global_state.admin,
+            CryptarenaError::Unauthorized
+        );
+        require!(
+            referral_share_bps <= 10000,
+            CryptarenaError::InvalidReferralShare
+        );
+        queue_settings(&mut ctx.accounts.global_state, |pending, executable_at| {
+            pending.referral_share_bps = Some(Queued { value: referral_share_bps, executable_at });
+        })?;
+        msg!("Referral share change to {} bps queued", referral_share_bps);
+        Ok(())
+    }
+
+    /// Queue a new treasury fee schedule (admin only)
+    /// Only arenas created after it takes effect use it; running arenas keep their snapshot.
+    pub fn update_fee_schedule(
+        ctx: Context<AdminOnly>,
+        fee_schedule: FeeSchedule,
+    ) -> Result<()> {
+        require!(
+            ctx.accounts.admin.key() == ctx.accounts.global_state.admin,
+            CryptarenaError::Unauthorized
+        );
+        require!(
+            fee_schedule.is_valid(),
+            CryptarenaError::InvalidFeeSchedule
+        );
+        queue_settings(&mut ctx.accounts.global_state, |pending, executable_at| {
+            pending.fee_schedule = Some(Queued { value: fee_schedule, executable_at });
+        })?;
+        msg!("Fee schedule change to {} bps base, {} bps cap queued",
+            fee_schedule.base_fee_bps, fee_schedule.max_fee_bps);
+        Ok(())
+    }
+
+    /// Queue a change to the settings timelock itself (admin only)
+    pub fn update_settings_timelock(
+        ctx: Context<AdminOnly>,
+        settings_timelock: i64,
+    ) -> Result<()> {
+        require!(
+            ctx.accounts.admin.key() == ctx.accounts.global_state.admin,
+            CryptarenaError::Unauthorized
+        );
+        require!(
+            settings_timelock >= 0,
+            CryptarenaError::InvalidTimelock
+        );
+        queue_settings(&mut ctx.accounts.global_state, |pending, executable_at| {
+            pending.settings_timelock = Some(Queued { value: settings_timelock, executable_at });
+        })?;
+        msg!("Settings timelock change to {} seconds queued", settings_timelock);
+        Ok(())
+    }
+
+    /// Apply the queued settings changes whose timelock has elapsed (admin only)
+    /// Changes still inside their own timelock stay queued.
+    pub fn execute_settings(ctx: Context<AdminOnly>) -> Result<()> {
+        require!(
+            ctx.accounts.admin.key() == ctx.accounts.global_state.admin,
+            CryptarenaError::Unauthorized
+        );
+        let global_state = &mut ctx.accounts.global_state;
+        let clock = Clock::get()?;
+
+        let mut pending = global_state
+            .pending_settings
+            .ok_or(CryptarenaError::NoPendingSettings)?;
+        let ready = pending.take_ready(clock.unix_timestamp);
+        require!(!ready.is_empty(), CryptarenaError::TimelockNotElapsed);
+
+        if let Some(treasury_wallet) = ready.treasury_wallet {
+            global_state.treasury_wallet = treasury_wallet.value;
+        }
+        if let Some(arena_duration) = ready.arena_duration {
+            global_state.arena_duration = arena_duration.value;
+        }
+        if let Some(entry_fee) = ready.entry_fee {
+            global_state.entry_fee = entry_fee.value;
+        }
+        if let Some(paused) = ready.is_paused {
+            global_state.is_paused = paused.value;
+        }
+        if let Some(settings_timelock) = ready.settings_timelock {
+            global_state.settings_timelock = settings_timelock.value;
+        }
+        if let Some(referral_share_bps) = ready.referral_share_bps {
+            global_state.referral_share_bps = referral_share_bps.value;
+        }
+        if let Some(fee_schedule) = ready.fee_schedule {
+            global_state.fee_schedule = fee_schedule.value;
+        }
+        global_state.pending_settings = (!pending.is_empty()).then_some(pending);
+
+        msg!("Settings updated");
+        Ok(())
+    }
+
+    /// Discard the queued settings changes (admin only)
+    pub fn cancel_settings(ctx: Context<AdminOnly>) -> Result<()> {
+        require!(
+            ctx.accounts.admin.key() == ctx.accounts.global_state.admin,
+            CryptarenaError::Unauthorized
+        );
+        require!(
+            ctx.accounts.global_state.pending_settings.is_some(),
+            CryptarenaError::NoPendingSettings
+        );
+        ctx.accounts.global_state.pending_settings = None;
+        msg!("Pending settings changes canceled");
+        Ok(())
+    }
+
+    /// Nominate a new admin, who takes over by calling `accept_admin` (admin only)
+    pub fn propose_admin(
+        ctx: Context<AdminOnly>,
+        new_admin: Pubkey,
+    ) -> Result<()> {
+        require!(
+            ctx.accounts.admin.key() == ctx.accounts.global_state.admin,
+            CryptarenaError::Unauthorized
+        );
+        ctx.accounts.global_state.pending_admin = Some(new_admin);
+        msg!("Admin transfer proposed to {}", new_admin);
+        Ok(())
+    }
+
+    /// Accept a proposed admin transfer (pending admin only)
+    pub fn accept_admin(ctx: Context<AcceptAdmin>) -> Result<()> {
+        let global_state = &mut ctx.accounts.global_state;
+        require!(
+            global_state.pending_admin == Some(ctx.accounts.new_admin.key()),
+            CryptarenaError::Unauthorized
+        );
+        global_state.admin = ctx.accounts.new_admin.key();
+        global_state.pending_admin = None;
+        msg!("Admin transferred to {}", global_state.admin);
         Ok(())
     }
 
@@ -140,6 +309,14 @@ pub mod cryptarena_sol {
         whitelisted_token.is_active = true;
         whitelisted_token.bump = ctx.bumps.whitelisted_token;
         
+        emit_cpi!(TokenWhitelistUpdated {
+            asset_index,
+            chain_type,
+            token_address,
+            symbol,
+            is_active: true,
+        });
+
         msg!("Token whitelisted at index {} | Chain: {} | Symbol: {:?}", 
             asset_index, chain_type, symbol);
         Ok(())
@@ -155,16 +332,116 @@ pub mod cryptarena_sol {
             CryptarenaError::Unauthorized
         );
         
-        ctx.accounts.whitelisted_token.is_active = false;
+        let whitelisted_token = &mut ctx.accounts.whitelisted_token;
+        whitelisted_token.is_active = false;
+
+        emit_cpi!(TokenWhitelistUpdated {
+            asset_index,
+            chain_type: whitelisted_token.chain_type,
+            token_address: whitelisted_token.token_address,
+            symbol: whitelisted_token.symbol,
+            is_active: false,
+        });
+
         msg!("Token at index {} removed from whitelist", asset_index);
         Ok(())
     }
 
+    /// Create an invite-only arena - joined with `enter_arena` by its id
+    /// `invite_hash` is the sha256 of the invite secret and only used by the `InviteCode` policy
+    pub fn create_private_arena(
+        ctx: Context<CreatePrivateArena>,
+        access_policy: AccessPolicy,
+        invite_hash: [u8; 32],
+    ) -> Result<()> {
+        let global_state = &mut ctx.accounts.global_state;
+        let arena = &mut ctx.accounts.arena;
+        let arena_vault = &mut ctx.accounts.arena_vault;
+
+        require!(
+            !global_state.is_paused,
+            CryptarenaError::ProtocolPaused
+        );
+        require!(
+            access_policy != AccessPolicy::Open,
+            CryptarenaError::InvalidAccessPolicy
+        );
+
+        arena.id = global_state.next_private_arena_id();
+        arena.status = ArenaStatus::Waiting as u8;
+        arena.player_count = 0;
+        arena.total_pool = 0;
+        arena.winning_asset = 255;
+        arena.is_canceled = false;
+        arena.treasury_claimed = false;
+        arena.bump = ctx.bumps.arena;
+        arena.start_timestamp = 0;
+        arena.end_timestamp = 0;
+        arena.token_slots = [255u8; 10];
+        arena.player_addresses = [Pubkey::default(); 10];
+        arena.creator = ctx.accounts.creator.key();
+        arena.access_policy = access_policy as u8;
+        arena.invite_hash = invite_hash;
+        arena.rent_payer = ctx.accounts.creator.key();
+        arena.open_entries = 0;
+        arena.outstanding_claims = 0;
+        arena.fee_schedule = global_state.fee_schedule.snapshot(Clock::get()?.unix_timestamp);
+        arena.referral_share_bps = global_state.referral_share_bps;
+
+        arena_vault.arena_id = arena.id;
+        arena_vault.bump = ctx.bumps.arena_vault;
+
+        global_state.private_arena_count += 1;
+
+        emit_cpi!(ArenaCreated {
+            arena: arena.key(),
+            arena_id: arena.id,
+            creator: arena.creator,
+            access_policy: arena.access_policy,
+        });
+
+        msg!("Private arena {} created by {}", arena.id, arena.creator);
+        Ok(())
+    }
+
+    /// Set the players allowed into an allowlist arena (creator only, while waiting)
+    pub fn set_arena_allowlist(
+        ctx: Context<SetArenaAllowlist>,
+        players: Vec<Pubkey>,
+    ) -> Result<()> {
+        let arena = &ctx.accounts.arena;
+
+        require!(
+            arena.status == ArenaStatus::Waiting as u8,
+            CryptarenaError::ArenaNotWaiting
+        );
+        require!(
+            arena.access_policy == AccessPolicy::Allowlist as u8,
+            CryptarenaError::InvalidAccessPolicy
+        );
+        require!(
+            players.len() <= MAX_ALLOWLIST_SIZE,
+            CryptarenaError::AllowlistTooLong
+        );
+
+        let arena_allowlist = &mut ctx.accounts.arena_allowlist;
+        arena_allowlist.arena = arena.key();
+        arena_allowlist.players = players;
+        arena_allowlist.bump = ctx.bumps.arena_allowlist;
+
+        msg!("Arena {} allowlist set to {} players", arena.id, arena_allowlist.players.len());
+        Ok(())
+    }
+
     /// Enter arena with SOL - each player picks a unique token
     /// Auto-creates new arena if none exists or current is full/started
+    /// Pass `private_arena_id` to join a private arena, with `invite_secret` or the allowlist account
     pub fn enter_arena(
         ctx: Context<EnterArena>,
         asset_index: u8,
+        private_arena_id: Option<u64>,
+        invite_secret: Option<Vec<u8>>,
+        referrer: Option<Pubkey>,
     ) -> Result<()> {
         let global_state = &mut ctx.accounts.global_state;
         let arena = &mut ctx.accounts.arena;
@@ -178,6 +455,12 @@ pub mod cryptarena_sol {
 
         // Initialize arena if new (first player creates it)
         if arena.status == ArenaStatus::Uninitialized as u8 {
+            // Private arenas only exist once `create_private_arena` has opened them
+            require!(
+                private_arena_id.is_none(),
+                CryptarenaError::ArenaNotWaiting
+            );
+
             arena.id = global_state.current_arena_id;
             arena.status = ArenaStatus::Waiting as u8;
             arena.player_count = 0;
@@ -190,11 +473,30 @@ pub mod cryptarena_sol {
             arena.end_timestamp = 0;
             arena.token_slots = [255u8; 10];
             arena.player_addresses = [Pubkey::default(); 10];
+            arena.creator = Pubkey::default();
+            arena.access_policy = AccessPolicy::Open as u8;
+            arena.invite_hash = [0u8; 32];
+            arena.rent_payer = ctx.accounts.player.key();
+            arena.open_entries = 0;
+            arena.outstanding_claims = 0;
+            arena.fee_schedule = global_state.fee_schedule.snapshot(clock.unix_timestamp);
+            arena.treasury_fee_bps = 0;
+            arena.referral_share_bps = global_state.referral_share_bps;
+            arena.referred_amount = 0;
+            arena.referral_fees = 0;
+            arena.referred_entries = 0;
             
             // Initialize arena vault
             arena_vault.arena_id = global_state.current_arena_id;
             arena_vault.bump = ctx.bumps.arena_vault;
             
+            emit_cpi!(ArenaCreated {
+                arena: arena.key(),
+                arena_id: arena.id,
+                creator: arena.creator,
+                access_policy: arena.access_policy,
+            });
+
             msg!("New arena {} created", arena.id);
         }
 
@@ -208,6 +510,13 @@ pub mod cryptarena_sol {
             CryptarenaError::ArenaFull
         );
 
+        check_arena_access(
+            arena,
+            &ctx.accounts.player.key(),
+            ctx.accounts.arena_allowlist.as_deref(),
+            invite_secret.as_deref(),
+        )?;
+
         // Check token is whitelisted
         let whitelisted_token = &ctx.accounts.whitelisted_token;
         require!(
@@ -253,19 +562,67 @@ pub mod cryptarena_sol {
         player_entry.price_movement = 0;
         player_entry.bump = ctx.bumps.player_entry;
 
+        // Created on the wallet's first entry, then carried across arenas
+        let player_profile = &mut ctx.accounts.player_profile;
+        if player_profile.player == Pubkey::default() {
+            player_profile.player = ctx.accounts.player.key();
+            player_profile.favorite_asset = 255; // Unset
+            player_profile.bump = ctx.bumps.player_profile;
+        }
+        player_profile.record_entry(asset_index, entry_fee);
+
+        // A player's referrer is bound once, on their first entry
+        let referral = &mut ctx.accounts.referral;
+        if referral.player == Pubkey::default() {
+            let referrer = referrer.unwrap_or_default();
+            require!(
+                referrer != ctx.accounts.player.key(),
+                CryptarenaError::SelfReferral
+            );
+            referral.player = ctx.accounts.player.key();
+            referral.referrer = referrer;
+            referral.bump = ctx.bumps.referral;
+
+            if referrer != Pubkey::default() {
+                emit_cpi!(ReferralBound {
+                    player: referral.player,
+                    referrer,
+                });
+            }
+        }
+
+        // The referrer's cut depends on the final player count, so it is only reserved in `end_arena`
+        player_entry.referrer = referral.referrer;
+        if referral.referrer != Pubkey::default() {
+            arena.referred_amount += entry_fee;
+            arena.referred_entries += 1;
+        }
+
         // Update arena state
         let player_idx = arena.player_count as usize;
         arena.token_slots[player_idx] = asset_index;
         arena.player_addresses[player_idx] = ctx.accounts.player.key();
         arena.player_count += 1;
+        arena.open_entries += 1;
         arena.total_pool += entry_fee;
 
+        emit_cpi!(PlayerEntered {
+            arena: arena.key(),
+            arena_id: arena.id,
+            player: ctx.accounts.player.key(),
+            asset_index,
+            player_index: player_entry.player_index,
+            entry_fee,
+            player_count: arena.player_count,
+            total_pool: arena.total_pool,
+        });
+
         msg!("Player {} entered arena {} | Token: {} | Entry Fee: {} | Players: {}/{}", 
             ctx.accounts.player.key(), arena.id, asset_index, entry_fee,
             arena.player_count, MAX_PLAYERS_PER_ARENA);
 
-        // If arena is now full (10 players), increment arena ID so next player creates new arena
-        if arena.player_count >= MAX_PLAYERS_PER_ARENA {
+        // If the public arena is now full (10 players), increment arena ID so next player creates new arena
+        if arena.player_count >= MAX_PLAYERS_PER_ARENA && arena.id == global_state.current_arena_id {
             global_state.current_arena_id += 1;
             msg!("Arena {} is FULL! Next player will create arena {}", arena.id, global_state.current_arena_id);
         }
@@ -273,6 +630,69 @@ pub mod cryptarena_sol {
         Ok(())
     }
 
+    /// Leave a waiting arena - refunds the entry fee and closes the player entry
+    /// The last player is moved into the vacated slot; pass their PlayerEntry as `moved_entry`
+    pub fn leave_arena(ctx: Context<LeaveArena>) -> Result<()> {
+        let arena = &mut ctx.accounts.arena;
+        let player_entry = &ctx.accounts.player_entry;
+
+        require!(
+            arena.status == ArenaStatus::Waiting as u8,
+            CryptarenaError::ArenaNotWaiting
+        );
+
+        let slot = player_entry.player_index as usize;
+        let last = arena.player_count as usize - 1;
+
+        // Compact the slots by moving the last player into the vacated one
+        if slot != last {
+            let moved_entry = ctx
+                .accounts
+                .moved_entry
+                .as_mut()
+                .ok_or(CryptarenaError::MissingMovedEntry)?;
+            require!(
+                moved_entry.arena == arena.key()
+                    && moved_entry.player == arena.player_addresses[last],
+                CryptarenaError::MissingMovedEntry
+            );
+
+            arena.token_slots[slot] = arena.token_slots[last];
+            arena.player_addresses[slot] = arena.player_addresses[last];
+            moved_entry.player_index = slot as u8;
+        }
+        arena.token_slots[last] = 255;
+        arena.player_addresses[last] = Pubkey::default();
+
+        // Refund the entry fee from the arena vault
+        let refund_amount = player_entry.entry_fee;
+        **ctx.accounts.arena_vault.to_account_info().try_borrow_mut_lamports()? -= refund_amount;
+        **ctx.accounts.player.to_account_info().try_borrow_mut_lamports()? += refund_amount;
+
+        arena.player_count -= 1;
+        arena.open_entries -= 1;
+        arena.total_pool -= refund_amount;
+        if player_entry.referrer != Pubkey::default() {
+            arena.referred_amount -= refund_amount;
+            arena.referred_entries -= 1;
+        }
+        ctx.accounts.player_profile.undo_entry(player_entry.asset_index, refund_amount);
+
+        emit_cpi!(PlayerLeft {
+            arena: arena.key(),
+            arena_id: arena.id,
+            player: ctx.accounts.player.key(),
+            asset_index: player_entry.asset_index,
+            refund_amount,
+            player_count: arena.player_count,
+        });
+
+        msg!("Player {} left arena {} | Refunded {} lamports | Players: {}/{}", 
+            ctx.accounts.player.key(), arena.id, refund_amount,
+            arena.player_count, MAX_PLAYERS_PER_ARENA);
+        Ok(())
+    }
+
     /// Start arena (ADMIN ONLY) - requires at least 1 player
     /// After starting, increments arena ID so next player creates new arena
     pub fn start_arena(ctx: Context<StartArena>) -> Result<()> {
@@ -299,8 +719,19 @@ pub mod cryptarena_sol {
         arena.start_timestamp = clock.unix_timestamp;
         arena.end_timestamp = clock.unix_timestamp + global_state.arena_duration;
 
-        // Increment arena ID so next player entering creates a new arena
-        global_state.current_arena_id += 1;
+        // Increment arena ID so next player entering creates a new arena (private arenas sit outside the sequence)
+        if arena.id == global_state.current_arena_id {
+            global_state.current_arena_id += 1;
+        }
+
+        emit_cpi!(ArenaStarted {
+            arena: arena.key(),
+            arena_id: arena.id,
+            player_count: arena.player_count,
+            total_pool: arena.total_pool,
+            start_timestamp: arena.start_timestamp,
+            end_timestamp: arena.end_timestamp,
+        });
 
         msg!("Arena {} STARTED! {} players | Ends at {}", 
             arena.id, arena.player_count, arena.end_timestamp);
@@ -329,6 +760,16 @@ pub mod cryptarena_sol {
 
         player_entry.start_price = price;
 
+        emit_cpi!(PriceSet {
+            arena: arena.key(),
+            arena_id: arena.id,
+            player: player_entry.player,
+            asset_index: player_entry.asset_index,
+            price,
+            is_start: true,
+            price_movement: player_entry.price_movement,
+        });
+
         msg!("Player {} token {} start price: {}", 
             player_entry.player_index, player_entry.asset_index, price);
 
@@ -374,6 +815,16 @@ pub mod cryptarena_sol {
             player_entry.price_movement = movement as i64;
         }
 
+        emit_cpi!(PriceSet {
+            arena: arena.key(),
+            arena_id: arena.id,
+            player: player_entry.player,
+            asset_index: player_entry.asset_index,
+            price,
+            is_start: false,
+            price_movement: player_entry.price_movement,
+        });
+
         msg!("Player {} token {} end price: {} | Movement: {} (12 decimals)", 
             player_entry.player_index, player_entry.asset_index, 
             price, player_entry.price_movement);
@@ -382,8 +833,9 @@ pub mod cryptarena_sol {
     }
 
     /// End arena and determine winner (ADMIN ONLY)
-    /// Must pass all PlayerEntry accounts as remaining_accounts
-    pub fn end_arena(ctx: Context<EndArena>) -> Result<()> {
+    /// Must pass a (PlayerEntry, PlayerProfile) pair per player as remaining_accounts; the losers'
+    /// profiles record the loss here, since a loser has nothing left to claim
+    pub fn end_arena<'info>(ctx: Context<'_, '_, 'info, 'info, EndArena<'info>>) -> Result<()> {
         let arena = &mut ctx.accounts.arena;
         let global_state = &ctx.accounts.global_state;
         let clock = Clock::get()?;
@@ -410,12 +862,19 @@ pub mod cryptarena_sol {
         let mut tie_detected = false;
         let mut prices_set_count = 0;
 
-        for account_info in ctx.remaining_accounts.iter() {
-            let data = account_info.try_borrow_data()?;
+        let pairs = ctx.remaining_accounts.chunks_exact(2);
+        require!(
+            pairs.remainder().is_empty(),
+            CryptarenaError::InvalidRemainingAccounts
+        );
+
+        for pair in pairs.clone() {
+            let data = pair[0].try_borrow_data()?;
             // Skip discriminator (8 bytes) and parse PlayerEntry
             // PlayerEntry layout: arena(32) + player(32) + asset_index(1) + player_index(1) + 
             //                     entry_fee(8) + entry_timestamp(8) + start_price(8) + end_price(8) + 
-            //                     price_movement(8) + is_winner(1) + has_claimed(1) + bump(1)
+            //                     price_movement(8) + is_winner(1) + has_claimed(1) + referrer(32) +
+            //                     referral_credited(1) + bump(1)
             if data.len() >= 8 + 32 + 32 + 1 + 1 + 8 + 8 + 8 + 8 + 8 {
                 let asset_index = data[8 + 32 + 32]; // After discriminator + arena + player
                 
@@ -465,21 +924,71 @@ pub mod cryptarena_sol {
             // Cancel arena - users can claim back their SOL
             arena.is_canceled = true;
             arena.status = ArenaStatus::Canceled as u8;
+            arena.outstanding_claims = arena.player_count;
+            emit_cpi!(ArenaEnded {
+                arena: arena.key(),
+                arena_id: arena.id,
+                status: arena.status,
+                winning_asset: arena.winning_asset,
+                winning_movement: best_movement,
+                total_pool: arena.total_pool,
+                treasury_fee_bps: arena.treasury_fee_bps,
+            });
             msg!("Arena {} CANCELED due to tie! Users can claim refunds.", arena.id);
             return Ok(());
         }
 
         arena.winning_asset = winning_asset;
         arena.status = ArenaStatus::Ended as u8;
+        // The fee rate comes from the arena's own schedule now that the player count is final;
+        // referrers' cuts are carved out of it
+        arena.treasury_fee_bps = arena.fee_schedule.fee_bps(arena.player_count);
+        arena.referral_fees = arena.referral_fee(arena.referred_amount);
+        // Tokens are unique per arena, so there is one winner
+        arena.outstanding_claims = 1;
+        if arena.treasury_fee() > 0 {
+            arena.outstanding_claims += 1;
+        }
+        if arena.referral_fees > 0 {
+            arena.outstanding_claims += arena.referred_entries;
+        }
+
+        emit_cpi!(ArenaEnded {
+            arena: arena.key(),
+            arena_id: arena.id,
+            status: arena.status,
+            winning_asset,
+            winning_movement: best_movement,
+            total_pool: arena.total_pool,
+            treasury_fee_bps: arena.treasury_fee_bps,
+        });
+
+        // Each player's entry must appear once, with that player's profile
+        let mut recorded: u16 = 0;
+        for pair in pairs {
+            let entry: Account<PlayerEntry> = Account::try_from(&pair[0])?;
+            let mut profile: Account<PlayerProfile> = Account::try_from(&pair[1])?;
+            require!(
+                entry.arena == arena.key()
+                    && profile.player == entry.player
+                    && recorded & (1 << entry.player_index) == 0,
+                CryptarenaError::InvalidRemainingAccounts
+            );
+            recorded |= 1 << entry.player_index;
+            if entry.asset_index != winning_asset {
+                profile.record_loss(arena.end_timestamp);
+                profile.exit(&crate::ID)?;
+            }
+        }
 
         msg!("Arena {} ENDED! Winner token: {} with {} (8 decimals)", 
             arena.id, winning_asset, best_movement);
         Ok(())
     }
 
-    /// Winner claims rewards (100% for single-player arenas, 90% for multi-player arenas)
+    /// Winner claims the pool less the treasury fee set by the arena's fee schedule
     pub fn claim_winner_rewards(ctx: Context<ClaimWinnerRewards>) -> Result<()> {
-        let arena = &ctx.accounts.arena;
+        let arena = &mut ctx.accounts.arena;
         let player_entry = &mut ctx.accounts.player_entry;
 
         require!(
@@ -497,15 +1006,9 @@ pub mod cryptarena_sol {
             CryptarenaError::RewardAlreadyClaimed
         );
 
-        // Calculate winner's reward
-        // If only 1 player, winner gets 100% of pool. Otherwise, 90% (normal split)
+        // Calculate winner's reward: everything but the treasury fee, referrers' cuts included
         let total_pool = arena.total_pool;
-        let winner_share_bps = if arena.player_count == 1 {
-            10000 // 100% for single player arenas
-        } else {
-            WINNER_SHARE_BPS // 90% for multi-player arenas
-        };
-        let winner_reward = (total_pool * winner_share_bps) / 10000;
+        let winner_reward = total_pool - (total_pool * arena.treasury_fee_bps) / 10000;
 
         // Transfer SOL from arena vault to winner
         **ctx.accounts.arena_vault.to_account_info().try_borrow_mut_lamports()? -= winner_reward;
@@ -513,16 +1016,23 @@ pub mod cryptarena_sol {
 
         player_entry.is_winner = true;
         player_entry.has_claimed = true;
-
-        if arena.player_count == 1 {
-            msg!("Single player winner claimed {} lamports ({} SOL) - 100% of pool", winner_reward, winner_reward as f64 / 1_000_000_000.0);
-        } else {
-            msg!("Winner claimed {} lamports ({} SOL) - 90% of pool", winner_reward, winner_reward as f64 / 1_000_000_000.0);
-        }
+        arena.outstanding_claims -= 1;
+        ctx.accounts.player_profile.record_win(winner_reward, arena.end_timestamp);
+
+        emit_cpi!(WinnerRewardClaimed {
+            arena: arena.key(),
+            arena_id: arena.id,
+            player: player_entry.player,
+            asset_index: player_entry.asset_index,
+            amount: winner_reward,
+        });
+
+        msg!("Winner claimed {} lamports ({} SOL) - {} bps treasury fee",
+            winner_reward, winner_reward as f64 / 1_000_000_000.0, arena.treasury_fee_bps);
         Ok(())
     }
 
-    /// Admin claims treasury fee (10% of total pool, 0% for single-player arenas)
+    /// Admin claims the treasury fee set by the arena's fee schedule, less the referrers' cuts
     pub fn claim_treasury_fee(ctx: Context<ClaimTreasuryFee>) -> Result<()> {
         require!(
             ctx.accounts.admin.key() == ctx.accounts.global_state.admin,
@@ -541,33 +1051,34 @@ pub mod cryptarena_sol {
             CryptarenaError::TreasuryFeeAlreadyClaimed
         );
 
-        // Calculate treasury fee
-        // For single-player arenas, treasury gets 0% (winner gets 100%)
-        // For multi-player arenas, treasury gets 10%
-        let total_pool = arena.total_pool;
-        let treasury_fee = if arena.player_count == 1 {
-            0 // No treasury fee for single-player arenas
-        } else {
-            (total_pool * TREASURY_FEE_BPS) / 10000
-        };
+        // Referrers' cuts were reserved in `end_arena` and are paid through `credit_referral`
+        let treasury_fee = arena.treasury_fee();
 
         // Only transfer if there's a fee to claim
         if treasury_fee > 0 {
             // Transfer SOL from arena vault to treasury
             **ctx.accounts.arena_vault.to_account_info().try_borrow_mut_lamports()? -= treasury_fee;
             **ctx.accounts.treasury_wallet.to_account_info().try_borrow_mut_lamports()? += treasury_fee;
+            arena.outstanding_claims -= 1;
             msg!("Treasury claimed {} lamports ({} SOL)", treasury_fee, treasury_fee as f64 / 1_000_000_000.0);
         } else {
-            msg!("Treasury fee is 0 for single-player arena - nothing to claim");
+            msg!("Treasury fee is 0 for this arena - nothing to claim");
         }
 
         arena.treasury_claimed = true;
+
+        emit_cpi!(TreasuryFeeClaimed {
+            arena: arena.key(),
+            arena_id: arena.id,
+            treasury_wallet: ctx.accounts.treasury_wallet.key(),
+            amount: treasury_fee,
+        });
         Ok(())
     }
 
     /// Claim refund for canceled arena (tie scenario)
     pub fn claim_refund(ctx: Context<ClaimRefund>) -> Result<()> {
-        let arena = &ctx.accounts.arena;
+        let arena = &mut ctx.accounts.arena;
         let player_entry = &mut ctx.accounts.player_entry;
 
         require!(
@@ -593,10 +1104,193 @@ pub mod cryptarena_sol {
         **ctx.accounts.player.to_account_info().try_borrow_mut_lamports()? += refund_amount;
 
         player_entry.has_claimed = true;
+        arena.outstanding_claims -= 1;
+        ctx.accounts.player_profile.record_refund();
+
+        emit_cpi!(RefundClaimed {
+            arena: arena.key(),
+            arena_id: arena.id,
+            player: player_entry.player,
+            asset_index: player_entry.asset_index,
+            amount: refund_amount,
+        });
 
         msg!("Player refunded {} lamports ({} SOL)", refund_amount, refund_amount as f64 / 1_000_000_000.0);
         Ok(())
     }
+
+    /// Move a referred entry's cut of the treasury fee to its referrer's balance (permissionless)
+    pub fn credit_referral(ctx: Context<CreditReferral>) -> Result<()> {
+        let arena = &mut ctx.accounts.arena;
+        let player_entry = &mut ctx.accounts.player_entry;
+        let referrer_balance = &mut ctx.accounts.referrer_balance;
+
+        require!(
+            arena.status == ArenaStatus::Ended as u8,
+            CryptarenaError::ArenaNotEnded
+        );
+        require!(
+            arena.referral_fees > 0
+                && player_entry.referrer != Pubkey::default()
+                && !player_entry.referral_credited,
+            CryptarenaError::NoReferralFee
+        );
+
+        // The balance account holds the lamports it owes; rounding dust of the reserve stays in the vault
+        let amount = arena.referral_fee(player_entry.entry_fee);
+        **ctx.accounts.arena_vault.to_account_info().try_borrow_mut_lamports()? -= amount;
+        **referrer_balance.to_account_info().try_borrow_mut_lamports()? += amount;
+
+        if referrer_balance.referrer == Pubkey::default() {
+            referrer_balance.referrer = player_entry.referrer;
+            referrer_balance.bump = ctx.bumps.referrer_balance;
+        }
+        referrer_balance.balance += amount;
+        referrer_balance.total_earned += amount;
+        player_entry.referral_credited = true;
+        arena.outstanding_claims -= 1;
+
+        emit_cpi!(ReferralCredited {
+            arena: arena.key(),
+            arena_id: arena.id,
+            player: player_entry.player,
+            referrer: player_entry.referrer,
+            amount,
+            balance: referrer_balance.balance,
+        });
+
+        msg!("Referrer {} credited {} lamports for player {} in arena {}",
+            player_entry.referrer, amount, player_entry.player, arena.id);
+        Ok(())
+    }
+
+    /// Referrer withdraws their accrued referral balance
+    pub fn claim_referral_rewards(ctx: Context<ClaimReferralRewards>) -> Result<()> {
+        let referrer_balance = &mut ctx.accounts.referrer_balance;
+        let amount = referrer_balance.balance;
+
+        require!(
+            amount > 0,
+            CryptarenaError::NoReferralFee
+        );
+
+        **referrer_balance.to_account_info().try_borrow_mut_lamports()? -= amount;
+        **ctx.accounts.referrer.to_account_info().try_borrow_mut_lamports()? += amount;
+        referrer_balance.balance = 0;
+
+        emit_cpi!(ReferralRewardsClaimed {
+            referrer: referrer_balance.referrer,
+            amount,
+        });
+
+        msg!("Referrer {} claimed {} lamports ({} SOL)",
+            referrer_balance.referrer, amount, amount as f64 / 1_000_000_000.0);
+        Ok(())
+    }
+
+    /// Close a settled player entry and return its rent to the player (permissionless)
+    /// The loser of an ended arena can close right away; everyone else once they have claimed.
+    /// Referred entries of an ended arena that owes referral fees must be credited with
+    /// `credit_referral` first.
+    pub fn close_player_entry(ctx: Context<ClosePlayerEntry>) -> Result<()> {
+        let arena = &mut ctx.accounts.arena;
+        let player_entry = &ctx.accounts.player_entry;
+
+        require!(
+            arena.is_finished(),
+            CryptarenaError::ArenaNotFinished
+        );
+
+        let settled = player_entry.has_claimed
+            || (arena.status == ArenaStatus::Ended as u8
+                && player_entry.asset_index != arena.winning_asset);
+        let referral_owed = arena.status == ArenaStatus::Ended as u8
+            && arena.referral_fees > 0
+            && player_entry.referrer != Pubkey::default()
+            && !player_entry.referral_credited;
+        require!(
+            settled && !referral_owed,
+            CryptarenaError::EntryNotSettled
+        );
+
+        arena.open_entries -= 1;
+
+        emit_cpi!(PlayerEntryClosed {
+            arena: arena.key(),
+            arena_id: arena.id,
+            player: player_entry.player,
+        });
+
+        msg!("Closed entry of player {} in arena {} | Open entries: {}",
+            player_entry.player, arena.id, arena.open_entries);
+        Ok(())
+    }
+
+    /// Close a finished arena and its vault once every claim is paid and all entries are closed
+    /// Permissionless so a crank can sweep finished arenas; rent (and any rounding dust left in
+    /// the vault) always goes to the arena's rent payer. Pass `arena_allowlist` to close it too.
+    pub fn close_arena(ctx: Context<CloseArena>) -> Result<()> {
+        let arena = &ctx.accounts.arena;
+
+        require!(
+            arena.is_finished(),
+            CryptarenaError::ArenaNotFinished
+        );
+        require!(
+            arena.outstanding_claims == 0,
+            CryptarenaError::OutstandingClaims
+        );
+        require!(
+            arena.open_entries == 0,
+            CryptarenaError::EntriesStillOpen
+        );
+
+        emit_cpi!(ArenaClosed {
+            arena: arena.key(),
+            arena_id: arena.id,
+            status: arena.status,
+            rent_payer: arena.rent_payer,
+            vault_lamports: ctx.accounts.arena_vault.to_account_info().lamports(),
+        });
+
+        msg!("Closed arena {} | Rent returned to {}", arena.id, arena.rent_payer);
+        Ok(())
+    }
+}
+
+// ============================================================================
+// HELPER FUNCTIONS
+// ============================================================================
+
+/// Merge a change into the pending settings; only the field it queues gets the new timelock
+fn queue_settings(
+    global_state: &mut GlobalState,
+    change: impl FnOnce(&mut PendingSettings, i64),
+) -> Result<()> {
+    let executable_at = Clock::get()?.unix_timestamp + global_state.settings_timelock;
+    let pending = global_state.pending_settings.get_or_insert_with(PendingSettings::default);
+    change(pending, executable_at);
+    Ok(())
+}
+
+/// Enforce a private arena's access policy for `player`
+fn check_arena_access(
+    arena: &Arena,
+    player: &Pubkey,
+    allowlist: Option<&ArenaAllowlist>,
+    invite_secret: Option<&[u8]>,
+) -> Result<()> {
+    if arena.access_policy == AccessPolicy::Allowlist as u8 {
+        let allowlist = allowlist.ok_or(CryptarenaError::NotInvited)?;
+        require!(allowlist.players.contains(player), CryptarenaError::NotInvited);
+    } else if arena.access_policy == AccessPolicy::InviteCode as u8 {
+        let invite_secret = invite_secret.ok_or(CryptarenaError::InvalidInviteSecret)?;
+        require!(
+            hash(invite_secret).to_bytes() == arena.invite_hash,
+            CryptarenaError::InvalidInviteSecret
+        );
+    }
+    Ok(())
 }
 
 // ============================================================================
@@ -631,6 +1325,15 @@ pub struct AdminOnly<'info> {
     pub admin: Signer<'info>,
 }
 
+#[derive(Accounts)]
+pub struct AcceptAdmin<'info> {
+    #[account(mut, seeds = [b"global_state"], bump = global_state.bump)]
+    pub global_state: Account<'info, GlobalState>,
+
+    pub new_admin: Signer<'info>,
+}
+
+#[event_cpi]
 #[derive(Accounts)]
 #[instruction(asset_index: u8)]
 pub struct AddWhitelistedToken<'info> {
@@ -652,6 +1355,7 @@ pub struct AddWhitelistedToken<'info> {
     pub system_program: Program<'info, System>,
 }
 
+#[event_cpi]
 #[derive(Accounts)]
 #[instruction(asset_index: u8)]
 pub struct RemoveWhitelistedToken<'info> {
@@ -668,39 +1372,125 @@ pub struct RemoveWhitelistedToken<'info> {
     pub admin: Signer<'info>,
 }
 
+#[event_cpi]
 #[derive(Accounts)]
-#[instruction(asset_index: u8)]
-pub struct EnterArena<'info> {
+pub struct CreatePrivateArena<'info> {
     #[account(mut, seeds = [b"global_state"], bump = global_state.bump)]
     pub global_state: Account<'info, GlobalState>,
 
     #[account(
-        init_if_needed,
-        payer = player,
+        init,
+        payer = creator,
         space = 8 + Arena::INIT_SPACE,
-        seeds = [b"arena", global_state.current_arena_id.to_le_bytes().as_ref()],
+        seeds = [b"arena", global_state.next_private_arena_id().to_le_bytes().as_ref()],
         bump
     )]
     pub arena: Account<'info, Arena>,
 
     #[account(
-        init_if_needed,
-        payer = player,
+        init,
+        payer = creator,
         space = 8 + ArenaVault::INIT_SPACE,
-        seeds = [b"arena_vault", global_state.current_arena_id.to_le_bytes().as_ref()],
+        seeds = [b"arena_vault", global_state.next_private_arena_id().to_le_bytes().as_ref()],
         bump
     )]
     pub arena_vault: Account<'info, ArenaVault>,
 
-    #[account(
-        init,
-        payer = player,
-        space = 8 + PlayerEntry::INIT_SPACE,
+    #[account(mut)]
+    pub creator: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetArenaAllowlist<'info> {
+    #[account(
+        seeds = [b"arena", arena.id.to_le_bytes().as_ref()],
+        bump = arena.bump,
+        constraint = arena.creator == creator.key() @ CryptarenaError::Unauthorized
+    )]
+    pub arena: Account<'info, Arena>,
+
+    #[account(
+        init_if_needed,
+        payer = creator,
+        space = 8 + ArenaAllowlist::INIT_SPACE,
+        seeds = [b"arena_allowlist", arena.key().as_ref()],
+        bump
+    )]
+    pub arena_allowlist: Account<'info, ArenaAllowlist>,
+
+    #[account(mut)]
+    pub creator: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(asset_index: u8, private_arena_id: Option<u64>)]
+pub struct EnterArena<'info> {
+    #[account(mut, seeds = [b"global_state"], bump = global_state.bump)]
+    pub global_state: Account<'info, GlobalState>,
+
+    #[account(
+        init_if_needed,
+        payer = player,
+        space = 8 + Arena::INIT_SPACE,
+        seeds = [
+            b"arena",
+            private_arena_id.unwrap_or(global_state.current_arena_id).to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub arena: Account<'info, Arena>,
+
+    #[account(
+        init_if_needed,
+        payer = player,
+        space = 8 + ArenaVault::INIT_SPACE,
+        seeds = [
+            b"arena_vault",
+            private_arena_id.unwrap_or(global_state.current_arena_id).to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub arena_vault: Account<'info, ArenaVault>,
+
+    /// Required when entering an allowlist arena
+    #[account(
+        seeds = [b"arena_allowlist", arena.key().as_ref()],
+        bump = arena_allowlist.bump
+    )]
+    pub arena_allowlist: Option<Account<'info, ArenaAllowlist>>,
+
+    #[account(
+        init,
+        payer = player,
+        space = 8 + PlayerEntry::INIT_SPACE,
         seeds = [b"player_entry", arena.key().as_ref(), player.key().as_ref()],
         bump
     )]
     pub player_entry: Account<'info, PlayerEntry>,
 
+    #[account(
+        init_if_needed,
+        payer = player,
+        space = 8 + PlayerProfile::INIT_SPACE,
+        seeds = [b"player_profile", player.key().as_ref()],
+        bump
+    )]
+    pub player_profile: Account<'info, PlayerProfile>,
+
+    #[account(
+        init_if_needed,
+        payer = player,
+        space = 8 + Referral::INIT_SPACE,
+        seeds = [b"referral", player.key().as_ref()],
+        bump
+    )]
+    pub referral: Account<'info, Referral>,
+
     /// Whitelisted token account (looked up by asset_index)
     #[account(
         seeds = [b"whitelist_token", asset_index.to_le_bytes().as_ref()],
@@ -714,6 +1504,49 @@ pub struct EnterArena<'info> {
     pub system_program: Program<'info, System>,
 }
 
+#[event_cpi]
+#[derive(Accounts)]
+pub struct LeaveArena<'info> {
+    #[account(
+        mut,
+        seeds = [b"arena", arena.id.to_le_bytes().as_ref()],
+        bump = arena.bump
+    )]
+    pub arena: Account<'info, Arena>,
+
+    #[account(
+        mut,
+        seeds = [b"arena_vault", arena.id.to_le_bytes().as_ref()],
+        bump = arena_vault.bump
+    )]
+    pub arena_vault: Account<'info, ArenaVault>,
+
+    #[account(
+        mut,
+        close = player,
+        seeds = [b"player_entry", arena.key().as_ref(), player.key().as_ref()],
+        bump = player_entry.bump,
+    )]
+    pub player_entry: Account<'info, PlayerEntry>,
+
+    #[account(
+        mut,
+        seeds = [b"player_profile", player.key().as_ref()],
+        bump = player_profile.bump
+    )]
+    pub player_profile: Account<'info, PlayerProfile>,
+
+    /// Entry of the last player in the arena, moved into the vacated slot (omit if leaving from the last slot)
+    #[account(mut)]
+    pub moved_entry: Option<Account<'info, PlayerEntry>>,
+
+    #[account(mut)]
+    pub player: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[event_cpi]
 #[derive(Accounts)]
 pub struct StartArena<'info> {
     #[account(mut, seeds = [b"global_state"], bump = global_state.bump)]
@@ -729,6 +1562,7 @@ pub struct StartArena<'info> {
     pub admin: Signer<'info>,
 }
 
+#[event_cpi]
 #[derive(Accounts)]
 pub struct SetPlayerPrice<'info> {
     #[account(seeds = [b"global_state"], bump = global_state.bump)]
@@ -750,6 +1584,7 @@ pub struct SetPlayerPrice<'info> {
     pub admin: Signer<'info>,
 }
 
+#[event_cpi]
 #[derive(Accounts)]
 pub struct EndArena<'info> {
     #[account(seeds = [b"global_state"], bump = global_state.bump)]
@@ -763,12 +1598,14 @@ pub struct EndArena<'info> {
     pub arena: Account<'info, Arena>,
 
     pub admin: Signer<'info>,
-    // PlayerEntry accounts passed as remaining_accounts
+    // (PlayerEntry, PlayerProfile) pairs passed as remaining_accounts
 }
 
+#[event_cpi]
 #[derive(Accounts)]
 pub struct ClaimWinnerRewards<'info> {
     #[account(
+        mut,
         seeds = [b"arena", arena.id.to_le_bytes().as_ref()],
         bump = arena.bump
     )]
@@ -788,12 +1625,20 @@ pub struct ClaimWinnerRewards<'info> {
     )]
     pub player_entry: Account<'info, PlayerEntry>,
 
+    #[account(
+        mut,
+        seeds = [b"player_profile", winner.key().as_ref()],
+        bump = player_profile.bump
+    )]
+    pub player_profile: Account<'info, PlayerProfile>,
+
     #[account(mut)]
     pub winner: Signer<'info>,
 
     pub system_program: Program<'info, System>,
 }
 
+#[event_cpi]
 #[derive(Accounts)]
 pub struct ClaimTreasuryFee<'info> {
     #[account(seeds = [b"global_state"], bump = global_state.bump)]
@@ -826,9 +1671,11 @@ pub struct ClaimTreasuryFee<'info> {
     pub system_program: Program<'info, System>,
 }
 
+#[event_cpi]
 #[derive(Accounts)]
 pub struct ClaimRefund<'info> {
     #[account(
+        mut,
         seeds = [b"arena", arena.id.to_le_bytes().as_ref()],
         bump = arena.bump
     )]
@@ -848,12 +1695,138 @@ pub struct ClaimRefund<'info> {
     )]
     pub player_entry: Account<'info, PlayerEntry>,
 
+    #[account(
+        mut,
+        seeds = [b"player_profile", player.key().as_ref()],
+        bump = player_profile.bump
+    )]
+    pub player_profile: Account<'info, PlayerProfile>,
+
     #[account(mut)]
     pub player: Signer<'info>,
 
     pub system_program: Program<'info, System>,
 }
 
+#[event_cpi]
+#[derive(Accounts)]
+pub struct CreditReferral<'info> {
+    #[account(
+        mut,
+        seeds = [b"arena", arena.id.to_le_bytes().as_ref()],
+        bump = arena.bump
+    )]
+    pub arena: Account<'info, Arena>,
+
+    #[account(
+        mut,
+        seeds = [b"arena_vault", arena.id.to_le_bytes().as_ref()],
+        bump = arena_vault.bump
+    )]
+    pub arena_vault: Account<'info, ArenaVault>,
+
+    #[account(
+        mut,
+        seeds = [b"player_entry", arena.key().as_ref(), player_entry.player.as_ref()],
+        bump = player_entry.bump,
+    )]
+    pub player_entry: Account<'info, PlayerEntry>,
+
+    #[account(
+        init_if_needed,
+        payer = caller,
+        space = 8 + ReferrerBalance::INIT_SPACE,
+        seeds = [b"referrer_balance", player_entry.referrer.as_ref()],
+        bump
+    )]
+    pub referrer_balance: Account<'info, ReferrerBalance>,
+
+    /// CHECK: Anyone can credit referrals; the fee always goes to the entry's referrer
+    #[account(mut)]
+    pub caller: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct ClaimReferralRewards<'info> {
+    #[account(
+        mut,
+        seeds = [b"referrer_balance", referrer.key().as_ref()],
+        bump = referrer_balance.bump,
+        has_one = referrer
+    )]
+    pub referrer_balance: Account<'info, ReferrerBalance>,
+
+    #[account(mut)]
+    pub referrer: Signer<'info>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct ClosePlayerEntry<'info> {
+    #[account(
+        mut,
+        seeds = [b"arena", arena.id.to_le_bytes().as_ref()],
+        bump = arena.bump
+    )]
+    pub arena: Account<'info, Arena>,
+
+    #[account(
+        mut,
+        close = player,
+        seeds = [b"player_entry", arena.key().as_ref(), player.key().as_ref()],
+        bump = player_entry.bump,
+        has_one = player
+    )]
+    pub player_entry: Account<'info, PlayerEntry>,
+
+    /// CHECK: The entry's player, who paid its rent
+    #[account(mut)]
+    pub player: UncheckedAccount<'info>,
+
+    /// CHECK: Anyone can close settled entries; rent always goes to the player
+    pub caller: Signer<'info>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct CloseArena<'info> {
+    #[account(
+        mut,
+        close = rent_payer,
+        seeds = [b"arena", arena.id.to_le_bytes().as_ref()],
+        bump = arena.bump,
+        has_one = rent_payer
+    )]
+    pub arena: Account<'info, Arena>,
+
+    #[account(
+        mut,
+        close = rent_payer,
+        seeds = [b"arena_vault", arena.id.to_le_bytes().as_ref()],
+        bump = arena_vault.bump
+    )]
+    pub arena_vault: Account<'info, ArenaVault>,
+
+    /// Only private arenas have one; paid by the creator, who is also the arena's rent payer
+    #[account(
+        mut,
+        close = rent_payer,
+        seeds = [b"arena_allowlist", arena.key().as_ref()],
+        bump = arena_allowlist.bump
+    )]
+    pub arena_allowlist: Option<Account<'info, ArenaAllowlist>>,
+
+    /// CHECK: Paid the arena and vault rent
+    #[account(mut)]
+    pub rent_payer: UncheckedAccount<'info>,
+
+    /// CHECK: Anyone can close finished arenas
+    pub caller: Signer<'info>,
+}
+
 // ============================================================================
 // STATE
 // ============================================================================
@@ -866,10 +1839,156 @@ pub struct GlobalState {
     pub arena_duration: i64,
     pub entry_fee: u64,
     pub current_arena_id: u64,
+    /// Number of private arenas created, see `next_private_arena_id`
+    pub private_arena_count: u64,
+    /// Share of a referred entry's treasury fee paid to the referrer, in bps of the fee
+    pub referral_share_bps: u64,
+    /// Treasury fee rates new arenas snapshot when they are created
+    pub fee_schedule: FeeSchedule,
+    /// Admin nominated by `propose_admin`, waiting to accept
+    pub pending_admin: Option<Pubkey>,
+    /// Seconds queued settings changes wait before they can be executed
+    pub settings_timelock: i64,
+    pub pending_settings: Option<PendingSettings>,
     pub is_paused: bool,
     pub bump: u8,
 }
 
+/// Settings changes queued by the admin setters, each with its own timelock; `None` fields are left unchanged
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, InitSpace)]
+pub struct PendingSettings {
+    pub treasury_wallet: Option<Queued<Pubkey>>,
+    pub arena_duration: Option<Queued<i64>>,
+    pub entry_fee: Option<Queued<u64>>,
+    pub is_paused: Option<Queued<bool>>,
+    pub settings_timelock: Option<Queued<i64>>,
+    pub referral_share_bps: Option<Queued<u64>>,
+    pub fee_schedule: Option<Queued<FeeSchedule>>,
+}
+
+impl PendingSettings {
+    /// Take out the changes whose timelock has elapsed, leaving the rest queued
+    fn take_ready(&mut self, now: i64) -> PendingSettings {
+        fn take<T: Copy>(slot: &mut Option<Queued<T>>, now: i64) -> Option<Queued<T>> {
+            match slot {
+                Some(queued) if now >= queued.executable_at => slot.take(),
+                _ => None,
+            }
+        }
+
+        PendingSettings {
+            treasury_wallet: take(&mut self.treasury_wallet, now),
+            arena_duration: take(&mut self.arena_duration, now),
+            entry_fee: take(&mut self.entry_fee, now),
+            is_paused: take(&mut self.is_paused, now),
+            settings_timelock: take(&mut self.settings_timelock, now),
+            referral_share_bps: take(&mut self.referral_share_bps, now),
+            fee_schedule: take(&mut self.fee_schedule, now),
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.treasury_wallet.is_none()
+            && self.arena_duration.is_none()
+            && self.entry_fee.is_none()
+            && self.is_paused.is_none()
+            && self.settings_timelock.is_none()
+            && self.referral_share_bps.is_none()
+            && self.fee_schedule.is_none()
+    }
+}
+
+/// Queued change together with the time its own timelock elapses
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+pub struct Queued<T> {
+    pub value: T,
+    pub executable_at: i64,
+}
+
+/// `InitSpace` cannot size a generic over primitives, so each queued type is sized here
+macro_rules! impl_queued_space {
+    ($($ty:ty => $len:expr),* $(,)?) => {
+        $(impl Space for Queued<$ty> {
+            const INIT_SPACE: usize = $len + 8;
+        })*
+    };
+}
+
+impl_queued_space!(
+    bool => 1,
+    i64 => 8,
+    u64 => 8,
+    Pubkey => 32,
+    FeeSchedule => FeeSchedule::INIT_SPACE,
+);
+
+/// Treasury fee rates, set through `update_fee_schedule` and snapshotted by each arena at creation
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, InitSpace)]
+pub struct FeeSchedule {
+    /// Fee when no override matches the player count, in bps of the pool
+    pub base_fee_bps: u64,
+    /// Fees for specific player counts; slots with a zero player count are unused
+    pub player_count_fees: [PlayerCountFee; MAX_FEE_OVERRIDES],
+    /// Ceiling on the fee whatever the base rate or overrides say, in bps
+    pub max_fee_bps: u64,
+    /// Arenas created within `[promo_start, promo_end)` charge no fee
+    pub promo_start: i64,
+    pub promo_end: i64,
+}
+
+/// Fee override for arenas that end with exactly `player_count` players
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, InitSpace)]
+pub struct PlayerCountFee {
+    pub player_count: u8,
+    pub fee_bps: u64,
+}
+
+impl FeeSchedule {
+    /// Same fee for every arena, with no overrides or promotion
+    pub fn flat(fee_bps: u64) -> Self {
+        Self {
+            base_fee_bps: fee_bps,
+            max_fee_bps: fee_bps,
+            ..Default::default()
+        }
+    }
+
+    /// Copy stored on an arena created at `now`; a running promotion caps its fee at zero
+    pub fn snapshot(&self, now: i64) -> Self {
+        let mut snapshot = *self;
+        if (self.promo_start..self.promo_end).contains(&now) {
+            snapshot.max_fee_bps = 0;
+        }
+        snapshot
+    }
+
+    /// Fee in bps for an arena ending with `player_count` players
+    pub fn fee_bps(&self, player_count: u8) -> u64 {
+        self.player_count_fees
+            .iter()
+            .find(|fee| fee.player_count != 0 && fee.player_count == player_count)
+            .map_or(self.base_fee_bps, |fee| fee.fee_bps)
+            .min(self.max_fee_bps)
+    }
+
+    /// Rates fit in a pool, overrides name reachable player counts and the promotion window is ordered
+    pub fn is_valid(&self) -> bool {
+        self.max_fee_bps <= 10000
+            && self.promo_start <= self.promo_end
+            && self
+                .player_count_fees
+                .iter()
+                .all(|fee| fee.player_count <= MAX_PLAYERS_PER_ARENA)
+    }
+}
+
+impl GlobalState {
+    /// Id the next private arena will take
+    pub fn next_private_arena_id(&self) -> u64 {
+        PRIVATE_ARENA_ID_OFFSET + self.private_arena_count
+    }
+}
+
 /// ArenaVault - PDA that holds SOL for an arena
 #[account]
 #[derive(InitSpace)]
@@ -899,6 +2018,57 @@ pub struct Arena {
     // Player addresses in order
     #[max_len(10)]
     pub player_addresses: [Pubkey; 10],
+    // Creator of a private arena (default for public arenas)
+    pub creator: Pubkey,
+    // Who may enter, as `AccessPolicy`
+    pub access_policy: u8,
+    // sha256 of the invite secret for `InviteCode` arenas
+    pub invite_hash: [u8; 32],
+    // Paid the arena and vault rent: the first entrant of a public arena, the creator of a private one
+    pub rent_payer: Pubkey,
+    // Player entries not yet closed
+    pub open_entries: u8,
+    // Claims and refunds still owed; the arena can only close at zero
+    pub outstanding_claims: u8,
+    // Global fee schedule as it stood when the arena was created; later changes never apply
+    pub fee_schedule: FeeSchedule,
+    // Treasury fee rate resolved from the schedule once the arena ends, in bps
+    pub treasury_fee_bps: u64,
+    // Referral share snapshotted with the fee schedule, in bps of the treasury fee
+    pub referral_share_bps: u64,
+    // Entry fees paid by referred players
+    pub referred_amount: u64,
+    // Lamports of the treasury fee reserved for referrers once the arena ends
+    pub referral_fees: u64,
+    // Entries whose referral fee is owed if the arena ends with referral fees
+    pub referred_entries: u8,
+}
+
+impl Arena {
+    /// Ended and canceled arenas only pay out what they owe
+    pub fn is_finished(&self) -> bool {
+        self.status == ArenaStatus::Ended as u8 || self.status == ArenaStatus::Canceled as u8
+    }
+
+    /// Referrer's cut of the treasury fee on `amount` of entry fees, at the resolved rate
+    pub fn referral_fee(&self, amount: u64) -> u64 {
+        (amount * self.treasury_fee_bps / 10000) * self.referral_share_bps / 10000
+    }
+
+    /// Lamports owed to the treasury once the arena has ended, after the referrers' cuts
+    pub fn treasury_fee(&self) -> u64 {
+        (self.total_pool * self.treasury_fee_bps) / 10000 - self.referral_fees
+    }
+}
+
+/// ArenaAllowlist - Players allowed into an allowlist arena
+#[account]
+#[derive(InitSpace)]
+pub struct ArenaAllowlist {
+    pub arena: Pubkey,
+    #[max_len(MAX_ALLOWLIST_SIZE)]
+    pub players: Vec<Pubkey>,
+    pub bump: u8,
 }
 
 /// PlayerEntry - One per player in an arena
@@ -916,6 +2086,104 @@ pub struct PlayerEntry {
     pub price_movement: i64,
     pub is_winner: bool,
     pub has_claimed: bool,
+    pub referrer: Pubkey,         // Referrer bound to the player when they entered
+    pub referral_credited: bool,  // Referrer's cut of this entry's treasury fee has been credited
+    pub bump: u8,
+}
+
+/// PlayerProfile - Lifetime stats of one wallet across every arena it entered
+#[account]
+#[derive(InitSpace)]
+pub struct PlayerProfile {
+    pub player: Pubkey,
+    pub arenas_played: u32,
+    pub wins: u32,
+    pub losses: u32,              // Recorded when the arena ends
+    pub ties: u32,                // Refunds from canceled (tied) arenas
+    pub total_staked: u64,        // Entry fees paid, in lamports
+    pub total_won: u64,           // Winner rewards claimed, in lamports
+    pub current_streak: u32,
+    pub best_streak: u32,
+    pub streak_timestamp: i64,    // End of the latest arena counted into the streak
+    pub asset_entries: [u16; MAX_ASSET_INDICES], // Entries per asset index
+    pub favorite_asset: u8,       // Most entered asset (255 = none yet)
+    pub bump: u8,
+}
+
+impl PlayerProfile {
+    pub fn record_entry(&mut self, asset_index: u8, entry_fee: u64) {
+        self.arenas_played += 1;
+        self.total_staked += entry_fee;
+        self.asset_entries[asset_index as usize] += 1;
+        self.refresh_favorite_asset();
+    }
+
+    /// Leaving a waiting arena does not count as playing it
+    pub fn undo_entry(&mut self, asset_index: u8, entry_fee: u64) {
+        self.arenas_played -= 1;
+        self.total_staked -= entry_fee;
+        self.asset_entries[asset_index as usize] -= 1;
+        self.refresh_favorite_asset();
+    }
+
+    pub fn record_win(&mut self, reward: u64, end_timestamp: i64) {
+        self.wins += 1;
+        self.total_won += reward;
+        if self.advance_streak(end_timestamp) {
+            self.current_streak += 1;
+            self.best_streak = self.best_streak.max(self.current_streak);
+        }
+    }
+
+    pub fn record_loss(&mut self, end_timestamp: i64) {
+        self.losses += 1;
+        if self.advance_streak(end_timestamp) {
+            self.current_streak = 0;
+        }
+    }
+
+    /// Wins are recorded on claim, which can come after a later arena ended; only a result
+    /// from an arena ending no earlier than the last one counted moves the streak
+    fn advance_streak(&mut self, end_timestamp: i64) -> bool {
+        if end_timestamp < self.streak_timestamp {
+            return false;
+        }
+        self.streak_timestamp = end_timestamp;
+        true
+    }
+
+    /// Ties leave the streak untouched
+    pub fn record_refund(&mut self) {
+        self.ties += 1;
+    }
+
+    fn refresh_favorite_asset(&mut self) {
+        self.favorite_asset = self
+            .asset_entries
+            .iter()
+            .enumerate()
+            .filter(|(_, entries)| **entries > 0)
+            .max_by_key(|(asset_idx, entries)| (**entries, std::cmp::Reverse(*asset_idx)))
+            .map_or(255, |(asset_idx, _)| asset_idx as u8);
+    }
+}
+
+/// Referral - Referrer bound to a player on their first entry; never changes afterwards
+#[account]
+#[derive(InitSpace)]
+pub struct Referral {
+    pub player: Pubkey,
+    pub referrer: Pubkey,         // Default when the player joined without a referrer
+    pub bump: u8,
+}
+
+/// ReferrerBalance - Referral fees a referrer has accrued; holds the lamports it owes
+#[account]
+#[derive(InitSpace)]
+pub struct ReferrerBalance {
+    pub referrer: Pubkey,
+    pub balance: u64,             // Claimable lamports
+    pub total_earned: u64,
     pub bump: u8,
 }
 
@@ -934,6 +2202,149 @@ pub struct WhitelistedToken {
     pub symbol: [u8; 10],         // Token symbol (e.g., "PYTH", "ETH")
 }
 
+// ============================================================================
+// EVENTS
+// ============================================================================
+
+/// Emitted when a token is whitelisted or removed from the whitelist
+#[event]
+pub struct TokenWhitelistUpdated {
+    pub asset_index: u8,
+    pub chain_type: u8,
+    pub token_address: [u8; 32],
+    pub symbol: [u8; 10],
+    pub is_active: bool,
+}
+
+#[event]
+pub struct ArenaCreated {
+    pub arena: Pubkey,
+    pub arena_id: u64,
+    pub creator: Pubkey, // Default for public arenas
+    pub access_policy: u8,
+}
+
+#[event]
+pub struct PlayerEntered {
+    pub arena: Pubkey,
+    pub arena_id: u64,
+    pub player: Pubkey,
+    pub asset_index: u8,
+    pub player_index: u8,
+    pub entry_fee: u64,
+    pub player_count: u8,
+    pub total_pool: u64,
+}
+
+#[event]
+pub struct PlayerLeft {
+    pub arena: Pubkey,
+    pub arena_id: u64,
+    pub player: Pubkey,
+    pub asset_index: u8,
+    pub refund_amount: u64,
+    pub player_count: u8,
+}
+
+#[event]
+pub struct ArenaStarted {
+    pub arena: Pubkey,
+    pub arena_id: u64,
+    pub player_count: u8,
+    pub total_pool: u64,
+    pub start_timestamp: i64,
+    pub end_timestamp: i64,
+}
+
+/// Emitted by `set_start_price` and `set_end_price`; movement is only meaningful for end prices
+#[event]
+pub struct PriceSet {
+    pub arena: Pubkey,
+    pub arena_id: u64,
+    pub player: Pubkey,
+    pub asset_index: u8,
+    pub price: u64,
+    pub is_start: bool,
+    pub price_movement: i64,
+}
+
+/// Emitted by `end_arena`; a tie leaves the arena `Canceled` with no winner
+#[event]
+pub struct ArenaEnded {
+    pub arena: Pubkey,
+    pub arena_id: u64,
+    pub status: u8,
+    pub winning_asset: u8,
+    pub winning_movement: i64,
+    pub total_pool: u64,
+    pub treasury_fee_bps: u64, // Rate resolved from the arena's fee schedule; 0 if canceled
+}
+
+#[event]
+pub struct WinnerRewardClaimed {
+    pub arena: Pubkey,
+    pub arena_id: u64,
+    pub player: Pubkey,
+    pub asset_index: u8,
+    pub amount: u64,
+}
+
+#[event]
+pub struct TreasuryFeeClaimed {
+    pub arena: Pubkey,
+    pub arena_id: u64,
+    pub treasury_wallet: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct RefundClaimed {
+    pub arena: Pubkey,
+    pub arena_id: u64,
+    pub player: Pubkey,
+    pub asset_index: u8,
+    pub amount: u64,
+}
+
+#[event]
+pub struct ReferralBound {
+    pub player: Pubkey,
+    pub referrer: Pubkey,
+}
+
+#[event]
+pub struct ReferralCredited {
+    pub arena: Pubkey,
+    pub arena_id: u64,
+    pub player: Pubkey,
+    pub referrer: Pubkey,
+    pub amount: u64,
+    pub balance: u64,
+}
+
+#[event]
+pub struct ReferralRewardsClaimed {
+    pub referrer: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct PlayerEntryClosed {
+    pub arena: Pubkey,
+    pub arena_id: u64,
+    pub player: Pubkey,
+}
+
+/// Emitted by `close_arena`; `vault_lamports` (rent plus any dust) goes to the rent payer
+#[event]
+pub struct ArenaClosed {
+    pub arena: Pubkey,
+    pub arena_id: u64,
+    pub status: u8,
+    pub rent_payer: Pubkey,
+    pub vault_lamports: u64,
+}
+
 // ============================================================================
 // ENUMS & ERRORS
 // ============================================================================
@@ -947,6 +2358,13 @@ pub enum ArenaStatus {
     Canceled = 4,
 }
 
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum AccessPolicy {
+    Open = 0,
+    Allowlist = 1,
+    InviteCode = 2,
+}
+
 #[error_code]
 pub enum CryptarenaError {
     #[msg("Protocol is paused")]
@@ -979,6 +2397,12 @@ pub enum CryptarenaError {
     InvalidDuration,
     #[msg("Invalid entry fee - must be greater than 0")]
     InvalidEntryFee,
+    #[msg("Settings timelock cannot be negative")]
+    InvalidTimelock,
+    #[msg("No pending settings change")]
+    NoPendingSettings,
+    #[msg("Settings timelock has not elapsed")]
+    TimelockNotElapsed,
     #[msg("Token is not whitelisted")]
     TokenNotWhitelisted,
     #[msg("Asset index does not match whitelisted token")]
@@ -989,5 +2413,33 @@ pub enum CryptarenaError {
     InvalidTreasury,
     #[msg("Invalid chain type - must be 0 (Solana) or 1 (EVM)")]
     InvalidChainType,
+    #[msg("Entry of the last player must be passed to fill the vacated slot")]
+    MissingMovedEntry,
+    #[msg("Player is not on the arena's allowlist")]
+    NotInvited,
+    #[msg("Invite secret does not match the arena")]
+    InvalidInviteSecret,
+    #[msg("Arena does not use this access policy")]
+    InvalidAccessPolicy,
+    #[msg("Allowlist exceeds the maximum size")]
+    AllowlistTooLong,
+    #[msg("Arena has not finished")]
+    ArenaNotFinished,
+    #[msg("Arena still has outstanding claims")]
+    OutstandingClaims,
+    #[msg("Player entry still has an unclaimed reward or refund")]
+    EntryNotSettled,
+    #[msg("Arena still has open player entries")]
+    EntriesStillOpen,
+    #[msg("Referral share cannot exceed 10000 bps")]
+    InvalidReferralShare,
+    #[msg("Players cannot refer themselves")]
+    SelfReferral,
+    #[msg("No referral fee owed")]
+    NoReferralFee,
+    #[msg("Fee schedule is invalid")]
+    InvalidFeeSchedule,
+    #[msg("Remaining accounts must be a (PlayerEntry, PlayerProfile) pair per player")]
+    InvalidRemainingAccounts,
 }
 
@@ -7,12 +7,15 @@ declare_id!("2LsREShXRB5GMera37czrEKwe5xt9FUnKAjwpW183ce9");
 // CONSTANTS
 // ============================================================================
 
-pub const TREASURY_FEE_BPS: u64 = 1000; // 10%
+pub const DEFAULT_TREASURY_FEE_BPS: u64 = 1000; // 10%, flat until `update_fee_schedule` sets a schedule
+pub const MAX_FEE_OVERRIDES: usize = 4;
 pub const MIN_USD_ENTRY: u64 = 10_000_000; // $10 (6 decimals)
 pub const MAX_USD_ENTRY: u64 = 20_000_000; // $20 (6 decimals)
 pub const DEFAULT_ARENA_DURATION: i64 = 60; // 1 minute for testing
 pub const MAX_SAME_ASSET_PER_ARENA: u8 = 3;
 pub const MAX_PLAYERS_PER_ARENA: u8 = 10; // Can be increased to 100+
+pub const MAX_ASSET_INDICES: usize = 256; // Whitelisted tokens carry a u8 asset index
+pub const DEFAULT_SETTINGS_TIMELOCK: i64 = 0; // Raise with `update_settings_timelock` once the protocol is live
 
 // ============================================================================
 // PROGRAM
@@ -34,6 +37,10 @@ pub mod cryptarena_svm_test {
         global_state.current_arena_id = 0;
         global_state.max_players_per_arena = MAX_PLAYERS_PER_ARENA;
         global_state.max_same_asset = MAX_SAME_ASSET_PER_ARENA;
+        global_state.fee_schedule = FeeSchedule::flat(DEFAULT_TREASURY_FEE_BPS);
+        global_state.pending_admin = None;
+        global_state.settings_timelock = DEFAULT_SETTINGS_TIMELOCK;
+        global_state.pending_settings = None;
         global_state.is_paused = false;
         global_state.bump = ctx.bumps.global_state;
         
@@ -42,7 +49,7 @@ pub mod cryptarena_svm_test {
         Ok(())
     }
 
-    /// Update treasury wallet (admin only)
+    /// Queue a treasury wallet change (admin only)
     pub fn update_treasury_wallet(
         ctx: Context<AdminOnly>,
         new_treasury: Pubkey,
@@ -51,12 +58,14 @@ pub mod cryptarena_svm_test {
             ctx.accounts.admin.key() == ctx.accounts.global_state.admin,
             CryptarenaError::Unauthorized
         );
-        ctx.accounts.global_state.treasury_wallet = new_treasury;
-        msg!("Treasury updated to {}", new_treasury);
+        queue_settings(&mut ctx.accounts.global_state, |pending, executable_at| {
+            pending.treasury_wallet = Some(Queued { value: new_treasury, executable_at });
+        })?;
+        msg!("Treasury change to {} queued", new_treasury);
         Ok(())
     }
 
-    /// Update max players per arena (admin only)
+    /// Queue a max players per arena change (admin only)
     pub fn update_max_players(
         ctx: Context<AdminOnly>,
         max_players: u8,
@@ -65,12 +74,14 @@ pub mod cryptarena_svm_test {
             ctx.accounts.admin.key() == ctx.accounts.global_state.admin,
             CryptarenaError::Unauthorized
         );
-        ctx.accounts.global_state.max_players_per_arena = max_players;
-        msg!("Max players updated to {}", max_players);
+        queue_settings(&mut ctx.accounts.global_state, |pending, executable_at| {
+            pending.max_players_per_arena = Some(Queued { value: max_players, executable_at });
+        })?;
+        msg!("Max players change to {} queued", max_players);
         Ok(())
     }
 
-    /// Update arena duration (admin only)
+    /// Queue an arena duration change (admin only)
     pub fn update_arena_duration(
         ctx: Context<AdminOnly>,
         arena_duration: i64,
@@ -83,8 +94,131 @@ pub mod cryptarena_svm_test {
             arena_duration > 0,
             CryptarenaError::InvalidDuration
         );
-        ctx.accounts.global_state.arena_duration = arena_duration;
-        msg!("Arena duration updated to {} seconds", arena_duration);
+        queue_settings(&mut ctx.accounts.global_state, |pending, executable_at| {
+            pending.arena_duration = Some(Queued { value: arena_duration, executable_at });
+        })?;
+        msg!("Arena duration change to {} seconds queued", arena_duration);
+        Ok(())
+    }
+
+    /// Queue a new treasury fee schedule (admin only)
+    /// Only arenas created after it takes effect use it; running arenas keep their snapshot.
+    pub fn update_fee_schedule(
+        ctx: Context<AdminOnly>,
+        fee_schedule: FeeSchedule,
+    ) -> Result<()> {
+        require!(
+            ctx.accounts.admin.key() == ctx.accounts.global_state.admin,
+            CryptarenaError::Unauthorized
+        );
+        require!(
+            fee_schedule.is_valid(),
+            CryptarenaError::InvalidFeeSchedule
+        );
+        queue_settings(&mut ctx.accounts.global_state, |pending, executable_at| {
+            pending.fee_schedule = Some(Queued { value: fee_schedule, executable_at });
+        })?;
+        msg!("Fee schedule change to {} bps base, {} bps cap queued",
+            fee_schedule.base_fee_bps, fee_schedule.max_fee_bps);
+        Ok(())
+    }
+
+    /// Queue a change to the settings timelock itself (admin only)
+    pub fn update_settings_timelock(
+        ctx: Context<AdminOnly>,
+        settings_timelock: i64,
+    ) -> Result<()> {
+        require!(
+            ctx.accounts.admin.key() == ctx.accounts.global_state.admin,
+            CryptarenaError::Unauthorized
+        );
+        require!(
+            settings_timelock >= 0,
+            CryptarenaError::InvalidTimelock
+        );
+        queue_settings(&mut ctx.accounts.global_state, |pending, executable_at| {
+            pending.settings_timelock = Some(Queued { value: settings_timelock, executable_at });
+        })?;
+        msg!("Settings timelock change to {} seconds queued", settings_timelock);
+        Ok(())
+    }
+
+    /// Apply the queued settings changes whose timelock has elapsed (admin only)
+    /// Changes still inside their own timelock stay queued.
+    pub fn execute_settings(ctx: Context<AdminOnly>) -> Result<()> {
+        require!(
+            ctx.accounts.admin.key() == ctx.accounts.global_state.admin,
+            CryptarenaError::Unauthorized
+        );
+        let global_state = &mut ctx.accounts.global_state;
+        let clock = Clock::get()?;
+
+        let mut pending = global_state
+            .pending_settings
+            .ok_or(CryptarenaError::NoPendingSettings)?;
+        let ready = pending.take_ready(clock.unix_timestamp);
+        require!(!ready.is_empty(), CryptarenaError::TimelockNotElapsed);
+
+        if let Some(treasury_wallet) = ready.treasury_wallet {
+            global_state.treasury_wallet = treasury_wallet.value;
+        }
+        if let Some(max_players) = ready.max_players_per_arena {
+            global_state.max_players_per_arena = max_players.value;
+        }
+        if let Some(arena_duration) = ready.arena_duration {
+            global_state.arena_duration = arena_duration.value;
+        }
+        if let Some(settings_timelock) = ready.settings_timelock {
+            global_state.settings_timelock = settings_timelock.value;
+        }
+        if let Some(fee_schedule) = ready.fee_schedule {
+            global_state.fee_schedule = fee_schedule.value;
+        }
+        global_state.pending_settings = (!pending.is_empty()).then_some(pending);
+
+        msg!("Settings updated");
+        Ok(())
+    }
+
+    /// Discard the queued settings changes (admin only)
+    pub fn cancel_settings(ctx: Context<AdminOnly>) -> Result<()> {
+        require!(
+            ctx.accounts.admin.key() == ctx.accounts.global_state.admin,
+            CryptarenaError::Unauthorized
+        );
+        require!(
+            ctx.accounts.global_state.pending_settings.is_some(),
+            CryptarenaError::NoPendingSettings
+        );
+        ctx.accounts.global_state.pending_settings = None;
+        msg!("Pending settings changes canceled");
+        Ok(())
+    }
+
+    /// Nominate a new admin, who takes over by calling `accept_admin` (admin only)
+    pub fn propose_admin(
+        ctx: Context<AdminOnly>,
+        new_admin: Pubkey,
+    ) -> Result<()> {
+        require!(
+            ctx.accounts.admin.key() == ctx.accounts.global_state.admin,
+            CryptarenaError::Unauthorized
+        );
+        ctx.accounts.global_state.pending_admin = Some(new_admin);
+        msg!("Admin transfer proposed to {}", new_admin);
+        Ok(())
+    }
+
+    /// Accept a proposed admin transfer (pending admin only)
+    pub fn accept_admin(ctx: Context<AcceptAdmin>) -> Result<()> {
+        let global_state = &mut ctx.accounts.global_state;
+        require!(
+            global_state.pending_admin == Some(ctx.accounts.new_admin.key()),
+            CryptarenaError::Unauthorized
+        );
+        global_state.admin = ctx.accounts.new_admin.key();
+        global_state.pending_admin = None;
+        msg!("Admin transferred to {}", global_state.admin);
         Ok(())
     }
 
@@ -104,6 +238,11 @@ pub mod cryptarena_svm_test {
         whitelisted_token.is_active = true;
         whitelisted_token.bump = ctx.bumps.whitelisted_token;
         
+        emit_cpi!(TokenWhitelistUpdated {
+            mint: whitelisted_token.mint,
+            asset_index,
+            is_active: true,
+        });
         msg!("Token {} whitelisted at index {}", ctx.accounts.token_mint.key(), asset_index);
         Ok(())
     }
@@ -118,6 +257,11 @@ pub mod cryptarena_svm_test {
         );
         
         ctx.accounts.whitelisted_token.is_active = false;
+        emit_cpi!(TokenWhitelistUpdated {
+            mint: ctx.accounts.whitelisted_token.mint,
+            asset_index: ctx.accounts.whitelisted_token.asset_index,
+            is_active: false,
+        });
         msg!("Token {} removed from whitelist", ctx.accounts.whitelisted_token.mint);
         Ok(())
     }
@@ -142,7 +286,20 @@ pub mod cryptarena_svm_test {
             arena.asset_count = 0;
             arena.total_pool = 0;
             arena.winning_asset = 255;
+            arena.rent_payer = ctx.accounts.player.key();
+            arena.open_entries = 0;
+            arena.open_assets = 0;
+            arena.outstanding_claims = 0;
+            arena.fee_schedule = global_state.fee_schedule.snapshot(clock.unix_timestamp);
+            arena.treasury_fee_bps = 0;
             arena.bump = ctx.bumps.arena;
+            emit_cpi!(ArenaCreated {
+                arena: arena.key(),
+                arena_id: arena.id,
+                max_players: global_state.max_players_per_arena,
+                duration: global_state.arena_duration,
+                created_timestamp: clock.unix_timestamp,
+            });
             msg!("New arena {} created", arena.id);
         }
 
@@ -171,8 +328,10 @@ pub mod cryptarena_svm_test {
             arena_asset.start_price = 0;
             arena_asset.end_price = 0;
             arena_asset.price_movement = 0;
+            arena_asset.rent_payer = ctx.accounts.player.key();
             arena_asset.bump = ctx.bumps.arena_asset;
             arena.asset_count += 1;
+            arena.open_assets += 1;
         }
 
         require!(
@@ -195,9 +354,19 @@ pub mod cryptarena_svm_test {
         player_entry.rewards_claimed_bitmap = 0;
         player_entry.bump = ctx.bumps.player_entry;
 
+        // Created on the wallet's first entry, then carried across arenas
+        let player_profile = &mut ctx.accounts.player_profile;
+        if player_profile.player == Pubkey::default() {
+            player_profile.player = ctx.accounts.player.key();
+            player_profile.favorite_asset = 255; // Unset
+            player_profile.bump = ctx.bumps.player_profile;
+        }
+        player_profile.record_entry(asset_index, usd_value);
+
         // Update counts
         arena_asset.player_count += 1;
         arena.player_count += 1;
+        arena.open_entries += 1;
         arena.total_pool += usd_value;
 
         // Transfer tokens to arena vault
@@ -211,6 +380,19 @@ pub mod cryptarena_svm_test {
         );
         token::transfer(transfer_ctx, amount)?;
 
+        emit_cpi!(PlayerEntered {
+            arena: arena.key(),
+            arena_id: arena.id,
+            player: player_entry.player,
+            asset_index,
+            mint: whitelisted_token.mint,
+            amount,
+            usd_value,
+            player_index: player_entry.player_index,
+            player_count: arena.player_count,
+            total_pool: arena.total_pool,
+        });
+
         msg!("Player {} entered arena {} | Asset: {} | Amount: {} | Players: {}/{}", 
             ctx.accounts.player.key(), arena.id, asset_index, amount,
             arena.player_count, global_state.max_players_per_arena);
@@ -219,6 +401,13 @@ pub mod cryptarena_svm_test {
         if arena.player_count >= global_state.max_players_per_arena {
             arena.status = ArenaStatus::Ready as u8;
             global_state.current_arena_id += 1;
+            emit_cpi!(ArenaReady {
+                arena: arena.key(),
+                arena_id: arena.id,
+                player_count: arena.player_count,
+                asset_count: arena.asset_count,
+                total_pool: arena.total_pool,
+            });
             msg!("Arena {} FULL! Status: READY", arena.id);
         }
 
@@ -254,6 +443,17 @@ pub mod cryptarena_svm_test {
         arena_asset.start_price = price;
         arena.prices_set += 1;
 
+        emit_cpi!(PriceSet {
+            arena: arena.key(),
+            arena_id: arena.id,
+            asset_index: arena_asset.asset_index,
+            price,
+            is_start: true,
+            price_movement: 0,
+            prices_set: arena.prices_set,
+            asset_count: arena.asset_count,
+        });
+
         msg!("Asset {} start price: {} | Prices set: {}/{}", 
             arena_asset.asset_index, price, arena.prices_set, arena.asset_count);
 
@@ -261,6 +461,12 @@ pub mod cryptarena_svm_test {
         if arena.prices_set >= arena.asset_count {
             arena.status = ArenaStatus::Active as u8;
             arena.end_timestamp = clock.unix_timestamp + global_state.arena_duration;
+            emit_cpi!(ArenaStarted {
+                arena: arena.key(),
+                arena_id: arena.id,
+                start_timestamp: arena.start_timestamp,
+                end_timestamp: arena.end_timestamp,
+            });
             msg!("Arena {} ACTIVE! Ends at {}", arena.id, arena.end_timestamp);
         }
 
@@ -309,6 +515,17 @@ pub mod cryptarena_svm_test {
         
         arena.end_prices_set += 1;
 
+        emit_cpi!(PriceSet {
+            arena: arena.key(),
+            arena_id: arena.id,
+            asset_index: arena_asset.asset_index,
+            price,
+            is_start: false,
+            price_movement: arena_asset.price_movement,
+            prices_set: arena.end_prices_set,
+            asset_count: arena.asset_count,
+        });
+
         msg!("Asset {} end price: {} | Movement: {}bps | End prices: {}/{}", 
             arena_asset.asset_index, price, arena_asset.price_movement,
             arena.end_prices_set, arena.asset_count);
@@ -340,6 +557,7 @@ pub mod cryptarena_svm_test {
         // Find winning asset from remaining_accounts
         let mut best_movement: i64 = i64::MIN;
         let mut winning_asset: u8 = 255;
+        let mut winner_count: u8 = 0;
         let mut tie_detected = false;
 
         for account_info in ctx.remaining_accounts.iter() {
@@ -348,6 +566,7 @@ pub mod cryptarena_svm_test {
             if data.len() >= 8 + 32 + 1 + 1 + 8 + 8 + 8 + 1 {
                 // Parse ArenaAsset data manually
                 let asset_index = data[8 + 32]; // After discriminator + arena pubkey
+                let player_count = data[8 + 32 + 1];
                 let movement_bytes: [u8; 8] = data[8 + 32 + 1 + 1 + 8 + 8..8 + 32 + 1 + 1 + 8 + 8 + 8]
                     .try_into()
                     .unwrap_or([0u8; 8]);
@@ -358,6 +577,7 @@ pub mod cryptarena_svm_test {
                 if movement > best_movement {
                     best_movement = movement;
                     winning_asset = asset_index;
+                    winner_count = player_count;
                     tie_detected = false;
                 } else if movement == best_movement && winning_asset != 255 {
                     tie_detected = true;
@@ -368,12 +588,40 @@ pub mod cryptarena_svm_test {
         if tie_detected {
             arena.is_suspended = true;
             arena.status = ArenaStatus::Suspended as u8;
+            emit_cpi!(ArenaFinalized {
+                arena: arena.key(),
+                arena_id: arena.id,
+                status: arena.status,
+                winning_asset: 255,
+                winning_movement: best_movement,
+                total_pool: arena.total_pool,
+                treasury_fee_bps: arena.treasury_fee_bps,
+            });
             msg!("Arena {} SUSPENDED due to tie!", arena.id);
             return Ok(());
         }
 
         arena.winning_asset = winning_asset;
         arena.status = ArenaStatus::Ended as u8;
+        // The fee rate comes from the arena's own schedule now that the player count is final
+        arena.treasury_fee_bps = arena.fee_schedule.fee_bps(arena.player_count);
+
+        // Each winner claims its own tokens and its share of every loser; treasury collects once per
+        // loser unless the arena charges no fee
+        let winners = winner_count as u16;
+        let losers = arena.player_count as u16 - winners;
+        let treasury_collections = if arena.treasury_fee_bps > 0 { losers } else { 0 };
+        arena.outstanding_claims = winners + winners * losers + treasury_collections;
+
+        emit_cpi!(ArenaFinalized {
+            arena: arena.key(),
+            arena_id: arena.id,
+            status: arena.status,
+            winning_asset,
+            winning_movement: best_movement,
+            total_pool: arena.total_pool,
+            treasury_fee_bps: arena.treasury_fee_bps,
+        });
 
         msg!("Arena {} ENDED! Winner: Asset {} with {}bps", 
             arena.id, winning_asset, best_movement);
@@ -382,7 +630,8 @@ pub mod cryptarena_svm_test {
 
     /// Winner claims their own tokens back (100%)
     pub fn claim_own_tokens(ctx: Context<ClaimOwnTokens>) -> Result<()> {
-        let arena = &ctx.accounts.arena;
+        let arena_info = ctx.accounts.arena.to_account_info();
+        let arena = &mut ctx.accounts.arena;
         let player_entry = &mut ctx.accounts.player_entry;
 
         require!(
@@ -412,7 +661,7 @@ pub mod cryptarena_svm_test {
             Transfer {
                 from: ctx.accounts.arena_vault.to_account_info(),
                 to: ctx.accounts.winner_token_account.to_account_info(),
-                authority: ctx.accounts.arena.to_account_info(),
+                authority: arena_info,
             },
             signer,
         );
@@ -420,16 +669,29 @@ pub mod cryptarena_svm_test {
 
         player_entry.own_tokens_claimed = true;
         player_entry.is_winner = true;
+        arena.outstanding_claims -= 1;
+        ctx.accounts.player_profile.record_win(player_entry.usd_value, arena.end_timestamp);
+
+        emit_cpi!(OwnTokensClaimed {
+            arena: arena.key(),
+            arena_id: arena.id,
+            player: player_entry.player,
+            asset_index: player_entry.asset_index,
+            mint: ctx.accounts.arena_vault.mint,
+            amount,
+        });
 
         msg!("Winner claimed {} own tokens", amount);
         Ok(())
     }
 
     /// Winner claims tokens from a loser (90% only - treasury claims separately)
+    /// The first claim against a loser records their loss, since the loser has nothing to claim
     pub fn claim_loser_tokens(ctx: Context<ClaimLoserTokens>) -> Result<()> {
-        let arena = &ctx.accounts.arena;
+        let arena_info = ctx.accounts.arena.to_account_info();
+        let arena = &mut ctx.accounts.arena;
         let winner_entry = &mut ctx.accounts.winner_entry;
-        let loser_entry = &ctx.accounts.loser_entry;
+        let loser_entry = &mut ctx.accounts.loser_entry;
         let arena_asset = &ctx.accounts.arena_asset;
 
         require!(
@@ -455,26 +717,26 @@ pub mod cryptarena_svm_test {
             CryptarenaError::RewardAlreadyClaimed
         );
 
-        // Calculate winner's share: 90% of (loser_amount / winner_count)
+        // Calculate winner's share: (loser_amount / winner_count) less the arena's treasury fee
         let winner_count = arena_asset.player_count as u64;
         let loser_amount = loser_entry.amount;
         let amount_per_winner = loser_amount / winner_count.max(1);
         
-        // Winner gets 90% (10% stays in vault for treasury)
-        let treasury_fee = (amount_per_winner * TREASURY_FEE_BPS) / 10000;
+        // The treasury fee stays in the vault for `collect_treasury_fee`
+        let treasury_fee = (amount_per_winner * arena.treasury_fee_bps) / 10000;
         let winner_reward = amount_per_winner - treasury_fee;
 
         let arena_id_bytes = arena.id.to_le_bytes();
         let seeds = &[b"arena_v2".as_ref(), arena_id_bytes.as_ref(), &[arena.bump]];
         let signer = &[&seeds[..]];
 
-        // Transfer 90% to winner (10% stays in arena vault for treasury)
+        // Transfer the winner's share (the treasury fee stays in the arena vault)
         let transfer_to_winner = CpiContext::new_with_signer(
             ctx.accounts.token_program.to_account_info(),
             Transfer {
                 from: ctx.accounts.arena_vault.to_account_info(),
                 to: ctx.accounts.winner_token_account.to_account_info(),
-                authority: ctx.accounts.arena.to_account_info(),
+                authority: arena_info,
             },
             signer,
         );
@@ -483,20 +745,43 @@ pub mod cryptarena_svm_test {
         // Mark as claimed by this winner
         winner_entry.rewards_claimed_bitmap |= loser_bit;
         winner_entry.is_winner = true;
+        arena.outstanding_claims -= 1;
+
+        // Value the claim in USD at entry so winnings add up across mints
+        let loser_usd_share = loser_entry.usd_value / winner_count.max(1);
+        let winner_usd = loser_usd_share - (loser_usd_share * arena.treasury_fee_bps) / 10000;
+        ctx.accounts.player_profile.total_won += winner_usd;
+
+        if !loser_entry.loss_recorded {
+            loser_entry.loss_recorded = true;
+            ctx.accounts.loser_profile.record_loss(arena.end_timestamp);
+        }
+
+        emit_cpi!(LoserTokensClaimed {
+            arena: arena.key(),
+            arena_id: arena.id,
+            winner: winner_entry.player,
+            loser: loser_entry.player,
+            loser_index: loser_entry.player_index,
+            mint: ctx.accounts.arena_vault.mint,
+            amount: winner_reward,
+            treasury_fee,
+        });
 
         msg!("Winner claimed {} from loser {} (treasury fee {} in vault)", 
             winner_reward, loser_entry.player_index, treasury_fee);
         Ok(())
     }
 
-    /// Admin collects treasury fee from a loser (10%) - INDEPENDENT of winner claims
+    /// Admin collects the treasury fee from a loser at the arena's rate - INDEPENDENT of winner claims
     pub fn collect_treasury_fee(ctx: Context<CollectTreasuryFee>) -> Result<()> {
         require!(
             ctx.accounts.admin.key() == ctx.accounts.global_state.admin,
             CryptarenaError::Unauthorized
         );
 
-        let arena = &ctx.accounts.arena;
+        let arena_info = ctx.accounts.arena.to_account_info();
+        let arena = &mut ctx.accounts.arena;
         let loser_entry = &mut ctx.accounts.loser_entry;
 
         require!(
@@ -516,20 +801,26 @@ pub mod cryptarena_svm_test {
             CryptarenaError::TreasuryFeeAlreadyClaimed
         );
 
-        // Calculate treasury fee (10% of loser's tokens)
-        let treasury_fee = (loser_entry.amount * TREASURY_FEE_BPS) / 10000;
+        // Zero-fee arenas owe no collections, see `finalize_arena`
+        require!(
+            arena.treasury_fee_bps > 0,
+            CryptarenaError::NoTreasuryFee
+        );
+
+        // Calculate treasury fee on the loser's tokens
+        let treasury_fee = (loser_entry.amount * arena.treasury_fee_bps) / 10000;
 
         let arena_id_bytes = arena.id.to_le_bytes();
         let seeds = &[b"arena_v2".as_ref(), arena_id_bytes.as_ref(), &[arena.bump]];
         let signer = &[&seeds[..]];
 
-        // Transfer 10% from arena vault to treasury token account
+        // Transfer the fee from arena vault to treasury token account
         let transfer_ctx = CpiContext::new_with_signer(
             ctx.accounts.token_program.to_account_info(),
             Transfer {
                 from: ctx.accounts.arena_vault.to_account_info(),
                 to: ctx.accounts.treasury_token_account.to_account_info(),
-                authority: ctx.accounts.arena.to_account_info(),
+                authority: arena_info,
             },
             signer,
         );
@@ -537,10 +828,115 @@ pub mod cryptarena_svm_test {
 
         // Mark treasury fee as claimed for this loser
         loser_entry.treasury_fee_claimed = true;
+        arena.outstanding_claims -= 1;
+
+        emit_cpi!(TreasuryFeeCollected {
+            arena: arena.key(),
+            arena_id: arena.id,
+            loser: loser_entry.player,
+            loser_index: loser_entry.player_index,
+            mint: ctx.accounts.arena_vault.mint,
+            amount: treasury_fee,
+            treasury_token_account: ctx.accounts.treasury_token_account.key(),
+        });
 
         msg!("Treasury collected {} from loser {}", treasury_fee, loser_entry.player_index);
         Ok(())
     }
+
+    /// Close a player entry once every claim of the arena is done, returning rent to the player
+    /// Permissionless so a crank can sweep finished arenas
+    pub fn close_player_entry(ctx: Context<ClosePlayerEntry>) -> Result<()> {
+        let arena = &mut ctx.accounts.arena;
+
+        require!(
+            arena.status == ArenaStatus::Ended as u8,
+            CryptarenaError::ArenaNotEnded
+        );
+        require!(
+            arena.outstanding_claims == 0,
+            CryptarenaError::OutstandingClaims
+        );
+
+        arena.open_entries -= 1;
+
+        emit_cpi!(PlayerEntryClosed {
+            arena: arena.key(),
+            arena_id: arena.id,
+            player: ctx.accounts.player_entry.player,
+        });
+
+        msg!("Closed entry of player {} in arena {}", ctx.accounts.player_entry.player, arena.id);
+        Ok(())
+    }
+
+    /// Close an arena asset once every claim of the arena is done, returning rent to its first entrant
+    pub fn close_arena_asset(ctx: Context<CloseArenaAsset>) -> Result<()> {
+        let arena = &mut ctx.accounts.arena;
+
+        require!(
+            arena.status == ArenaStatus::Ended as u8,
+            CryptarenaError::ArenaNotEnded
+        );
+        require!(
+            arena.outstanding_claims == 0,
+            CryptarenaError::OutstandingClaims
+        );
+
+        arena.open_assets -= 1;
+
+        emit_cpi!(ArenaAssetClosed {
+            arena: arena.key(),
+            arena_id: arena.id,
+            asset_index: ctx.accounts.arena_asset.asset_index,
+            rent_payer: ctx.accounts.rent_payer.key(),
+        });
+
+        msg!("Closed asset {} of arena {}", ctx.accounts.arena_asset.asset_index, arena.id);
+        Ok(())
+    }
+
+    /// Close an ended arena after its entries and assets, returning rent to its first entrant
+    pub fn close_arena(ctx: Context<CloseArena>) -> Result<()> {
+        let arena = &ctx.accounts.arena;
+
+        require!(
+            arena.status == ArenaStatus::Ended as u8,
+            CryptarenaError::ArenaNotEnded
+        );
+        require!(
+            arena.outstanding_claims == 0,
+            CryptarenaError::OutstandingClaims
+        );
+        require!(
+            arena.open_entries == 0 && arena.open_assets == 0,
+            CryptarenaError::ArenaAccountsOpen
+        );
+
+        emit_cpi!(ArenaClosed {
+            arena: arena.key(),
+            arena_id: arena.id,
+            rent_payer: arena.rent_payer,
+        });
+
+        msg!("Closed arena {} | Rent returned to {}", arena.id, arena.rent_payer);
+        Ok(())
+    }
+}
+
+// ============================================================================
+// HELPER FUNCTIONS
+// ============================================================================
+
+/// Merge a change into the pending settings; only the field it queues gets the new timelock
+fn queue_settings(
+    global_state: &mut GlobalState,
+    change: impl FnOnce(&mut PendingSettings, i64),
+) -> Result<()> {
+    let executable_at = Clock::get()?.unix_timestamp + global_state.settings_timelock;
+    let pending = global_state.pending_settings.get_or_insert_with(PendingSettings::default);
+    change(pending, executable_at);
+    Ok(())
 }
 
 // ============================================================================
@@ -575,6 +971,15 @@ pub struct AdminOnly<'info> {
     pub admin: Signer<'info>,
 }
 
+#[derive(Accounts)]
+pub struct AcceptAdmin<'info> {
+    #[account(mut, seeds = [b"global_state_v2"], bump = global_state.bump)]
+    pub global_state: Account<'info, GlobalState>,
+
+    pub new_admin: Signer<'info>,
+}
+
+#[event_cpi]
 #[derive(Accounts)]
 #[instruction(asset_index: u8)]
 pub struct AddWhitelistedToken<'info> {
@@ -599,6 +1004,7 @@ pub struct AddWhitelistedToken<'info> {
     pub system_program: Program<'info, System>,
 }
 
+#[event_cpi]
 #[derive(Accounts)]
 pub struct RemoveWhitelistedToken<'info> {
     #[account(seeds = [b"global_state_v2"], bump = global_state.bump)]
@@ -614,6 +1020,7 @@ pub struct RemoveWhitelistedToken<'info> {
     pub admin: Signer<'info>,
 }
 
+#[event_cpi]
 #[derive(Accounts)]
 #[instruction(asset_index: u8)]
 pub struct EnterArena<'info> {
@@ -647,6 +1054,15 @@ pub struct EnterArena<'info> {
     )]
     pub player_entry: Account<'info, PlayerEntry>,
 
+    #[account(
+        init_if_needed,
+        payer = player,
+        space = 8 + PlayerProfile::INIT_SPACE,
+        seeds = [b"player_profile_v2", player.key().as_ref()],
+        bump
+    )]
+    pub player_profile: Account<'info, PlayerProfile>,
+
     #[account(mut)]
     pub player_token_account: Account<'info, TokenAccount>,
 
@@ -667,6 +1083,7 @@ pub struct EnterArena<'info> {
     pub system_program: Program<'info, System>,
 }
 
+#[event_cpi]
 #[derive(Accounts)]
 pub struct SetPrice<'info> {
     #[account(seeds = [b"global_state_v2"], bump = global_state.bump)]
@@ -685,6 +1102,7 @@ pub struct SetPrice<'info> {
     pub admin: Signer<'info>,
 }
 
+#[event_cpi]
 #[derive(Accounts)]
 pub struct FinalizeArena<'info> {
     #[account(seeds = [b"global_state_v2"], bump = global_state.bump)]
@@ -697,9 +1115,10 @@ pub struct FinalizeArena<'info> {
     // ArenaAsset accounts passed as remaining_accounts
 }
 
+#[event_cpi]
 #[derive(Accounts)]
 pub struct ClaimOwnTokens<'info> {
-    #[account(seeds = [b"arena_v2", arena.id.to_le_bytes().as_ref()], bump = arena.bump)]
+    #[account(mut, seeds = [b"arena_v2", arena.id.to_le_bytes().as_ref()], bump = arena.bump)]
     pub arena: Account<'info, Arena>,
 
     #[account(
@@ -709,6 +1128,13 @@ pub struct ClaimOwnTokens<'info> {
     )]
     pub player_entry: Account<'info, PlayerEntry>,
 
+    #[account(
+        mut,
+        seeds = [b"player_profile_v2", winner.key().as_ref()],
+        bump = player_profile.bump
+    )]
+    pub player_profile: Account<'info, PlayerProfile>,
+
     #[account(mut)]
     pub arena_vault: Account<'info, TokenAccount>,
 
@@ -720,12 +1146,13 @@ pub struct ClaimOwnTokens<'info> {
     pub token_program: Program<'info, Token>,
 }
 
+#[event_cpi]
 #[derive(Accounts)]
 pub struct ClaimLoserTokens<'info> {
     #[account(seeds = [b"global_state_v2"], bump = global_state.bump)]
     pub global_state: Account<'info, GlobalState>,
 
-    #[account(seeds = [b"arena_v2", arena.id.to_le_bytes().as_ref()], bump = arena.bump)]
+    #[account(mut, seeds = [b"arena_v2", arena.id.to_le_bytes().as_ref()], bump = arena.bump)]
     pub arena: Account<'info, Arena>,
 
     /// The winning asset's ArenaAsset (to get winner count)
@@ -742,13 +1169,28 @@ pub struct ClaimLoserTokens<'info> {
     )]
     pub winner_entry: Account<'info, PlayerEntry>,
 
+    #[account(
+        mut,
+        seeds = [b"player_profile_v2", winner.key().as_ref()],
+        bump = player_profile.bump
+    )]
+    pub player_profile: Account<'info, PlayerProfile>,
+
     /// Loser's player entry (to get their amount)
     #[account(
+        mut,
         seeds = [b"player_entry_v2", arena.key().as_ref(), loser_entry.player.as_ref()],
         bump = loser_entry.bump,
     )]
     pub loser_entry: Account<'info, PlayerEntry>,
 
+    #[account(
+        mut,
+        seeds = [b"player_profile_v2", loser_entry.player.as_ref()],
+        bump = loser_profile.bump
+    )]
+    pub loser_profile: Account<'info, PlayerProfile>,
+
     #[account(mut)]
     pub arena_vault: Account<'info, TokenAccount>,
 
@@ -760,12 +1202,13 @@ pub struct ClaimLoserTokens<'info> {
     pub token_program: Program<'info, Token>,
 }
 
+#[event_cpi]
 #[derive(Accounts)]
 pub struct CollectTreasuryFee<'info> {
     #[account(seeds = [b"global_state_v2"], bump = global_state.bump)]
     pub global_state: Account<'info, GlobalState>,
 
-    #[account(seeds = [b"arena_v2", arena.id.to_le_bytes().as_ref()], bump = arena.bump)]
+    #[account(mut, seeds = [b"arena_v2", arena.id.to_le_bytes().as_ref()], bump = arena.bump)]
     pub arena: Account<'info, Arena>,
 
     /// Loser's player entry
@@ -789,6 +1232,69 @@ pub struct CollectTreasuryFee<'info> {
     pub token_program: Program<'info, Token>,
 }
 
+#[event_cpi]
+#[derive(Accounts)]
+pub struct ClosePlayerEntry<'info> {
+    #[account(mut, seeds = [b"arena_v2", arena.id.to_le_bytes().as_ref()], bump = arena.bump)]
+    pub arena: Account<'info, Arena>,
+
+    #[account(
+        mut,
+        close = player,
+        seeds = [b"player_entry_v2", arena.key().as_ref(), player.key().as_ref()],
+        bump = player_entry.bump,
+        has_one = player
+    )]
+    pub player_entry: Account<'info, PlayerEntry>,
+
+    /// CHECK: The entry's player, who paid its rent
+    #[account(mut)]
+    pub player: UncheckedAccount<'info>,
+
+    pub caller: Signer<'info>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct CloseArenaAsset<'info> {
+    #[account(mut, seeds = [b"arena_v2", arena.id.to_le_bytes().as_ref()], bump = arena.bump)]
+    pub arena: Account<'info, Arena>,
+
+    #[account(
+        mut,
+        close = rent_payer,
+        seeds = [b"arena_asset_v2", arena.key().as_ref(), &[arena_asset.asset_index]],
+        bump = arena_asset.bump,
+        has_one = rent_payer
+    )]
+    pub arena_asset: Account<'info, ArenaAsset>,
+
+    /// CHECK: First entrant on the asset, who paid its rent
+    #[account(mut)]
+    pub rent_payer: UncheckedAccount<'info>,
+
+    pub caller: Signer<'info>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct CloseArena<'info> {
+    #[account(
+        mut,
+        close = rent_payer,
+        seeds = [b"arena_v2", arena.id.to_le_bytes().as_ref()],
+        bump = arena.bump,
+        has_one = rent_payer
+    )]
+    pub arena: Account<'info, Arena>,
+
+    /// CHECK: First entrant of the arena, who paid its rent
+    #[account(mut)]
+    pub rent_payer: UncheckedAccount<'info>,
+
+    pub caller: Signer<'info>,
+}
+
 // ============================================================================
 // STATE - All small, scalable accounts
 // ============================================================================
@@ -802,11 +1308,130 @@ pub struct GlobalState {
     pub current_arena_id: u64,
     pub max_players_per_arena: u8,
     pub max_same_asset: u8,
+    /// Treasury fee rates new arenas snapshot when they are created
+    pub fee_schedule: FeeSchedule,
+    /// Admin nominated by `propose_admin`, waiting to accept
+    pub pending_admin: Option<Pubkey>,
+    /// Seconds queued settings changes wait before they can be executed
+    pub settings_timelock: i64,
+    pub pending_settings: Option<PendingSettings>,
     pub is_paused: bool,
     pub bump: u8,
 }
 
-/// Arena - Lightweight metadata only (~100 bytes)
+/// Settings changes queued by the admin setters, each with its own timelock; `None` fields are left unchanged
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, InitSpace)]
+pub struct PendingSettings {
+    pub treasury_wallet: Option<Queued<Pubkey>>,
+    pub max_players_per_arena: Option<Queued<u8>>,
+    pub arena_duration: Option<Queued<i64>>,
+    pub settings_timelock: Option<Queued<i64>>,
+    pub fee_schedule: Option<Queued<FeeSchedule>>,
+}
+
+impl PendingSettings {
+    /// Take out the changes whose timelock has elapsed, leaving the rest queued
+    fn take_ready(&mut self, now: i64) -> PendingSettings {
+        fn take<T: Copy>(slot: &mut Option<Queued<T>>, now: i64) -> Option<Queued<T>> {
+            match slot {
+                Some(queued) if now >= queued.executable_at => slot.take(),
+                _ => None,
+            }
+        }
+
+        PendingSettings {
+            treasury_wallet: take(&mut self.treasury_wallet, now),
+            max_players_per_arena: take(&mut self.max_players_per_arena, now),
+            arena_duration: take(&mut self.arena_duration, now),
+            settings_timelock: take(&mut self.settings_timelock, now),
+            fee_schedule: take(&mut self.fee_schedule, now),
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.treasury_wallet.is_none()
+            && self.max_players_per_arena.is_none()
+            && self.arena_duration.is_none()
+            && self.settings_timelock.is_none()
+            && self.fee_schedule.is_none()
+    }
+}
+
+/// Queued change together with the time its own timelock elapses
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+pub struct Queued<T> {
+    pub value: T,
+    pub executable_at: i64,
+}
+
+/// `InitSpace` cannot size a generic over primitives, so each queued type is sized here
+macro_rules! impl_queued_space {
+    ($($ty:ty => $len:expr),* $(,)?) => {
+        $(impl Space for Queued<$ty> {
+            const INIT_SPACE: usize = $len + 8;
+        })*
+    };
+}
+
+impl_queued_space!(
+    u8 => 1,
+    i64 => 8,
+    Pubkey => 32,
+    FeeSchedule => FeeSchedule::INIT_SPACE,
+);
+
+/// FeeSchedule - Treasury fee rates, snapshotted by each arena at creation
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, InitSpace)]
+pub struct FeeSchedule {
+    pub base_fee_bps: u64,          // Fee when no override matches the player count
+    pub player_count_fees: [PlayerCountFee; MAX_FEE_OVERRIDES], // Zero player count = unused slot
+    pub max_fee_bps: u64,           // Ceiling on the fee whatever the base rate or overrides say
+    pub promo_start: i64,           // Arenas created within [promo_start, promo_end) charge no fee
+    pub promo_end: i64,
+}
+
+/// PlayerCountFee - Fee override for arenas that finalize with exactly `player_count` players
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, InitSpace)]
+pub struct PlayerCountFee {
+    pub player_count: u8,
+    pub fee_bps: u64,
+}
+
+impl FeeSchedule {
+    /// Same fee for every arena, with no overrides or promotion
+    pub fn flat(fee_bps: u64) -> Self {
+        Self {
+            base_fee_bps: fee_bps,
+            max_fee_bps: fee_bps,
+            ..Default::default()
+        }
+    }
+
+    /// Copy stored on an arena created at `now`; a running promotion caps its fee at zero
+    pub fn snapshot(&self, now: i64) -> Self {
+        let mut snapshot = *self;
+        if (self.promo_start..self.promo_end).contains(&now) {
+            snapshot.max_fee_bps = 0;
+        }
+        snapshot
+    }
+
+    /// Fee in bps for an arena finalizing with `player_count` players
+    pub fn fee_bps(&self, player_count: u8) -> u64 {
+        self.player_count_fees
+            .iter()
+            .find(|fee| fee.player_count != 0 && fee.player_count == player_count)
+            .map_or(self.base_fee_bps, |fee| fee.fee_bps)
+            .min(self.max_fee_bps)
+    }
+
+    /// Rates fit in a pool and the promotion window is ordered
+    pub fn is_valid(&self) -> bool {
+        self.max_fee_bps <= 10000 && self.promo_start <= self.promo_end
+    }
+}
+
+/// Arena - Lightweight metadata only (~150 bytes)
 #[account]
 #[derive(InitSpace)]
 pub struct Arena {
@@ -822,6 +1447,12 @@ pub struct Arena {
     pub start_timestamp: i64,
     pub end_timestamp: i64,
     pub total_pool: u64,
+    pub rent_payer: Pubkey,         // First entrant, paid the arena rent
+    pub open_entries: u8,           // PlayerEntry accounts not yet closed
+    pub open_assets: u8,            // ArenaAsset accounts not yet closed
+    pub outstanding_claims: u16,    // Claims and fee collections still owed after finalize
+    pub fee_schedule: FeeSchedule,  // Global fee schedule when the arena was created
+    pub treasury_fee_bps: u64,      // Fee rate resolved from the schedule at finalize
 }
 
 /// ArenaAsset - One per asset in arena (~80 bytes)
@@ -834,6 +1465,7 @@ pub struct ArenaAsset {
     pub start_price: u64,
     pub end_price: u64,
     pub price_movement: i64,
+    pub rent_payer: Pubkey, // First entrant on the asset, paid its rent
     pub bump: u8,
 }
 
@@ -850,11 +1482,71 @@ pub struct PlayerEntry {
     pub entry_timestamp: i64,
     pub is_winner: bool,
     pub own_tokens_claimed: bool,
-    pub treasury_fee_claimed: bool, // True when admin collected 10% from this loser
+    pub treasury_fee_claimed: bool, // True when admin collected the treasury fee from this loser
+    pub loss_recorded: bool,      // True once a winner's claim recorded this loser's loss
     pub rewards_claimed_bitmap: u128, // Supports up to 128 players
     pub bump: u8,
 }
 
+/// PlayerProfile - Lifetime stats of one wallet across every arena it entered (~590 bytes)
+#[account]
+#[derive(InitSpace)]
+pub struct PlayerProfile {
+    pub player: Pubkey,
+    pub arenas_played: u32,
+    pub wins: u32,
+    pub losses: u32,              // Recorded on the first claim of the loser's tokens
+    pub ties: u32,                // Suspended arenas have no payout path yet, so this stays 0
+    pub total_staked: u64,        // USD value entered (6 decimals)
+    pub total_won: u64,           // USD value of own stake plus loser shares claimed (6 decimals)
+    pub current_streak: u32,
+    pub best_streak: u32,
+    pub streak_timestamp: i64,    // End of the latest arena counted into the streak
+    pub asset_entries: [u16; MAX_ASSET_INDICES], // Entries per asset index
+    pub favorite_asset: u8,       // Most entered asset (255 = none yet)
+    pub bump: u8,
+}
+
+impl PlayerProfile {
+    pub fn record_entry(&mut self, asset_index: u8, usd_value: u64) {
+        self.arenas_played += 1;
+        self.total_staked += usd_value;
+        self.asset_entries[asset_index as usize] += 1;
+        self.favorite_asset = self
+            .asset_entries
+            .iter()
+            .enumerate()
+            .max_by_key(|(asset_idx, entries)| (**entries, std::cmp::Reverse(*asset_idx)))
+            .map_or(255, |(asset_idx, _)| asset_idx as u8);
+    }
+
+    pub fn record_win(&mut self, own_usd: u64, end_timestamp: i64) {
+        self.wins += 1;
+        self.total_won += own_usd;
+        if self.advance_streak(end_timestamp) {
+            self.current_streak += 1;
+            self.best_streak = self.best_streak.max(self.current_streak);
+        }
+    }
+
+    pub fn record_loss(&mut self, end_timestamp: i64) {
+        self.losses += 1;
+        if self.advance_streak(end_timestamp) {
+            self.current_streak = 0;
+        }
+    }
+
+    /// Results are recorded by claims in any order; only one from an arena ending no earlier
+    /// than the last one counted moves the streak
+    fn advance_streak(&mut self, end_timestamp: i64) -> bool {
+        if end_timestamp < self.streak_timestamp {
+            return false;
+        }
+        self.streak_timestamp = end_timestamp;
+        true
+    }
+}
+
 /// WhitelistedToken - Tokens allowed to enter arenas (~50 bytes)
 #[account]
 #[derive(InitSpace)]
@@ -866,6 +1558,135 @@ pub struct WhitelistedToken {
 }
 
 
+// ============================================================================
+// EVENTS
+// ============================================================================
+
+#[event]
+pub struct TokenWhitelistUpdated {
+    pub mint: Pubkey,
+    pub asset_index: u8,
+    pub is_active: bool,
+}
+
+#[event]
+pub struct ArenaCreated {
+    pub arena: Pubkey,
+    pub arena_id: u64,
+    pub max_players: u8,
+    pub duration: i64,
+    pub created_timestamp: i64,
+}
+
+#[event]
+pub struct PlayerEntered {
+    pub arena: Pubkey,
+    pub arena_id: u64,
+    pub player: Pubkey,
+    pub asset_index: u8,
+    pub mint: Pubkey,
+    pub amount: u64,
+    pub usd_value: u64,
+    pub player_index: u8,
+    pub player_count: u8,
+    pub total_pool: u64,
+}
+
+#[event]
+pub struct ArenaReady {
+    pub arena: Pubkey,
+    pub arena_id: u64,
+    pub player_count: u8,
+    pub asset_count: u8,
+    pub total_pool: u64,
+}
+
+#[event]
+pub struct PriceSet {
+    pub arena: Pubkey,
+    pub arena_id: u64,
+    pub asset_index: u8,
+    pub price: u64,
+    pub is_start: bool,
+    pub price_movement: i64, // Basis points, 0 for start prices
+    pub prices_set: u8,
+    pub asset_count: u8,
+}
+
+#[event]
+pub struct ArenaStarted {
+    pub arena: Pubkey,
+    pub arena_id: u64,
+    pub start_timestamp: i64,
+    pub end_timestamp: i64,
+}
+
+#[event]
+pub struct ArenaFinalized {
+    pub arena: Pubkey,
+    pub arena_id: u64,
+    pub status: u8, // Ended or Suspended
+    pub winning_asset: u8,
+    pub winning_movement: i64,
+    pub total_pool: u64,
+    pub treasury_fee_bps: u64, // Rate resolved from the arena's fee schedule; 0 if suspended
+}
+
+#[event]
+pub struct OwnTokensClaimed {
+    pub arena: Pubkey,
+    pub arena_id: u64,
+    pub player: Pubkey,
+    pub asset_index: u8,
+    pub mint: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct LoserTokensClaimed {
+    pub arena: Pubkey,
+    pub arena_id: u64,
+    pub winner: Pubkey,
+    pub loser: Pubkey,
+    pub loser_index: u8,
+    pub mint: Pubkey,
+    pub amount: u64,
+    pub treasury_fee: u64, // Left in the vault for collect_treasury_fee
+}
+
+#[event]
+pub struct TreasuryFeeCollected {
+    pub arena: Pubkey,
+    pub arena_id: u64,
+    pub loser: Pubkey,
+    pub loser_index: u8,
+    pub mint: Pubkey,
+    pub amount: u64,
+    pub treasury_token_account: Pubkey,
+}
+
+#[event]
+pub struct PlayerEntryClosed {
+    pub arena: Pubkey,
+    pub arena_id: u64,
+    pub player: Pubkey,
+}
+
+#[event]
+pub struct ArenaAssetClosed {
+    pub arena: Pubkey,
+    pub arena_id: u64,
+    pub asset_index: u8,
+    pub rent_payer: Pubkey,
+}
+
+#[event]
+pub struct ArenaClosed {
+    pub arena: Pubkey,
+    pub arena_id: u64,
+    pub rent_payer: Pubkey,
+}
+
 // ============================================================================
 // ENUMS & ERRORS
 // ============================================================================
@@ -914,10 +1735,24 @@ pub enum CryptarenaError {
     CannotClaimFromWinner,
     #[msg("Invalid duration - must be greater than 0")]
     InvalidDuration,
+    #[msg("Settings timelock cannot be negative")]
+    InvalidTimelock,
+    #[msg("No pending settings change")]
+    NoPendingSettings,
+    #[msg("Settings timelock has not elapsed")]
+    TimelockNotElapsed,
     #[msg("Token is not whitelisted")]
     TokenNotWhitelisted,
     #[msg("Asset index does not match whitelisted token")]
     InvalidAssetIndex,
     #[msg("Treasury fee already claimed from this loser")]
     TreasuryFeeAlreadyClaimed,
+    #[msg("Arena still has outstanding claims")]
+    OutstandingClaims,
+    #[msg("Arena still has open player entries or assets")]
+    ArenaAccountsOpen,
+    #[msg("Fee schedule is invalid")]
+    InvalidFeeSchedule,
+    #[msg("Arena charges no treasury fee")]
+    NoTreasuryFee,
 }
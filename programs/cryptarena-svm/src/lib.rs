@@ -1,6 +1,9 @@
+#![allow(clippy::too_many_arguments)] // Generated CPI clients mirror the instruction arguments
+
 use anchor_lang::prelude::*;
-use anchor_spl::token::{self, Token, TokenAccount, Transfer};
-use pyth_solana_receiver_sdk::price_update::PriceUpdateV2;
+use anchor_spl::token::{self, CloseAccount, Mint, Token, TokenAccount, Transfer};
+use pyth_solana_receiver_sdk::price_update::{Price, PriceUpdateV2, VerificationLevel};
+use solana_sha256_hasher::hash;
 
 declare_id!("GjiVESbCveUyk2c1zqbFCzPnuficii3L5ZJHgYHMRhg6");
 
@@ -10,88 +13,32 @@ declare_id!("GjiVESbCveUyk2c1zqbFCzPnuficii3L5ZJHgYHMRhg6");
 
 pub const MAX_PLAYERS_PER_ARENA: usize = 10;
 pub const MAX_SAME_ASSET_PER_ARENA: u8 = 3;
-pub const MIN_ENTRY_USD: u64 = 10_000_000; // $10 with 6 decimals
-pub const MAX_ENTRY_USD: u64 = 20_000_000; // $20 with 6 decimals
-pub const TREASURY_FEE_BPS: u64 = 1000; // 10%
+pub const DEFAULT_TREASURY_FEE_BPS: u64 = 1000; // 10%, flat until `update_settings` sets a schedule
+pub const MAX_FEE_OVERRIDES: usize = 4;
+pub const DEFAULT_CRANK_BOUNTY_BPS: u64 = 50; // 0.5% to the keeper that settles
+pub const DEFAULT_REFERRAL_SHARE_BPS: u64 = 2000; // 20% of a referred entry's treasury fee
 pub const DEFAULT_ARENA_DURATION: i64 = 600; // 10 minutes in seconds
-pub const PYTH_PRICE_MAX_AGE: u64 = 60; // 60 seconds
-
-// Asset indices
-pub const ASSET_SOL: u8 = 0;
-pub const ASSET_TRUMP: u8 = 1;
-pub const ASSET_PUMP: u8 = 2;
-pub const ASSET_BONK: u8 = 3;
-pub const ASSET_JUP: u8 = 4;
-pub const ASSET_PENGU: u8 = 5;
-pub const ASSET_PYTH: u8 = 6;
-pub const ASSET_HNT: u8 = 7;
-pub const ASSET_FARTCOIN: u8 = 8;
-pub const ASSET_RAY: u8 = 9;
-pub const ASSET_JTO: u8 = 10;
-pub const ASSET_KMNO: u8 = 11;
-pub const ASSET_MET: u8 = 12;
-pub const ASSET_W: u8 = 13;
-pub const TOTAL_ASSETS: usize = 14;
-
-// Pyth Price Feed IDs (as bytes for on-chain storage)
-pub const PYTH_FEED_SOL: [u8; 32] = hex_to_bytes("de87506dabfadbef89af2d5d796ebae80ddaea240fc7667aa808fce3629cd8fb");
-pub const PYTH_FEED_TRUMP: [u8; 32] = hex_to_bytes("879551021853eec7a7dc827578e8e69da7e4fa8148339aa0d3d5296405be4b1a");
-pub const PYTH_FEED_PUMP: [u8; 32] = hex_to_bytes("7a01fca212788bba7c5bf8c9efd576a8a722f070d2c17596ff7bb609b8d5c3b9");
-pub const PYTH_FEED_BONK: [u8; 32] = hex_to_bytes("72b021217ca3fe68922a19aaf990109cb9d84e9ad004b4d2025ad6f529314419");
-pub const PYTH_FEED_JUP: [u8; 32] = hex_to_bytes("0a0408d619e9380abad35060f9192039ed5042fa6f82301d0e48bb52be830996");
-pub const PYTH_FEED_PENGU: [u8; 32] = hex_to_bytes("bed3097008b9b5e3c93bec20be79cb43986b85a996475589351a21e67bae9b61");
-pub const PYTH_FEED_PYTH: [u8; 32] = hex_to_bytes("0bbf28e9a841a1cc788f6a361b17ca072d0ea3098a1e5df1c3922d06719579ff");
-pub const PYTH_FEED_HNT: [u8; 32] = hex_to_bytes("649fdd7ec08e8e2a20f425729854e90293dcbe2376abc47197a14da6ff339756");
-pub const PYTH_FEED_FARTCOIN: [u8; 32] = hex_to_bytes("058cd29ef0e714c5affc44f269b2c1899a52da416d7acc147b9da692e6953608");
-pub const PYTH_FEED_RAY: [u8; 32] = hex_to_bytes("91568baa8beb53db23eb3fb7f22c6e8bd303d103919e19733f2bb642d3e7987a");
-pub const PYTH_FEED_JTO: [u8; 32] = hex_to_bytes("b43660a5f790c69354b0729a5ef9d50d68f1df92107540210b9cccba1f947cc2");
-pub const PYTH_FEED_KMNO: [u8; 32] = hex_to_bytes("b17e5bc5de742a8a378b54c9c75442b7d51e30ada63f28d9bd28d3c0e26511a0");
-pub const PYTH_FEED_MET: [u8; 32] = hex_to_bytes("0292e0f405bcd4a496d34e48307f6787349ad2bcd8505c3d3a9f77d81a67a682");
-pub const PYTH_FEED_W: [u8; 32] = hex_to_bytes("eff7446475e218517566ea99e72a4abec2e1bd8498b43b7d8331e29dcb059389");
-
-/// Convert hex string to bytes at compile time
-const fn hex_to_bytes(hex: &str) -> [u8; 32] {
-    let bytes = hex.as_bytes();
-    let mut result = [0u8; 32];
-    let mut i = 0;
-    while i < 32 {
-        let high = hex_char_to_nibble(bytes[i * 2]);
-        let low = hex_char_to_nibble(bytes[i * 2 + 1]);
-        result[i] = (high << 4) | low;
-        i += 1;
-    }
-    result
-}
-
-const fn hex_char_to_nibble(c: u8) -> u8 {
-    match c {
-        b'0'..=b'9' => c - b'0',
-        b'a'..=b'f' => c - b'a' + 10,
-        b'A'..=b'F' => c - b'A' + 10,
-        _ => 0,
-    }
-}
-
-/// Get Pyth feed ID for an asset index
-pub fn get_pyth_feed_id(asset_index: u8) -> [u8; 32] {
-    match asset_index {
-        ASSET_SOL => PYTH_FEED_SOL,
-        ASSET_TRUMP => PYTH_FEED_TRUMP,
-        ASSET_PUMP => PYTH_FEED_PUMP,
-        ASSET_BONK => PYTH_FEED_BONK,
-        ASSET_JUP => PYTH_FEED_JUP,
-        ASSET_PENGU => PYTH_FEED_PENGU,
-        ASSET_PYTH => PYTH_FEED_PYTH,
-        ASSET_HNT => PYTH_FEED_HNT,
-        ASSET_FARTCOIN => PYTH_FEED_FARTCOIN,
-        ASSET_RAY => PYTH_FEED_RAY,
-        ASSET_JTO => PYTH_FEED_JTO,
-        ASSET_KMNO => PYTH_FEED_KMNO,
-        ASSET_MET => PYTH_FEED_MET,
-        ASSET_W => PYTH_FEED_W,
-        _ => [0u8; 32],
-    }
-}
+pub const DEFAULT_LOBBY_TIMEOUT: i64 = 3600; // 1 hour in seconds
+pub const DEFAULT_MIN_CUSTOM_DURATION: i64 = 60; // 1 minute in seconds
+pub const DEFAULT_MAX_CUSTOM_DURATION: i64 = 86400; // 1 day in seconds
+pub const DEFAULT_MAX_CREATOR_FEE_BPS: u64 = 500; // 5%
+pub const DEFAULT_SETTINGS_TIMELOCK: i64 = 0; // Raise with `update_settings` once the protocol is live
+pub const MIN_PLAYERS_PER_ARENA: u8 = 2;
+pub const MAX_ALLOWLIST_SIZE: usize = 32;
+pub const START_PRICE_WINDOW: i64 = 10; // Room for the few transactions a fully verified Pyth post takes before activation
+pub const END_PRICE_WINDOW: i64 = 1; // Pyth publishes every 400ms, so the first update after end_timestamp lands within a second
+pub const DEFAULT_ORACLE_GRACE_PERIOD: i64 = 600; // Assets still missing an end price 10 minutes after end are voided
+pub const MIN_VERIFICATION_LEVEL: VerificationLevel = VerificationLevel::Full;
+pub const ARENA_TYPE_COUNT: usize = 2; // Bullish, Bearish
+pub const MAX_SEASON_WINNERS: usize = 10;
+pub const SEASON_PARTICIPATION_POINTS: u64 = 10; // Every entry in an ended arena
+pub const SEASON_WIN_POINTS: u64 = 100; // Bonus for backing the winning asset
+pub const SEASON_MAX_MARGIN_POINTS: u64 = 100; // 1 point per 10 bps of margin over the runner-up
+pub const SEASON_FORCE_CLOSE_DELAY: i64 = 30 * 86400; // Admin may sweep a season still unpaid 30 days after it ends
+pub const SEASON_SCORE_CLOSE_DELAY: i64 = 7 * 86400; // Anyone may close an entry a season left uncredited 7 days after its arena ends
+
+/// Capacity of the asset registry; per-asset arrays and bitmaps are sized by it
+pub const MAX_ASSETS: usize = 16;
 
 // ============================================================================
 // PROGRAM
@@ -107,8 +54,19 @@ pub mod cryptarena_svm {
         global_state.admin = ctx.accounts.admin.key();
         global_state.treasury = ctx.accounts.treasury.key();
         global_state.arena_duration = if arena_duration > 0 { arena_duration } else { DEFAULT_ARENA_DURATION };
-        global_state.current_arena_id = 0;
-        global_state.waiting_arena = None;
+        global_state.lobby_timeout = DEFAULT_LOBBY_TIMEOUT;
+        global_state.crank_bounty_bps = DEFAULT_CRANK_BOUNTY_BPS;
+        global_state.min_custom_duration = DEFAULT_MIN_CUSTOM_DURATION;
+        global_state.max_custom_duration = DEFAULT_MAX_CUSTOM_DURATION;
+        global_state.max_creator_fee_bps = DEFAULT_MAX_CREATOR_FEE_BPS;
+        global_state.referral_share_bps = DEFAULT_REFERRAL_SHARE_BPS;
+        global_state.fee_schedule = FeeSchedule::flat(DEFAULT_TREASURY_FEE_BPS);
+        global_state.oracle_grace_period = DEFAULT_ORACLE_GRACE_PERIOD;
+        global_state.seasons_start = 0;
+        global_state.seasons_end = 0;
+        global_state.pending_admin = None;
+        global_state.settings_timelock = DEFAULT_SETTINGS_TIMELOCK;
+        global_state.pending_settings = None;
         global_state.is_paused = false;
         global_state.bump = ctx.bumps.global_state;
 
@@ -116,153 +74,397 @@ pub mod cryptarena_svm {
         Ok(())
     }
 
-    /// Update admin settings
+    /// Queue an admin settings change, merged into any pending one
+    /// Each field takes effect through `execute_settings` once its own timelock has elapsed;
+    /// fields left `None` keep whatever is already queued for them.
     pub fn update_settings(
         ctx: Context<UpdateSettings>,
         new_arena_duration: Option<i64>,
         new_treasury: Option<Pubkey>,
         is_paused: Option<bool>,
+        new_lobby_timeout: Option<i64>,
+        new_crank_bounty_bps: Option<u64>,
+        new_settings_timelock: Option<i64>,
+        new_referral_share_bps: Option<u64>,
+        new_fee_schedule: Option<FeeSchedule>,
+        new_oracle_grace_period: Option<i64>,
     ) -> Result<()> {
         let global_state = &mut ctx.accounts.global_state;
 
-        if let Some(duration) = new_arena_duration {
-            require!(duration > 0, CryptarenaError::InvalidDuration);
-            global_state.arena_duration = duration;
+        let executable_at = queue_settings(global_state, |pending, executable_at| {
+            queue_change(&mut pending.arena_duration, new_arena_duration, executable_at);
+            queue_change(&mut pending.treasury, new_treasury, executable_at);
+            queue_change(&mut pending.is_paused, is_paused, executable_at);
+            queue_change(&mut pending.lobby_timeout, new_lobby_timeout, executable_at);
+            queue_change(&mut pending.crank_bounty_bps, new_crank_bounty_bps, executable_at);
+            queue_change(&mut pending.settings_timelock, new_settings_timelock, executable_at);
+            queue_change(&mut pending.referral_share_bps, new_referral_share_bps, executable_at);
+            queue_change(&mut pending.fee_schedule, new_fee_schedule, executable_at);
+            queue_change(&mut pending.oracle_grace_period, new_oracle_grace_period, executable_at);
+        })?;
+
+        msg!("Settings change queued, executable at {}", executable_at);
+        Ok(())
+    }
+
+    /// Apply every pending settings change whose timelock has elapsed (admin only)
+    /// Changes queued on a stake tier or registry entry are applied when that account is passed in;
+    /// changes still inside their timelock stay queued.
+    pub fn execute_settings(ctx: Context<ExecuteSettings>) -> Result<()> {
+        let global_state = &mut ctx.accounts.global_state;
+        let clock = Clock::get()?;
+        let mut queued = false;
+        let mut applied = false;
+
+        if let Some(mut pending) = global_state.pending_settings {
+            queued = true;
+            let ready = pending.take_ready(clock.unix_timestamp);
+            // Bounds may have moved since the change was queued
+            validate_settings(global_state, &ready)?;
+            applied = !ready.is_empty();
+
+            if let Some(duration) = ready.arena_duration {
+                global_state.arena_duration = duration.value;
+            }
+            if let Some(timeout) = ready.lobby_timeout {
+                global_state.lobby_timeout = timeout.value;
+            }
+            if let Some(bounty_bps) = ready.crank_bounty_bps {
+                global_state.crank_bounty_bps = bounty_bps.value;
+            }
+            if let Some(treasury) = ready.treasury {
+                global_state.treasury = treasury.value;
+            }
+            if let Some(paused) = ready.is_paused {
+                global_state.is_paused = paused.value;
+            }
+            if let Some(timelock) = ready.settings_timelock {
+                global_state.settings_timelock = timelock.value;
+            }
+            if let Some(share_bps) = ready.referral_share_bps {
+                global_state.referral_share_bps = share_bps.value;
+            }
+            if let Some(fee_schedule) = ready.fee_schedule {
+                global_state.fee_schedule = fee_schedule.value;
+            }
+            if let Some(grace_period) = ready.oracle_grace_period {
+                global_state.oracle_grace_period = grace_period.value;
+            }
+            if let Some(bounds) = ready.custom_arena_bounds {
+                global_state.min_custom_duration = bounds.value.min_duration;
+                global_state.max_custom_duration = bounds.value.max_duration;
+                global_state.max_creator_fee_bps = bounds.value.max_creator_fee_bps;
+            }
+            global_state.pending_settings = (!pending.is_empty()).then_some(pending);
+        }
+
+        if let Some(stake_tier) = ctx.accounts.stake_tier.as_mut() {
+            if let Some(change) = stake_tier.pending_change {
+                queued = true;
+                if clock.unix_timestamp >= change.executable_at {
+                    stake_tier.min_entry_usd = change.value.min_entry_usd;
+                    stake_tier.max_entry_usd = change.value.max_entry_usd;
+                    stake_tier.enabled = change.value.enabled;
+                    stake_tier.pending_change = None;
+                    applied = true;
+                }
+            }
         }
 
-        if let Some(treasury) = new_treasury {
-            global_state.treasury = treasury;
+        let mut listed = None;
+        if let Some(asset_config) = ctx.accounts.asset_config.as_mut() {
+            if let Some(change) = asset_config.pending_change {
+                queued = true;
+                if clock.unix_timestamp >= change.executable_at {
+                    let change = change.value;
+                    asset_config.feed_id = change.feed_id;
+                    asset_config.mint = change.mint;
+                    asset_config.decimals = change.decimals;
+                    asset_config.max_age = change.max_age;
+                    asset_config.max_confidence_bps = change.max_confidence_bps;
+                    asset_config.enabled = change.enabled;
+                    asset_config.pending_change = None;
+                    listed = Some((asset_config.asset_index, change));
+                    applied = true;
+                }
+            }
         }
 
-        if let Some(paused) = is_paused {
-            global_state.is_paused = paused;
+        require!(queued, CryptarenaError::NoPendingSettings);
+        require!(applied, CryptarenaError::TimelockNotElapsed);
+
+        if let Some((asset_index, change)) = listed {
+            emit_cpi!(AssetConfigSet {
+                asset_index,
+                feed_id: change.feed_id,
+                mint: change.mint,
+                decimals: change.decimals,
+                max_age: change.max_age,
+                max_confidence_bps: change.max_confidence_bps,
+                enabled: change.enabled,
+            });
         }
 
         msg!("Settings updated");
         Ok(())
     }
 
-    /// Enter an arena with a selected asset
-    pub fn enter_arena(
-        ctx: Context<EnterArena>,
-        asset_index: u8,
-        amount: u64,
-    ) -> Result<()> {
-        let global_state = &mut ctx.accounts.global_state;
-        let clock = Clock::get()?;
+    /// Discard the pending settings changes, and those of any stake tier or registry entry passed in (admin only)
+    pub fn cancel_settings(ctx: Context<ExecuteSettings>) -> Result<()> {
+        let accounts = ctx.accounts;
+        let mut canceled = accounts.global_state.pending_settings.take().is_some();
+        if let Some(stake_tier) = accounts.stake_tier.as_mut() {
+            canceled |= stake_tier.pending_change.take().is_some();
+        }
+        if let Some(asset_config) = accounts.asset_config.as_mut() {
+            canceled |= asset_config.pending_change.take().is_some();
+        }
+        require!(canceled, CryptarenaError::NoPendingSettings);
 
-        // Validate protocol is not paused
-        require!(!global_state.is_paused, CryptarenaError::ProtocolPaused);
+        msg!("Pending settings change canceled");
+        Ok(())
+    }
 
-        // Validate asset index
-        require!(asset_index < TOTAL_ASSETS as u8, CryptarenaError::InvalidAsset);
+    /// Nominate a new admin, who takes over by calling `accept_admin` (admin only)
+    pub fn propose_admin(ctx: Context<UpdateSettings>, new_admin: Pubkey) -> Result<()> {
+        ctx.accounts.global_state.pending_admin = Some(new_admin);
 
-        // Get price from Pyth oracle
-        let price_update = &ctx.accounts.price_update;
-        let expected_feed_id = get_pyth_feed_id(asset_index);
-        let price = price_update.get_price_no_older_than(
-            &Clock::get()?,
-            PYTH_PRICE_MAX_AGE,
-            &expected_feed_id,
-        )?;
+        msg!("Admin transfer proposed to {}", new_admin);
+        Ok(())
+    }
+
+    /// Accept a proposed admin transfer (pending admin only)
+    pub fn accept_admin(ctx: Context<AcceptAdmin>) -> Result<()> {
+        let global_state = &mut ctx.accounts.global_state;
+        global_state.admin = ctx.accounts.new_admin.key();
+        global_state.pending_admin = None;
 
-        // Calculate USD value of entry
-        let price_value = price.price as u64;
-        let expo = price.exponent;
-        let usd_value = calculate_usd_value(amount, price_value, expo)?;
+        msg!("Admin transferred to {}", global_state.admin);
+        Ok(())
+    }
+
+    /// Queue the bounds creators must respect in `create_arena` (admin only)
+    pub fn set_custom_arena_bounds(
+        ctx: Context<UpdateSettings>,
+        min_duration: i64,
+        max_duration: i64,
+        max_creator_fee_bps: u64,
+    ) -> Result<()> {
+        let executable_at = queue_settings(&mut ctx.accounts.global_state, |pending, executable_at| {
+            let bounds = CustomArenaBounds {
+                min_duration,
+                max_duration,
+                max_creator_fee_bps,
+            };
+            queue_change(&mut pending.custom_arena_bounds, Some(bounds), executable_at);
+        })?;
+
+        msg!("Custom arena bounds queued: {}-{}s, creator fee up to {}bps, executable at {}",
+            min_duration, max_duration, max_creator_fee_bps, executable_at);
+        Ok(())
+    }
 
-        // Validate entry amount is within bounds ($10-$20)
+    /// Create a stake tier or queue an update to it (admin only)
+    /// The change is queued on the tier itself, replacing any earlier one for it, and a new tier
+    /// stays disabled until it is executed. Each tier keeps its own lobbies and arena id sequence,
+    /// which are left untouched on update.
+    pub fn set_stake_tier(
+        ctx: Context<SetStakeTier>,
+        tier_id: u8,
+        min_entry_usd: u64,
+        max_entry_usd: u64,
+        enabled: bool,
+    ) -> Result<()> {
         require!(
-            usd_value >= MIN_ENTRY_USD && usd_value <= MAX_ENTRY_USD,
-            CryptarenaError::InvalidEntryAmount
+            min_entry_usd > 0 && min_entry_usd <= max_entry_usd,
+            CryptarenaError::InvalidStakeTier
         );
+        let executable_at = settings_executable_at(&ctx.accounts.global_state)?;
+
+        let stake_tier = &mut ctx.accounts.stake_tier;
+        stake_tier.tier_id = tier_id;
+        stake_tier.bump = ctx.bumps.stake_tier;
+        stake_tier.pending_change = Some(Queued {
+            value: StakeTierSettings {
+                min_entry_usd,
+                max_entry_usd,
+                enabled,
+            },
+            executable_at,
+        });
 
-        // Get or create arena
-        let arena = &mut ctx.accounts.arena;
-        
-        if arena.status == ArenaStatus::Uninitialized as u8 {
-            // Initialize new arena
-            arena.id = global_state.current_arena_id;
-            arena.status = ArenaStatus::Waiting as u8;
-            arena.player_count = 0;
-            arena.asset_counts = [0u8; TOTAL_ASSETS];
-            arena.start_timestamp = 0;
-            arena.end_timestamp = 0;
-            arena.total_pool = 0;
-            arena.winning_asset = 255; // Invalid/unset
-            arena.is_suspended = false;
-            arena.bump = ctx.bumps.arena;
-
-            global_state.waiting_arena = Some(arena.key());
-            global_state.current_arena_id += 1;
-        }
-
-        // Validate arena is in waiting status
+        msg!("Stake tier {} queued: {}-{} USD, enabled {}, executable at {}",
+            tier_id, min_entry_usd, max_entry_usd, enabled, executable_at);
+        Ok(())
+    }
+
+    /// List an asset in the registry or queue an update to it (admin only)
+    /// The change is queued on the registry entry, replacing any earlier one for it, and a new
+    /// asset stays disabled until it is executed. Delisting is done by clearing `enabled`; arenas
+    /// already holding the asset still settle.
+    pub fn set_asset_config(
+        ctx: Context<SetAssetConfig>,
+        asset_index: u8,
+        feed_id: [u8; 32],
+        max_age: u64,
+        max_confidence_bps: u16,
+        enabled: bool,
+    ) -> Result<()> {
+        require!(asset_index < MAX_ASSETS as u8, CryptarenaError::InvalidAsset);
+        require!(max_age > 0, CryptarenaError::InvalidMaxAge);
         require!(
-            arena.status == ArenaStatus::Waiting as u8,
-            CryptarenaError::ArenaNotWaiting
+            max_confidence_bps > 0 && max_confidence_bps <= 10000,
+            CryptarenaError::InvalidConfidenceBound
         );
+        let executable_at = settings_executable_at(&ctx.accounts.global_state)?;
+
+        let mint = &ctx.accounts.mint;
+        let asset_config = &mut ctx.accounts.asset_config;
+        asset_config.asset_index = asset_index;
+        asset_config.bump = ctx.bumps.asset_config;
+        asset_config.pending_change = Some(Queued {
+            value: AssetConfigSettings {
+                feed_id,
+                mint: mint.key(),
+                decimals: mint.decimals,
+                max_age,
+                max_confidence_bps,
+                enabled,
+            },
+            executable_at,
+        });
+
+        msg!("Asset {} queued: mint {}, enabled {}, executable at {}",
+            asset_index, mint.key(), enabled, executable_at);
+        Ok(())
+    }
+
+    /// Create a custom arena with creator-chosen parameters
+    /// The arena is keyed by creator and nonce and is joined through `enter_arena` with its key.
+    /// `invite_hash` is the sha256 of the invite secret and only used by the `InviteCode` policy.
+    pub fn create_arena(
+        ctx: Context<CreateArena>,
+        nonce: u64,
+        tier_id: u8,
+        arena_type: ArenaType,
+        duration: i64,
+        max_players: u8,
+        creator_fee_bps: u64,
+        access_policy: AccessPolicy,
+        invite_hash: [u8; 32],
+    ) -> Result<()> {
+        let global_state = &ctx.accounts.global_state;
+        let clock = Clock::get()?;
 
-        // Check if max same asset limit reached
+        require!(!global_state.is_paused, CryptarenaError::ProtocolPaused);
+        require!(
+            duration >= global_state.min_custom_duration
+                && duration <= global_state.max_custom_duration,
+            CryptarenaError::InvalidDuration
+        );
         require!(
-            arena.asset_counts[asset_index as usize] < MAX_SAME_ASSET_PER_ARENA,
-            CryptarenaError::MaxAssetLimitReached
+            (MIN_PLAYERS_PER_ARENA..=MAX_PLAYERS_PER_ARENA as u8).contains(&max_players),
+            CryptarenaError::InvalidPlayerCapacity
+        );
+        require!(
+            creator_fee_bps <= global_state.max_creator_fee_bps,
+            CryptarenaError::CreatorFeeTooHigh
         );
 
-        // Initialize player entry
-        let player_entry = &mut ctx.accounts.player_entry;
-        player_entry.arena = arena.key();
-        player_entry.player = ctx.accounts.player.key();
-        player_entry.asset_index = asset_index;
-        player_entry.amount = amount;
-        player_entry.usd_value = usd_value;
-        player_entry.entry_price = price_value;
-        player_entry.entry_timestamp = clock.unix_timestamp;
-        player_entry.is_winner = false;
-        player_entry.reward_claimed = false;
-        player_entry.bump = ctx.bumps.player_entry;
-
-        // Update arena state
-        let player_count = arena.player_count as usize;
-        arena.players[player_count] = ctx.accounts.player.key();
-        arena.asset_counts[asset_index as usize] += 1;
-        arena.player_count += 1;
-        arena.total_pool += usd_value;
+        // Fresh account is zeroed; only non-default fields are set
+        let arena = &mut ctx.accounts.arena;
+        arena.id = nonce;
+        arena.tier_id = tier_id;
+        arena.creator = ctx.accounts.creator.key();
+        arena.status = ArenaStatus::Waiting as u8;
+        arena.arena_type = arena_type as u8;
+        arena.max_players = max_players;
+        arena.duration = duration;
+        arena.creator_fee_bps = creator_fee_bps;
+        arena.fee_schedule = global_state.fee_schedule.snapshot(clock.unix_timestamp);
+        arena.referral_share_bps = global_state.referral_share_bps;
+        arena.crank_bounty_bps = global_state.crank_bounty_bps;
+        require!(arena.fees_fit_pool(), CryptarenaError::InvalidFeeSchedule);
+        arena.access_policy = access_policy as u8;
+        arena.invite_hash = invite_hash;
+        arena.created_timestamp = clock.unix_timestamp;
+        arena.winning_asset = 255; // Invalid/unset
+        arena.rent_payer = ctx.accounts.creator.key();
+        arena.bump = ctx.bumps.arena;
+
+        emit_cpi!(arena_created_event(arena));
+
+        msg!("Creator {} opened arena {} in tier {}: {} players, {}s, {}bps fee",
+            arena.creator, nonce, tier_id, max_players, duration, creator_fee_bps);
+        Ok(())
+    }
 
-        // Store starting price for this asset if not already stored
-        if arena.start_prices[asset_index as usize] == 0 {
-            arena.start_prices[asset_index as usize] = price_value;
-        }
+    /// Set the players allowed into an allowlist arena (creator only, while waiting)
+    pub fn set_arena_allowlist(
+        ctx: Context<SetArenaAllowlist>,
+        players: Vec<Pubkey>,
+    ) -> Result<()> {
+        let arena = &ctx.accounts.arena;
 
-        // Transfer tokens to arena vault
-        let transfer_ctx = CpiContext::new(
-            ctx.accounts.token_program.to_account_info(),
-            Transfer {
-                from: ctx.accounts.player_token_account.to_account_info(),
-                to: ctx.accounts.arena_vault.to_account_info(),
-                authority: ctx.accounts.player.to_account_info(),
-            },
+        require!(
+            arena.status == ArenaStatus::Waiting as u8,
+            CryptarenaError::ArenaNotWaiting
         );
-        token::transfer(transfer_ctx, amount)?;
+        require!(
+            arena.access_policy == AccessPolicy::Allowlist as u8,
+            CryptarenaError::InvalidAccessPolicy
+        );
+        require!(players.len() <= MAX_ALLOWLIST_SIZE, CryptarenaError::AllowlistTooLong);
 
-        msg!("Player {} entered arena {} with asset {}", 
-            ctx.accounts.player.key(), arena.id, asset_index);
+        let arena_allowlist = &mut ctx.accounts.arena_allowlist;
+        arena_allowlist.arena = arena.key();
+        arena_allowlist.players = players;
+        arena_allowlist.bump = ctx.bumps.arena_allowlist;
 
-        // Check if arena is full and should start
-        if arena.player_count == MAX_PLAYERS_PER_ARENA as u8 {
-            arena.status = ArenaStatus::Active as u8;
-            arena.start_timestamp = clock.unix_timestamp;
-            arena.end_timestamp = clock.unix_timestamp + global_state.arena_duration;
-            global_state.waiting_arena = None;
+        msg!("Arena {} allowlist set to {} players", arena.id, arena_allowlist.players.len());
+        Ok(())
+    }
 
-            msg!("Arena {} started! Ends at {}", arena.id, arena.end_timestamp);
-        }
+    /// Enter an arena with a selected asset
+    /// Joins the tier's open lobby for `arena_type`, or creates one if none is waiting.
+    /// Passing `custom_arena` joins that creator's arena instead; its tier and type must match.
+    /// The entrant that fills the arena must pass an `(AssetConfig, PriceUpdateV2)` pair for every
+    /// other represented asset as remaining accounts; start prices are snapshotted from them.
+    /// Private arenas also need the `arena_allowlist` account or the `invite_secret` preimage.
+    /// `referrer` is bound to the player on their first entry and ignored afterwards.
+    pub fn enter_arena<'info>(
+        ctx: Context<'_, '_, 'info, 'info, EnterArena<'info>>,
+        asset_index: u8,
+        amount: u64,
+        arena_type: ArenaType,
+        tier_id: u8,
+        custom_arena: Option<CustomArenaKey>,
+        invite_secret: Option<Vec<u8>>,
+        referrer: Option<Pubkey>,
+    ) -> Result<()> {
+        process_entry(ctx, asset_index, amount, arena_type, tier_id, custom_arena, invite_secret, referrer, false)
+    }
 
-        Ok(())
+    /// Enter an arena like `enter_arena`, paying the entry from the user vault balance
+    /// Requires the `user_vault` and `protocol_vault` accounts instead of `player_token_account`.
+    pub fn enter_arena_from_vault<'info>(
+        ctx: Context<'_, '_, 'info, 'info, EnterArena<'info>>,
+        asset_index: u8,
+        amount: u64,
+        arena_type: ArenaType,
+        tier_id: u8,
+        custom_arena: Option<CustomArenaKey>,
+        invite_secret: Option<Vec<u8>>,
+        referrer: Option<Pubkey>,
+    ) -> Result<()> {
+        process_entry(ctx, asset_index, amount, arena_type, tier_id, custom_arena, invite_secret, referrer, true)
     }
 
-    /// End an arena and determine winners (bullish mode - highest positive movement wins)
-    pub fn end_arena(ctx: Context<EndArena>) -> Result<()> {
+    /// End an arena and determine winners
+    /// Bullish: highest movement wins. Bearish: largest negative movement wins.
+    /// Fails while a represented asset lacks an end price, until the oracle grace period voids it.
+    /// Remaining accounts: every player's writable `PlayerProfile`, in `arena.players` order.
+    pub fn end_arena<'info>(ctx: Context<'_, '_, 'info, 'info, EndArena<'info>>) -> Result<()> {
         let arena = &mut ctx.accounts.arena;
         let clock = Clock::get()?;
 
@@ -276,46 +478,61 @@ pub mod cryptarena_svm {
             CryptarenaError::ArenaDurationNotComplete
         );
 
-        // Process each unique asset in the arena
-        let mut best_movement: i64 = i64::MIN;
-        let mut winning_asset: u8 = 255;
-        let mut tie_detected = false;
-
-        for asset_idx in 0..TOTAL_ASSETS {
-            if arena.asset_counts[asset_idx] > 0 {
-                let start_price = arena.start_prices[asset_idx] as i64;
-                let end_price = arena.end_prices[asset_idx] as i64;
-
-                if start_price > 0 && end_price > 0 {
-                    // Calculate percentage movement (scaled by 10000 for precision)
-                    let movement = ((end_price - start_price) * 10000) / start_price;
-                    arena.price_movements[asset_idx] = movement;
-
-                    if movement > best_movement {
-                        best_movement = movement;
-                        winning_asset = asset_idx as u8;
-                        tie_detected = false;
-                    } else if movement == best_movement && winning_asset != 255 {
-                        tie_detected = true;
-                    }
-                }
-            }
-        }
+        void_missing_end_prices(arena, clock.unix_timestamp, ctx.accounts.global_state.oracle_grace_period)?;
+        resolve_arena(arena, ctx.accounts.caller.key());
+        record_losses(arena, ctx.remaining_accounts)?;
+        emit_cpi!(arena_resolved_event(arena));
+        Ok(())
+    }
 
-        // Handle tie - suspend arena for withdrawals
-        if tie_detected {
-            arena.is_suspended = true;
-            arena.status = ArenaStatus::Suspended as u8;
-            msg!("Arena {} suspended due to tie", arena.id);
-            return Ok(());
-        }
+    /// Record end prices for every represented asset and resolve the arena in one transaction
+    /// Takes an `(AssetConfig, PriceUpdateV2)` pair per asset through remaining accounts; assets whose
+    /// end price was already recorded by `update_end_prices` may be omitted, and assets still without
+    /// one once the oracle grace period has passed are voided. The pairs are followed by every
+    /// player's writable `PlayerProfile`, in `arena.players` order.
+    pub fn settle_arena<'info>(
+        ctx: Context<'_, '_, 'info, 'info, SettleArena<'info>>,
+    ) -> Result<()> {
+        let arena = &mut ctx.accounts.arena;
+        let clock = Clock::get()?;
 
-        arena.winning_asset = winning_asset;
-        arena.status = ArenaStatus::Ended as u8;
+        require!(
+            arena.status == ArenaStatus::Active as u8,
+            CryptarenaError::ArenaNotActive
+        );
+        require!(
+            clock.unix_timestamp >= arena.end_timestamp,
+            CryptarenaError::ArenaDurationNotComplete
+        );
 
-        msg!("Arena {} ended. Winning asset: {}, Movement: {}bps", 
-            arena.id, winning_asset, best_movement);
+        let pair_accounts = ctx
+            .remaining_accounts
+            .len()
+            .checked_sub(arena.player_count as usize)
+            .ok_or(CryptarenaError::InvalidRemainingAccounts)?;
+        let (pair_accounts, profiles) = ctx.remaining_accounts.split_at(pair_accounts);
+        let pairs = pair_accounts.chunks_exact(2);
+        require!(
+            pairs.remainder().is_empty(),
+            CryptarenaError::InvalidRemainingAccounts
+        );
+        for pair in pairs {
+            let feed_config: Account<AssetConfig> = Account::try_from(&pair[0])?;
+            let feed_update: Account<PriceUpdateV2> = Account::try_from(&pair[1])?;
+            let feed_asset = feed_config.asset_index;
+            require!(
+                arena.asset_counts[feed_asset as usize] > 0,
+                CryptarenaError::AssetNotInArena
+            );
+
+            let price = read_pyth_price(&feed_update, &feed_config)?;
+            record_end_price(arena, feed_asset, &price)?;
+        }
 
+        void_missing_end_prices(arena, clock.unix_timestamp, ctx.accounts.global_state.oracle_grace_period)?;
+        resolve_arena(arena, ctx.accounts.caller.key());
+        record_losses(arena, profiles)?;
+        emit_cpi!(arena_resolved_event(arena));
         Ok(())
     }
 
@@ -336,28 +553,34 @@ pub mod cryptarena_svm {
             clock.unix_timestamp >= arena.end_timestamp,
             CryptarenaError::ArenaDurationNotComplete
         );
-        require!(asset_index < TOTAL_ASSETS as u8, CryptarenaError::InvalidAsset);
+        require!(asset_index < MAX_ASSETS as u8, CryptarenaError::InvalidAsset);
         require!(
             arena.asset_counts[asset_index as usize] > 0,
             CryptarenaError::AssetNotInArena
         );
 
-        let expected_feed_id = get_pyth_feed_id(asset_index);
-        let price = price_update.get_price_no_older_than(
-            &clock,
-            PYTH_PRICE_MAX_AGE,
-            &expected_feed_id,
-        )?;
+        let price = read_pyth_price(price_update, &ctx.accounts.asset_config)?;
+        record_end_price(arena, asset_index, &price)?;
 
-        arena.end_prices[asset_index as usize] = price.price as u64;
+        emit_cpi!(EndPriceRecorded {
+            arena: arena.key(),
+            arena_id: arena.id,
+            asset_index,
+            price: price.price as u64,
+            publish_time: price.publish_time,
+        });
 
-        msg!("Updated end price for asset {}: {}", asset_index, price.price);
+        msg!("Updated end price for asset {}: {} (published {})", 
+            asset_index, price.price, price.publish_time);
         Ok(())
     }
 
-    /// Claim rewards for a winning player
-    pub fn claim_reward(ctx: Context<ClaimReward>) -> Result<()> {
-        let arena = &ctx.accounts.arena;
+    /// Claim a winner's share of one asset's vault, paid in that asset's mint
+    /// Winners call this once per represented asset (including the winning asset).
+    /// Shares are pro-rata to USD stake; the last winner to claim from a vault receives the rounding dust.
+    pub fn claim_reward(ctx: Context<ClaimReward>, asset_index: u8) -> Result<()> {
+        let arena_info = ctx.accounts.arena.to_account_info();
+        let arena = &mut ctx.accounts.arena;
         let player_entry = &mut ctx.accounts.player_entry;
 
         // Validate arena is ended
@@ -372,48 +595,108 @@ pub mod cryptarena_svm {
             CryptarenaError::NotAWinner
         );
 
-        // Validate reward not already claimed
         require!(
-            !player_entry.reward_claimed,
+            arena.asset_counts[asset_index as usize] > 0,
+            CryptarenaError::AssetNotInArena
+        );
+        require!(!arena.is_asset_void(asset_index), CryptarenaError::AssetVoided);
+
+        // Validate this vault has not already been claimed by the player
+        let asset_bit = 1u16 << asset_index;
+        require!(
+            player_entry.claimed_assets_bitmap & asset_bit == 0,
             CryptarenaError::RewardAlreadyClaimed
         );
 
         // Get winner count from asset_counts
         let winner_count = arena.asset_counts[arena.winning_asset as usize];
 
-        // Calculate player's share
-        let total_pool = arena.total_pool;
-        let treasury_fee = (total_pool * TREASURY_FEE_BPS) / 10000;
-        let winner_pool = total_pool - treasury_fee;
-
-        let player_reward = if winner_count == 1 {
-            winner_pool
+        // Calculate player's share of this vault (treasury, referral, crank and creator fees stay in the vault for their own claims)
+        let vault_amount = arena.asset_amounts[asset_index as usize];
+        let distributable = vault_amount
+            - arena.asset_treasury_fees[asset_index as usize]
+            - arena.asset_referral_fees[asset_index as usize]
+            - arena.asset_crank_bounties[asset_index as usize]
+            - arena.asset_creator_fees[asset_index as usize];
+
+        let player_reward = if arena.vault_claims[asset_index as usize] + 1 == winner_count {
+            // Last winner to claim sweeps the remainder so the full distributable amount is paid out
+            distributable - arena.vault_paid[asset_index as usize]
         } else {
-            // Proportional distribution based on entry value
-            // For now, equal split among winners
-            winner_pool / winner_count as u64
+            calculate_pro_rata_share(
+                distributable,
+                player_entry.usd_value,
+                arena.asset_usd_totals[arena.winning_asset as usize],
+            )
         };
 
-        // Credit to user vault
-        let user_vault = &mut ctx.accounts.user_vault;
-        user_vault.available_balance += player_reward;
+        arena.vault_claims[asset_index as usize] += 1;
+        arena.vault_paid[asset_index as usize] += player_reward;
+        arena.outstanding_claims -= 1;
+
+        // The first vault claimed records the win, valued in USD against the whole pool
+        if player_entry.claimed_assets_bitmap == 0 {
+            let winner_pool = arena.settled_pool() - arena.treasury_fee - arena.crank_bounty - arena.creator_fee;
+            let won_usd = calculate_pro_rata_share(
+                winner_pool,
+                player_entry.usd_value,
+                arena.asset_usd_totals[arena.winning_asset as usize],
+            );
+            ctx.accounts.player_profile.record_win(won_usd, arena.end_timestamp);
+        }
+
+        // Transfer from the asset's arena vault to the winner
+        let arena_seed_key = arena.seed_key();
+        let arena_id_bytes = arena.id.to_le_bytes();
+        let seeds = &[
+            b"arena".as_ref(),
+            arena_seed_key.as_ref(),
+            arena_id_bytes.as_ref(),
+            &[arena.bump],
+        ];
+        let signer = &[&seeds[..]];
+
+        let transfer_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            Transfer {
+                from: ctx.accounts.arena_vault.to_account_info(),
+                to: ctx.accounts.player_token_account.to_account_info(),
+                authority: arena_info,
+            },
+            signer,
+        );
+        token::transfer(transfer_ctx, player_reward)?;
 
+        player_entry.claimed_assets_bitmap |= asset_bit;
         player_entry.is_winner = true;
-        player_entry.reward_claimed = true;
+        player_entry.reward_claimed = player_entry.claimed_assets_bitmap == arena.payout_assets_bitmap();
+
+        emit_cpi!(RewardClaimed {
+            arena: arena.key(),
+            arena_id: arena.id,
+            player: player_entry.player,
+            asset_index,
+            mint: arena.asset_mints[asset_index as usize],
+            amount: player_reward,
+        });
 
-        msg!("Player {} claimed reward of {} from arena {}", 
-            player_entry.player, player_reward, arena.id);
+        msg!("Player {} claimed {} of asset {} from arena {}", 
+            player_entry.player, player_reward, asset_index, arena.id);
 
         Ok(())
     }
 
-    /// Withdraw from suspended arena (tie scenario)
+    /// Withdraw from suspended arena (tie scenario), or from an asset voided in an ended arena
+    /// Returns the original entry in the deposited mint
     pub fn withdraw_suspended(ctx: Context<WithdrawSuspended>) -> Result<()> {
-        let arena = &ctx.accounts.arena;
+        let arena_info = ctx.accounts.arena.to_account_info();
+        let arena = &mut ctx.accounts.arena;
         let player_entry = &mut ctx.accounts.player_entry;
 
+        let voided = arena.status == ArenaStatus::Ended as u8
+            && arena.is_asset_void(player_entry.asset_index);
         require!(
-            arena.status == ArenaStatus::Suspended as u8,
+            arena.status == ArenaStatus::Suspended as u8 || voided,
             CryptarenaError::ArenaNotSuspended
         );
         require!(
@@ -421,386 +704,3535 @@ pub mod cryptarena_svm {
             CryptarenaError::AlreadyWithdrawn
         );
 
-        // Return original entry to user vault
-        let user_vault = &mut ctx.accounts.user_vault;
-        user_vault.available_balance += player_entry.usd_value;
+        let arena_seed_key = arena.seed_key();
+        let arena_id_bytes = arena.id.to_le_bytes();
+        let seeds = &[
+            b"arena".as_ref(),
+            arena_seed_key.as_ref(),
+            arena_id_bytes.as_ref(),
+            &[arena.bump],
+        ];
+        let signer = &[&seeds[..]];
+
+        let transfer_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            Transfer {
+                from: ctx.accounts.arena_vault.to_account_info(),
+                to: ctx.accounts.player_token_account.to_account_info(),
+                authority: arena_info,
+            },
+            signer,
+        );
+        token::transfer(transfer_ctx, player_entry.amount)?;
+
         player_entry.reward_claimed = true;
+        arena.outstanding_claims -= 1;
+        ctx.accounts.player_profile.record_refund();
 
-        msg!("Player {} withdrew {} from suspended arena {}", 
-            player_entry.player, player_entry.usd_value, arena.id);
+        // A voided entry's referral cut is refunded with it, so its referral credit is no longer owed
+        if voided && player_entry.referral_fee > 0 {
+            player_entry.referral_fee = 0;
+            arena.outstanding_claims -= 1;
+        }
 
-        Ok(())
-    }
+        emit_cpi!(EntryRefunded {
+            arena: arena.key(),
+            arena_id: arena.id,
+            player: player_entry.player,
+            asset_index: player_entry.asset_index,
+            mint: arena.asset_mints[player_entry.asset_index as usize],
+            amount: player_entry.amount,
+            arena_status: arena.status,
+        });
 
-    /// Initialize user vault
-    pub fn init_user_vault(ctx: Context<InitUserVault>) -> Result<()> {
-        let user_vault = &mut ctx.accounts.user_vault;
-        user_vault.owner = ctx.accounts.user.key();
-        user_vault.available_balance = 0;
-        user_vault.bump = ctx.bumps.user_vault;
+        msg!("Player {} withdrew {} from suspended or voided entry in arena {}", 
+            player_entry.player, player_entry.amount, arena.id);
 
-        msg!("User vault initialized for {}", ctx.accounts.user.key());
         Ok(())
     }
 
-    /// Withdraw from user vault
-    pub fn withdraw_from_vault(
-        ctx: Context<WithdrawFromVault>,
-        amount: u64,
-    ) -> Result<()> {
-        let user_vault = &mut ctx.accounts.user_vault;
+    /// Leave a waiting arena, refunding the entry and closing the player entry
+    pub fn leave_arena(ctx: Context<LeaveArena>) -> Result<()> {
+        let arena_info = ctx.accounts.arena.to_account_info();
+        let arena = &mut ctx.accounts.arena;
+        let player_entry = &ctx.accounts.player_entry;
+        let asset_idx = player_entry.asset_index as usize;
+        let mint = arena.asset_mints[asset_idx];
 
         require!(
-            user_vault.available_balance >= amount,
-            CryptarenaError::InsufficientBalance
+            arena.status == ArenaStatus::Waiting as u8,
+            CryptarenaError::ArenaNotWaiting
         );
 
-        user_vault.available_balance -= amount;
-
-        // Transfer tokens from protocol vault to user
-        let global_state = &ctx.accounts.global_state;
+        // Refund the entry from the asset's vault
+        let arena_seed_key = arena.seed_key();
+        let arena_id_bytes = arena.id.to_le_bytes();
         let seeds = &[
-            b"global_state".as_ref(),
-            &[global_state.bump],
+            b"arena".as_ref(),
+            arena_seed_key.as_ref(),
+            arena_id_bytes.as_ref(),
+            &[arena.bump],
         ];
         let signer = &[&seeds[..]];
 
         let transfer_ctx = CpiContext::new_with_signer(
             ctx.accounts.token_program.to_account_info(),
             Transfer {
-                from: ctx.accounts.protocol_vault.to_account_info(),
-                to: ctx.accounts.user_token_account.to_account_info(),
-                authority: ctx.accounts.global_state.to_account_info(),
+                from: ctx.accounts.arena_vault.to_account_info(),
+                to: ctx.accounts.player_token_account.to_account_info(),
+                authority: arena_info,
             },
             signer,
         );
-        token::transfer(transfer_ctx, amount)?;
+        token::transfer(transfer_ctx, player_entry.amount)?;
+
+        // Remove the player and keep the players array compact
+        let player_count = arena.player_count as usize;
+        if let Some(slot) = arena.players[..player_count]
+            .iter()
+            .position(|p| *p == player_entry.player)
+        {
+            arena.players.copy_within(slot + 1..player_count, slot);
+            arena.players[player_count - 1] = Pubkey::default();
+            arena.player_assets.copy_within(slot + 1..player_count, slot);
+            arena.player_assets[player_count - 1] = 0;
+        }
+
+        arena.player_count -= 1;
+        arena.open_entries -= 1;
+        ctx.accounts.player_profile.undo_entry(player_entry.asset_index, player_entry.usd_value);
+        arena.asset_counts[asset_idx] -= 1;
+        arena.asset_usd_totals[asset_idx] -= player_entry.usd_value;
+        arena.asset_amounts[asset_idx] -= player_entry.amount;
+        arena.total_pool -= player_entry.usd_value;
+        if player_entry.referral_fee > 0 {
+            arena.asset_referral_fees[asset_idx] -= player_entry.referral_fee;
+            arena.referred_entries -= 1;
+        }
+
+        // Last player on the asset frees it for a different mint
+        if arena.asset_counts[asset_idx] == 0 {
+            arena.asset_mints[asset_idx] = Pubkey::default();
+        }
+
+        emit_cpi!(EntryRefunded {
+            arena: arena.key(),
+            arena_id: arena.id,
+            player: player_entry.player,
+            asset_index: player_entry.asset_index,
+            mint,
+            amount: player_entry.amount,
+            arena_status: arena.status,
+        });
+
+        msg!("Player {} left arena {} | Refunded: {} | Players: {}", 
+            player_entry.player, arena.id, player_entry.amount, arena.player_count);
 
-        msg!("User {} withdrew {} from vault", ctx.accounts.user.key(), amount);
         Ok(())
     }
 
-    /// Transfer treasury funds (admin only)
-    pub fn transfer_treasury(
-        ctx: Context<TransferTreasury>,
-        amount: u64,
-    ) -> Result<()> {
+    /// Expire a waiting arena that has not filled within the lobby timeout (permissionless)
+    /// Players recover their entries with `refund_expired`
+    pub fn expire_arena(ctx: Context<ExpireArena>) -> Result<()> {
         let global_state = &ctx.accounts.global_state;
+        let arena = &mut ctx.accounts.arena;
+        let clock = Clock::get()?;
+
+        require!(
+            arena.status == ArenaStatus::Waiting as u8,
+            CryptarenaError::ArenaNotWaiting
+        );
+        require!(
+            clock.unix_timestamp >= arena.created_timestamp + global_state.lobby_timeout,
+            CryptarenaError::LobbyNotExpired
+        );
+
+        arena.status = ArenaStatus::Expired as u8;
+        arena.outstanding_claims = arena.player_count as u16;
+
+        let lobby = &mut ctx.accounts.stake_tier.waiting_arenas[arena.arena_type as usize];
+        if arena.is_public() && *lobby == Some(arena.id) {
+            *lobby = None;
+        }
+
+        emit_cpi!(ArenaExpired {
+            arena: arena.key(),
+            arena_id: arena.id,
+            player_count: arena.player_count,
+            total_pool: arena.total_pool,
+        });
+
+        msg!("Arena {} expired with {} players", arena.id, arena.player_count);
+        Ok(())
+    }
+
+    /// Refund a player's entry from an expired arena (permissionless, always paid to the player)
+    pub fn refund_expired(ctx: Context<RefundExpired>) -> Result<()> {
+        let arena_info = ctx.accounts.arena.to_account_info();
+        let arena = &mut ctx.accounts.arena;
+        let player_entry = &mut ctx.accounts.player_entry;
+
+        require!(
+            arena.status == ArenaStatus::Expired as u8,
+            CryptarenaError::ArenaNotExpired
+        );
+        require!(
+            !player_entry.reward_claimed,
+            CryptarenaError::AlreadyWithdrawn
+        );
+
+        let arena_seed_key = arena.seed_key();
+        let arena_id_bytes = arena.id.to_le_bytes();
         let seeds = &[
-            b"global_state".as_ref(),
-            &[global_state.bump],
+            b"arena".as_ref(),
+            arena_seed_key.as_ref(),
+            arena_id_bytes.as_ref(),
+            &[arena.bump],
         ];
         let signer = &[&seeds[..]];
 
         let transfer_ctx = CpiContext::new_with_signer(
             ctx.accounts.token_program.to_account_info(),
             Transfer {
-                from: ctx.accounts.treasury_vault.to_account_info(),
-                to: ctx.accounts.destination.to_account_info(),
-                authority: ctx.accounts.global_state.to_account_info(),
+                from: ctx.accounts.arena_vault.to_account_info(),
+                to: ctx.accounts.player_token_account.to_account_info(),
+                authority: arena_info,
             },
             signer,
         );
-        token::transfer(transfer_ctx, amount)?;
+        token::transfer(transfer_ctx, player_entry.amount)?;
+
+        player_entry.reward_claimed = true;
+        arena.outstanding_claims -= 1;
+        ctx.accounts.player_profile.record_refund();
+
+        emit_cpi!(EntryRefunded {
+            arena: arena.key(),
+            arena_id: arena.id,
+            player: player_entry.player,
+            asset_index: player_entry.asset_index,
+            mint: arena.asset_mints[player_entry.asset_index as usize],
+            amount: player_entry.amount,
+            arena_status: arena.status,
+        });
+
+        msg!("Player {} refunded {} from expired arena {}", 
+            player_entry.player, player_entry.amount, arena.id);
 
-        msg!("Treasury transfer: {} to {}", amount, ctx.accounts.destination.key());
         Ok(())
     }
-}
 
-// ============================================================================
-// HELPER FUNCTIONS
-// ============================================================================
+    /// Close a settled player entry and return its rent to the player (permissionless)
+    /// Losers of an ended arena can close right away; everyone else once their claim or refund is done.
+    /// Entries on a voided asset are refunded with `withdraw_suspended` first, referred entries of an
+    /// ended arena credited with `credit_referral`. If the arena ended within the span seasons cover,
+    /// only the player may close the entry until `record_season_score` credits it or
+    /// `SEASON_SCORE_CLOSE_DELAY` passes, so nobody else can wipe out their season points.
+    pub fn close_player_entry(ctx: Context<ClosePlayerEntry>) -> Result<()> {
+        let arena = &mut ctx.accounts.arena;
+        let player_entry = &ctx.accounts.player_entry;
+
+        require!(arena.is_finished(), CryptarenaError::ArenaNotFinished);
+
+        let lost = arena.status == ArenaStatus::Ended as u8
+            && player_entry.asset_index != arena.winning_asset
+            && !arena.is_asset_void(player_entry.asset_index);
+        let settled = player_entry.reward_claimed || lost;
+        let referral_owed =
+            arena.status == ArenaStatus::Ended as u8 && player_entry.referral_fee > 0;
+        require!(settled && !referral_owed, CryptarenaError::EntryNotSettled);
+
+        // Only entries a season may still credit wait for their score, and not past the close delay
+        let season_scorable = arena.status == ArenaStatus::Ended as u8
+            && !arena.is_asset_void(player_entry.asset_index)
+            && !player_entry.season_credited
+            && ctx.accounts.global_state.season_may_cover(arena.end_timestamp)
+            && Clock::get()?.unix_timestamp < arena.end_timestamp + SEASON_SCORE_CLOSE_DELAY;
+        require!(
+            !season_scorable || ctx.accounts.caller.key() == player_entry.player,
+            CryptarenaError::SeasonScoreNotRecorded
+        );
 
-fn calculate_usd_value(amount: u64, price: u64, expo: i32) -> Result<u64> {
-    // Pyth prices have variable exponents, normalize to 6 decimals (USD cents * 10000)
-    let amount_u128 = amount as u128;
-    let price_u128 = price as u128;
-    
-    // Adjust for exponent
-    let usd_value = if expo < 0 {
-        let divisor = 10u128.pow((-expo) as u32);
-        (amount_u128 * price_u128) / divisor
-    } else {
-        let multiplier = 10u128.pow(expo as u32);
-        amount_u128 * price_u128 * multiplier
-    };
+        arena.open_entries -= 1;
 
-    // Scale to 6 decimals
-    Ok((usd_value / 1_000_000_000_000) as u64)
-}
+        emit_cpi!(PlayerEntryClosed {
+            arena: arena.key(),
+            arena_id: arena.id,
+            player: player_entry.player,
+        });
 
-// ============================================================================
-// ACCOUNTS
-// ============================================================================
+        msg!("Closed entry of player {} in arena {} | Open entries: {}",
+            player_entry.player, arena.id, arena.open_entries);
+        Ok(())
+    }
 
+    /// Close a settled arena vault and return its rent to the entrant that opened it (permissionless)
+    /// Tokens left once every claim is settled, such as dust sent to the vault, are swept to the treasury vault.
+    pub fn close_arena_vault(ctx: Context<CloseArenaVault>, asset_index: u8) -> Result<()> {
+        let arena_info = ctx.accounts.arena.to_account_info();
+        let arena = &mut ctx.accounts.arena;
+
+        require!(arena.is_finished(), CryptarenaError::ArenaNotFinished);
+        require!(arena.outstanding_claims == 0, CryptarenaError::OutstandingClaims);
+
+        let arena_seed_key = arena.seed_key();
+        let arena_id_bytes = arena.id.to_le_bytes();
+        let seeds = &[
+            b"arena".as_ref(),
+            arena_seed_key.as_ref(),
+            arena_id_bytes.as_ref(),
+            &[arena.bump],
+        ];
+        let signer = &[&seeds[..]];
+
+        let swept_amount = ctx.accounts.arena_vault.amount;
+        if swept_amount > 0 {
+            let transfer_ctx = CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.arena_vault.to_account_info(),
+                    to: ctx.accounts.treasury_vault.to_account_info(),
+                    authority: arena_info.clone(),
+                },
+                signer,
+            );
+            token::transfer(transfer_ctx, swept_amount)?;
+        }
+
+        let close_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            CloseAccount {
+                account: ctx.accounts.arena_vault.to_account_info(),
+                destination: ctx.accounts.rent_receiver.to_account_info(),
+                authority: arena_info,
+            },
+            signer,
+        );
+        token::close_account(close_ctx)?;
+
+        arena.vault_payers[asset_index as usize] = Pubkey::default();
+
+        emit_cpi!(ArenaVaultClosed {
+            arena: arena.key(),
+            arena_id: arena.id,
+            asset_index,
+            mint: ctx.accounts.arena_vault.mint,
+            rent_receiver: ctx.accounts.rent_receiver.key(),
+            swept_amount,
+        });
+
+        msg!("Closed asset {} vault of arena {}", asset_index, arena.id);
+        Ok(())
+    }
+
+    /// Close a finished arena once every claim is paid and its entries and vaults are closed
+    /// Permissionless so a crank can sweep finished arenas; rent always goes to the arena's payer.
+    /// Pass `arena_allowlist` to reclaim the creator's allowlist rent as well.
+    pub fn close_arena(ctx: Context<CloseArena>) -> Result<()> {
+        let arena = &ctx.accounts.arena;
+
+        require!(arena.is_finished(), CryptarenaError::ArenaNotFinished);
+        require!(arena.outstanding_claims == 0, CryptarenaError::OutstandingClaims);
+        require!(
+            arena.open_entries == 0
+                && arena.vault_payers.iter().all(|payer| *payer == Pubkey::default()),
+            CryptarenaError::ArenaAccountsOpen
+        );
+
+        emit_cpi!(ArenaClosed {
+            arena: arena.key(),
+            arena_id: arena.id,
+            status: arena.status,
+            rent_payer: arena.rent_payer,
+        });
+
+        msg!("Closed arena {} | Rent returned to {}", arena.id, arena.rent_payer);
+        Ok(())
+    }
+
+    /// Initialize user vault for one mint
+    pub fn init_user_vault(ctx: Context<InitUserVault>) -> Result<()> {
+        let user_vault = &mut ctx.accounts.user_vault;
+        user_vault.owner = ctx.accounts.user.key();
+        user_vault.mint = ctx.accounts.mint.key();
+        user_vault.available_balance = 0;
+        user_vault.bump = ctx.bumps.user_vault;
+
+        msg!("User vault initialized for {} with mint {}", ctx.accounts.user.key(), user_vault.mint);
+        Ok(())
+    }
+
+    /// Deposit tokens into the user vault so later entries can be paid from it
+    pub fn deposit_to_vault(ctx: Context<DepositToVault>, amount: u64) -> Result<()> {
+        require!(amount > 0, CryptarenaError::InvalidDepositAmount);
+
+        let transfer_ctx = CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            Transfer {
+                from: ctx.accounts.user_token_account.to_account_info(),
+                to: ctx.accounts.protocol_vault.to_account_info(),
+                authority: ctx.accounts.user.to_account_info(),
+            },
+        );
+        token::transfer(transfer_ctx, amount)?;
+
+        let user_vault = &mut ctx.accounts.user_vault;
+        user_vault.available_balance += amount;
+
+        emit_cpi!(UserVaultDeposited {
+            owner: user_vault.owner,
+            mint: user_vault.mint,
+            amount,
+            available_balance: user_vault.available_balance,
+        });
+
+        msg!("User {} deposited {} to vault", ctx.accounts.user.key(), amount);
+        Ok(())
+    }
+
+    /// Withdraw from user vault
+    pub fn withdraw_from_vault(
+        ctx: Context<WithdrawFromVault>,
+        amount: u64,
+    ) -> Result<()> {
+        let user_vault = &mut ctx.accounts.user_vault;
+
+        require!(
+            user_vault.available_balance >= amount,
+            CryptarenaError::InsufficientBalance
+        );
+
+        user_vault.available_balance -= amount;
+
+        // Transfer tokens from protocol vault to user
+        let global_state = &ctx.accounts.global_state;
+        let seeds = &[
+            b"global_state".as_ref(),
+            &[global_state.bump],
+        ];
+        let signer = &[&seeds[..]];
+
+        let transfer_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            Transfer {
+                from: ctx.accounts.protocol_vault.to_account_info(),
+                to: ctx.accounts.user_token_account.to_account_info(),
+                authority: ctx.accounts.global_state.to_account_info(),
+            },
+            signer,
+        );
+        token::transfer(transfer_ctx, amount)?;
+
+        emit_cpi!(UserVaultWithdrawn {
+            owner: ctx.accounts.user_vault.owner,
+            mint: ctx.accounts.user_vault.mint,
+            amount,
+            available_balance: ctx.accounts.user_vault.available_balance,
+        });
+
+        msg!("User {} withdrew {} from vault", ctx.accounts.user.key(), amount);
+        Ok(())
+    }
+
+    /// Move the treasury fee owed on one asset vault of an ended arena into the treasury vault
+    /// Permissionless: the amount and destination are fixed by the arena's accounting
+    pub fn collect_arena_fee(ctx: Context<CollectArenaFee>, asset_index: u8) -> Result<()> {
+        let arena_info = ctx.accounts.arena.to_account_info();
+        let arena = &mut ctx.accounts.arena;
+
+        require!(
+            arena.status == ArenaStatus::Ended as u8,
+            CryptarenaError::ArenaNotEnded
+        );
+        require!(
+            arena.asset_counts[asset_index as usize] > 0,
+            CryptarenaError::AssetNotInArena
+        );
+
+        let asset_bit = 1u16 << asset_index;
+        require!(
+            arena.treasury_fees_collected_bitmap & asset_bit == 0,
+            CryptarenaError::TreasuryFeeAlreadyCollected
+        );
+
+        let fee = arena.asset_treasury_fees[asset_index as usize];
+
+        let arena_seed_key = arena.seed_key();
+        let arena_id_bytes = arena.id.to_le_bytes();
+        let seeds = &[
+            b"arena".as_ref(),
+            arena_seed_key.as_ref(),
+            arena_id_bytes.as_ref(),
+            &[arena.bump],
+        ];
+        let signer = &[&seeds[..]];
+
+        let transfer_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            Transfer {
+                from: ctx.accounts.arena_vault.to_account_info(),
+                to: ctx.accounts.treasury_vault.to_account_info(),
+                authority: arena_info,
+            },
+            signer,
+        );
+        token::transfer(transfer_ctx, fee)?;
+
+        arena.treasury_fees_collected_bitmap |= asset_bit;
+        if fee > 0 {
+            arena.outstanding_claims -= 1;
+        }
+        arena.treasury_collected =
+            arena.treasury_fees_collected_bitmap == arena.represented_assets_bitmap();
+
+        emit_cpi!(TreasuryFeeCollected {
+            arena: arena.key(),
+            arena_id: arena.id,
+            asset_index,
+            mint: arena.asset_mints[asset_index as usize],
+            amount: fee,
+        });
+
+        msg!("Treasury collected {} of asset {} from arena {}", fee, asset_index, arena.id);
+        Ok(())
+    }
+
+    /// Pay the settling keeper its crank bounty from one asset vault, in that vault's mint
+    pub fn claim_crank_bounty(ctx: Context<ClaimCrankBounty>, asset_index: u8) -> Result<()> {
+        let arena_info = ctx.accounts.arena.to_account_info();
+        let arena = &mut ctx.accounts.arena;
+
+        require!(
+            arena.status == ArenaStatus::Ended as u8,
+            CryptarenaError::ArenaNotEnded
+        );
+        require!(
+            arena.asset_counts[asset_index as usize] > 0,
+            CryptarenaError::AssetNotInArena
+        );
+
+        let asset_bit = 1u16 << asset_index;
+        require!(
+            arena.crank_bounties_claimed_bitmap & asset_bit == 0,
+            CryptarenaError::CrankBountyAlreadyClaimed
+        );
+
+        let bounty = arena.asset_crank_bounties[asset_index as usize];
+
+        let arena_seed_key = arena.seed_key();
+        let arena_id_bytes = arena.id.to_le_bytes();
+        let seeds = &[
+            b"arena".as_ref(),
+            arena_seed_key.as_ref(),
+            arena_id_bytes.as_ref(),
+            &[arena.bump],
+        ];
+        let signer = &[&seeds[..]];
+
+        let transfer_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            Transfer {
+                from: ctx.accounts.arena_vault.to_account_info(),
+                to: ctx.accounts.keeper_token_account.to_account_info(),
+                authority: arena_info,
+            },
+            signer,
+        );
+        token::transfer(transfer_ctx, bounty)?;
+
+        arena.crank_bounties_claimed_bitmap |= asset_bit;
+        if bounty > 0 {
+            arena.outstanding_claims -= 1;
+        }
+
+        emit_cpi!(CrankBountyClaimed {
+            arena: arena.key(),
+            arena_id: arena.id,
+            keeper: arena.crank_recipient,
+            asset_index,
+            mint: arena.asset_mints[asset_index as usize],
+            amount: bounty,
+        });
+
+        msg!("Keeper {} claimed {} of asset {} from arena {}",
+            arena.crank_recipient, bounty, asset_index, arena.id);
+        Ok(())
+    }
+
+    /// Pay a custom arena's creator its fee from one asset vault, in that vault's mint
+    pub fn claim_creator_fee(ctx: Context<ClaimCreatorFee>, asset_index: u8) -> Result<()> {
+        let arena_info = ctx.accounts.arena.to_account_info();
+        let arena = &mut ctx.accounts.arena;
+
+        require!(
+            arena.status == ArenaStatus::Ended as u8,
+            CryptarenaError::ArenaNotEnded
+        );
+        require!(
+            arena.asset_counts[asset_index as usize] > 0,
+            CryptarenaError::AssetNotInArena
+        );
+
+        let asset_bit = 1u16 << asset_index;
+        require!(
+            arena.creator_fees_claimed_bitmap & asset_bit == 0,
+            CryptarenaError::CreatorFeeAlreadyClaimed
+        );
+
+        let fee = arena.asset_creator_fees[asset_index as usize];
+
+        let arena_seed_key = arena.seed_key();
+        let arena_id_bytes = arena.id.to_le_bytes();
+        let seeds = &[
+            b"arena".as_ref(),
+            arena_seed_key.as_ref(),
+            arena_id_bytes.as_ref(),
+            &[arena.bump],
+        ];
+        let signer = &[&seeds[..]];
+
+        let transfer_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            Transfer {
+                from: ctx.accounts.arena_vault.to_account_info(),
+                to: ctx.accounts.creator_token_account.to_account_info(),
+                authority: arena_info,
+            },
+            signer,
+        );
+        token::transfer(transfer_ctx, fee)?;
+
+        arena.creator_fees_claimed_bitmap |= asset_bit;
+        if fee > 0 {
+            arena.outstanding_claims -= 1;
+        }
+
+        emit_cpi!(CreatorFeeClaimed {
+            arena: arena.key(),
+            arena_id: arena.id,
+            creator: arena.creator,
+            asset_index,
+            mint: arena.asset_mints[asset_index as usize],
+            amount: fee,
+        });
+
+        msg!("Creator {} claimed {} of asset {} from arena {}",
+            arena.creator, fee, asset_index, arena.id);
+        Ok(())
+    }
+
+    /// Move a referred entry's cut of an ended arena's treasury fee to its referrer's balance (permissionless)
+    pub fn credit_referral(ctx: Context<CreditReferral>) -> Result<()> {
+        let arena_info = ctx.accounts.arena.to_account_info();
+        let arena = &mut ctx.accounts.arena;
+        let player_entry = &mut ctx.accounts.player_entry;
+        let referrer_balance = &mut ctx.accounts.referrer_balance;
+
+        require!(
+            arena.status == ArenaStatus::Ended as u8,
+            CryptarenaError::ArenaNotEnded
+        );
+        require!(
+            player_entry.referral_fee > 0 && !arena.is_asset_void(player_entry.asset_index),
+            CryptarenaError::NoReferralFee
+        );
+
+        let amount = player_entry.referral_fee;
+        let arena_seed_key = arena.seed_key();
+        let arena_id_bytes = arena.id.to_le_bytes();
+        let seeds = &[
+            b"arena".as_ref(),
+            arena_seed_key.as_ref(),
+            arena_id_bytes.as_ref(),
+            &[arena.bump],
+        ];
+        let signer = &[&seeds[..]];
+
+        let transfer_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            Transfer {
+                from: ctx.accounts.arena_vault.to_account_info(),
+                to: ctx.accounts.referral_vault.to_account_info(),
+                authority: arena_info,
+            },
+            signer,
+        );
+        token::transfer(transfer_ctx, amount)?;
+
+        if referrer_balance.referrer == Pubkey::default() {
+            referrer_balance.referrer = player_entry.referrer;
+            referrer_balance.mint = ctx.accounts.mint.key();
+            referrer_balance.bump = ctx.bumps.referrer_balance;
+        }
+        referrer_balance.balance += amount;
+        referrer_balance.total_earned += amount;
+        player_entry.referral_fee = 0;
+        arena.outstanding_claims -= 1;
+
+        emit_cpi!(ReferralCredited {
+            arena: arena.key(),
+            arena_id: arena.id,
+            player: player_entry.player,
+            referrer: player_entry.referrer,
+            mint: referrer_balance.mint,
+            amount,
+            balance: referrer_balance.balance,
+        });
+
+        msg!("Referrer {} credited {} for player {} in arena {}",
+            player_entry.referrer, amount, player_entry.player, arena.id);
+        Ok(())
+    }
+
+    /// Referrer: withdraw the accrued referral balance of one mint
+    pub fn claim_referral_rewards(ctx: Context<ClaimReferralRewards>) -> Result<()> {
+        let referrer_balance = &mut ctx.accounts.referrer_balance;
+        let amount = referrer_balance.balance;
+        require!(amount > 0, CryptarenaError::NoReferralFee);
+
+        let seeds = &[
+            b"global_state".as_ref(),
+            &[ctx.accounts.global_state.bump],
+        ];
+        let signer = &[&seeds[..]];
+
+        let transfer_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            Transfer {
+                from: ctx.accounts.referral_vault.to_account_info(),
+                to: ctx.accounts.referrer_token_account.to_account_info(),
+                authority: ctx.accounts.global_state.to_account_info(),
+            },
+            signer,
+        );
+        token::transfer(transfer_ctx, amount)?;
+
+        referrer_balance.balance = 0;
+
+        emit_cpi!(ReferralRewardsClaimed {
+            referrer: referrer_balance.referrer,
+            mint: referrer_balance.mint,
+            amount,
+        });
+
+        msg!("Referrer {} claimed {} of mint {}", referrer_balance.referrer, amount, referrer_balance.mint);
+        Ok(())
+    }
+
+    /// Transfer collected fees from a treasury vault to the treasury wallet (admin only)
+    pub fn transfer_treasury(
+        ctx: Context<TransferTreasury>,
+        amount: u64,
+    ) -> Result<()> {
+        let global_state = &ctx.accounts.global_state;
+        let seeds = &[
+            b"global_state".as_ref(),
+            &[global_state.bump],
+        ];
+        let signer = &[&seeds[..]];
+
+        let transfer_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            Transfer {
+                from: ctx.accounts.treasury_vault.to_account_info(),
+                to: ctx.accounts.destination.to_account_info(),
+                authority: ctx.accounts.global_state.to_account_info(),
+            },
+            signer,
+        );
+        token::transfer(transfer_ctx, amount)?;
+
+        emit_cpi!(TreasuryTransferred {
+            mint: ctx.accounts.treasury_vault.mint,
+            destination: ctx.accounts.destination.key(),
+            amount,
+        });
+
+        msg!("Treasury transfer: {} to {}", amount, ctx.accounts.destination.key());
+        Ok(())
+    }
+
+    /// Open a season paying one mint to its top players (admin only)
+    /// `payout_bps` is the prize vault share of each rank, best first, and must sum to 10000.
+    pub fn create_season(
+        ctx: Context<CreateSeason>,
+        season_id: u64,
+        start_timestamp: i64,
+        end_timestamp: i64,
+        payout_bps: Vec<u16>,
+    ) -> Result<()> {
+        require!(end_timestamp > start_timestamp, CryptarenaError::InvalidSeasonWindow);
+        require!(
+            !payout_bps.is_empty()
+                && payout_bps.len() <= MAX_SEASON_WINNERS
+                && payout_bps.iter().map(|bps| *bps as u64).sum::<u64>() == 10000,
+            CryptarenaError::InvalidPayoutSplit
+        );
+
+        let season = &mut ctx.accounts.season;
+        season.id = season_id;
+        season.mint = ctx.accounts.mint.key();
+        season.start_timestamp = start_timestamp;
+        season.end_timestamp = end_timestamp;
+        season.winner_count = payout_bps.len() as u8;
+        season.payout_bps = [0u16; MAX_SEASON_WINNERS];
+        season.payout_bps[..payout_bps.len()].copy_from_slice(&payout_bps);
+        season.participants = 0;
+        season.ranked_participants = 0;
+        season.leaders = [Pubkey::default(); MAX_SEASON_WINNERS];
+        season.leader_points = [0u64; MAX_SEASON_WINNERS];
+        season.status = SeasonStatus::Active as u8;
+        season.rent_payer = ctx.accounts.admin.key();
+        season.bump = ctx.bumps.season;
+        ctx.accounts.global_state.cover_season(start_timestamp, end_timestamp);
+
+        emit_cpi!(SeasonCreated {
+            season: season.key(),
+            season_id,
+            mint: season.mint,
+            start_timestamp,
+            end_timestamp,
+            payout_bps,
+        });
+
+        msg!("Season {} created: {} to {}, top {} paid", 
+            season_id, start_timestamp, end_timestamp, season.winner_count);
+        Ok(())
+    }
+
+    /// Add tokens to an active season's prize vault (anyone can fund)
+    pub fn fund_season(ctx: Context<FundSeason>, amount: u64) -> Result<()> {
+        let season = &ctx.accounts.season;
+        require!(
+            season.status == SeasonStatus::Active as u8,
+            CryptarenaError::SeasonNotActive
+        );
+        require!(amount > 0, CryptarenaError::InvalidDepositAmount);
+
+        let transfer_ctx = CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            Transfer {
+                from: ctx.accounts.funder_token_account.to_account_info(),
+                to: ctx.accounts.season_vault.to_account_info(),
+                authority: ctx.accounts.funder.to_account_info(),
+            },
+        );
+        token::transfer(transfer_ctx, amount)?;
+
+        emit_cpi!(SeasonFunded {
+            season: season.key(),
+            season_id: season.id,
+            funder: ctx.accounts.funder.key(),
+            amount,
+        });
+
+        msg!("Season {} funded with {}", season.id, amount);
+        Ok(())
+    }
+
+    /// Credit a player's entry in an ended arena to the season it ended in (permissionless)
+    /// Must run before the entry is closed. Points: participation, plus win and margin-of-victory
+    /// bonuses for entries on the winning asset.
+    pub fn record_season_score(ctx: Context<RecordSeasonScore>) -> Result<()> {
+        let season_key = ctx.accounts.season.key();
+        let season = &mut ctx.accounts.season;
+        let arena = &ctx.accounts.arena;
+        let player_entry = &mut ctx.accounts.player_entry;
+        let season_score = &mut ctx.accounts.season_score;
+
+        require!(
+            season.status == SeasonStatus::Active as u8,
+            CryptarenaError::SeasonNotActive
+        );
+        require!(
+            arena.status == ArenaStatus::Ended as u8,
+            CryptarenaError::ArenaNotEnded
+        );
+        require!(
+            (season.start_timestamp..season.end_timestamp).contains(&arena.end_timestamp),
+            CryptarenaError::ArenaOutsideSeason
+        );
+        require!(!player_entry.season_credited, CryptarenaError::AlreadyCredited);
+        // Voided entries were refunded and did not take part in the result
+        require!(
+            !arena.is_asset_void(player_entry.asset_index),
+            CryptarenaError::AssetVoided
+        );
+
+        if season_score.player == Pubkey::default() {
+            season_score.season = season_key;
+            season_score.player = player_entry.player;
+            season_score.rent_payer = ctx.accounts.caller.key();
+            season_score.bump = ctx.bumps.season_score;
+            season.participants += 1;
+        }
+
+        let mut points = SEASON_PARTICIPATION_POINTS;
+        if player_entry.asset_index == arena.winning_asset {
+            let margin_points = (margin_of_victory_bps(arena) / 10).min(SEASON_MAX_MARGIN_POINTS);
+            points += SEASON_WIN_POINTS + margin_points;
+            season_score.wins += 1;
+        }
+        season_score.points += points;
+        season_score.arenas_played += 1;
+        player_entry.season_credited = true;
+
+        emit_cpi!(SeasonScoreRecorded {
+            season: season_key,
+            season_id: season.id,
+            arena: arena.key(),
+            arena_id: arena.id,
+            player: player_entry.player,
+            points,
+            total_points: season_score.points,
+        });
+
+        msg!("Season {}: player {} +{} points (total {})", 
+            season.id, player_entry.player, points, season_score.points);
+        Ok(())
+    }
+
+    /// Rank a batch of an ended season's scores into its on-chain leaderboard (permissionless)
+    /// Remaining accounts: writable `SeasonScore` accounts of the season, each counted only once.
+    /// The first batch freezes scoring, so batches can be spread over as many transactions as needed.
+    pub fn rank_season_scores<'info>(
+        ctx: Context<'_, '_, 'info, 'info, RankSeasonScores<'info>>,
+    ) -> Result<()> {
+        let season_key = ctx.accounts.season.key();
+        let season = &mut ctx.accounts.season;
+        let clock = Clock::get()?;
+
+        require!(
+            season.status == SeasonStatus::Active as u8
+                || season.status == SeasonStatus::Ranking as u8,
+            CryptarenaError::SeasonNotActive
+        );
+        require!(
+            clock.unix_timestamp >= season.end_timestamp,
+            CryptarenaError::SeasonNotEnded
+        );
+        require!(
+            !ctx.remaining_accounts.is_empty(),
+            CryptarenaError::InvalidRemainingAccounts
+        );
+        season.status = SeasonStatus::Ranking as u8;
+
+        for account_info in ctx.remaining_accounts {
+            let mut score: Account<SeasonScore> = Account::try_from(account_info)?;
+            require!(score.season == season_key, CryptarenaError::InvalidRanking);
+            require!(!score.ranked, CryptarenaError::AlreadyRanked);
+
+            season.rank(score.player, score.points);
+            score.ranked = true;
+            score.exit(ctx.program_id)?;
+        }
+
+        emit_cpi!(SeasonScoresRanked {
+            season: season_key,
+            season_id: season.id,
+            ranked_participants: season.ranked_participants,
+            participants: season.participants,
+        });
+
+        msg!("Season {} ranked {}/{} scores", 
+            season.id, season.ranked_participants, season.participants);
+        Ok(())
+    }
+
+    /// Pay an ended season's prize vault to its leaderboard and close the vault (permissionless)
+    /// Every score must be ranked with `rank_season_scores` first. Remaining accounts: one token
+    /// account of the season mint per paid rank, best first. Whatever the ranks do not pay out
+    /// goes to the treasury vault.
+    pub fn close_season<'info>(
+        ctx: Context<'_, '_, 'info, 'info, CloseSeason<'info>>,
+    ) -> Result<()> {
+        let season_info = ctx.accounts.season.to_account_info();
+        let season = &mut ctx.accounts.season;
+        let clock = Clock::get()?;
+
+        require!(
+            season.status == SeasonStatus::Active as u8
+                || season.status == SeasonStatus::Ranking as u8,
+            CryptarenaError::SeasonNotActive
+        );
+        require!(
+            clock.unix_timestamp >= season.end_timestamp,
+            CryptarenaError::SeasonNotEnded
+        );
+        require!(
+            season.ranked_participants == season.participants,
+            CryptarenaError::RankingIncomplete
+        );
+
+        let paid_ranks = (season.participants as usize).min(season.winner_count as usize);
+        require!(
+            ctx.remaining_accounts.len() == paid_ranks,
+            CryptarenaError::InvalidRemainingAccounts
+        );
+        let payout_accounts = ctx.remaining_accounts;
+
+        let season_id_bytes = season.id.to_le_bytes();
+        let seeds = &[
+            b"season".as_ref(),
+            season_id_bytes.as_ref(),
+            &[season.bump],
+        ];
+        let signer = &[&seeds[..]];
+
+        let prize_pool = ctx.accounts.season_vault.amount;
+        let mut winners = Vec::with_capacity(paid_ranks);
+        let mut payouts = Vec::with_capacity(paid_ranks);
+        for (rank, account_info) in payout_accounts.iter().enumerate() {
+            let winner = season.leaders[rank];
+            let winner_token_account: Account<TokenAccount> = Account::try_from(account_info)?;
+            require!(
+                winner_token_account.owner == winner,
+                CryptarenaError::Unauthorized
+            );
+            require!(
+                winner_token_account.mint == season.mint,
+                CryptarenaError::InvalidMint
+            );
+
+            let payout = (prize_pool * season.payout_bps[rank] as u64) / 10000;
+            let transfer_ctx = CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.season_vault.to_account_info(),
+                    to: account_info.clone(),
+                    authority: season_info.clone(),
+                },
+                signer,
+            );
+            token::transfer(transfer_ctx, payout)?;
+
+            winners.push(winner);
+            payouts.push(payout);
+        }
+
+        // Rounding dust and unfilled ranks go to the treasury
+        let treasury_amount = prize_pool - payouts.iter().sum::<u64>();
+        let transfer_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            Transfer {
+                from: ctx.accounts.season_vault.to_account_info(),
+                to: ctx.accounts.treasury_vault.to_account_info(),
+                authority: season_info.clone(),
+            },
+            signer,
+        );
+        token::transfer(transfer_ctx, treasury_amount)?;
+
+        let close_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            CloseAccount {
+                account: ctx.accounts.season_vault.to_account_info(),
+                destination: ctx.accounts.rent_payer.to_account_info(),
+                authority: season_info,
+            },
+            signer,
+        );
+        token::close_account(close_ctx)?;
+
+        season.status = SeasonStatus::Closed as u8;
+
+        emit_cpi!(SeasonClosed {
+            season: season.key(),
+            season_id: season.id,
+            participants: season.participants,
+            winners,
+            payouts,
+            treasury_amount,
+        });
+
+        msg!("Season {} closed | Prize pool: {} | Paid ranks: {} | To treasury: {}", 
+            season.id, prize_pool, paid_ranks, treasury_amount);
+        Ok(())
+    }
+
+    /// Sweep a season nobody could close to the treasury vault and close it (admin only)
+    /// Only allowed `SEASON_FORCE_CLOSE_DELAY` after the season ends, as a fallback for a stuck ranking.
+    pub fn force_close_season(ctx: Context<ForceCloseSeason>) -> Result<()> {
+        let season_info = ctx.accounts.season.to_account_info();
+        let season = &mut ctx.accounts.season;
+        let clock = Clock::get()?;
+
+        require!(
+            season.status != SeasonStatus::Closed as u8,
+            CryptarenaError::SeasonNotActive
+        );
+        require!(
+            clock.unix_timestamp >= season.end_timestamp + SEASON_FORCE_CLOSE_DELAY,
+            CryptarenaError::ForceCloseTooEarly
+        );
+
+        let season_id_bytes = season.id.to_le_bytes();
+        let seeds = &[
+            b"season".as_ref(),
+            season_id_bytes.as_ref(),
+            &[season.bump],
+        ];
+        let signer = &[&seeds[..]];
+
+        let treasury_amount = ctx.accounts.season_vault.amount;
+        let transfer_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            Transfer {
+                from: ctx.accounts.season_vault.to_account_info(),
+                to: ctx.accounts.treasury_vault.to_account_info(),
+                authority: season_info.clone(),
+            },
+            signer,
+        );
+        token::transfer(transfer_ctx, treasury_amount)?;
+
+        let close_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            CloseAccount {
+                account: ctx.accounts.season_vault.to_account_info(),
+                destination: ctx.accounts.rent_payer.to_account_info(),
+                authority: season_info,
+            },
+            signer,
+        );
+        token::close_account(close_ctx)?;
+
+        season.status = SeasonStatus::Closed as u8;
+
+        emit_cpi!(SeasonClosed {
+            season: season.key(),
+            season_id: season.id,
+            participants: season.participants,
+            winners: Vec::new(),
+            payouts: Vec::new(),
+            treasury_amount,
+        });
+
+        msg!("Season {} force-closed | {} swept to treasury", season.id, treasury_amount);
+        Ok(())
+    }
+
+    /// Close a score of a closed season and return its rent to whoever recorded it first (permissionless)
+    pub fn close_season_score(ctx: Context<CloseSeasonScore>) -> Result<()> {
+        let season = &ctx.accounts.season;
+        let season_score = &ctx.accounts.season_score;
+
+        require!(
+            season.status == SeasonStatus::Closed as u8,
+            CryptarenaError::SeasonNotClosed
+        );
+
+        emit_cpi!(SeasonScoreClosed {
+            season: season.key(),
+            season_id: season.id,
+            player: season_score.player,
+            rent_payer: season_score.rent_payer,
+        });
+
+        msg!("Closed season {} score of player {}", season.id, season_score.player);
+        Ok(())
+    }
+}
+
+// ============================================================================
+// HELPER FUNCTIONS
+// ============================================================================
+
+/// When a change queued now can execute, under the current settings timelock
+fn settings_executable_at(global_state: &GlobalState) -> Result<i64> {
+    Ok(Clock::get()?.unix_timestamp + global_state.settings_timelock)
+}
+
+/// Merge changes into the pending settings and return when they can execute
+/// Only the fields `change` queues get the new timelock; the rest keep their own.
+fn queue_settings(
+    global_state: &mut GlobalState,
+    change: impl FnOnce(&mut PendingSettings, i64),
+) -> Result<i64> {
+    let executable_at = settings_executable_at(global_state)?;
+    let mut pending = global_state.pending_settings.unwrap_or_default();
+    change(&mut pending, executable_at);
+    validate_settings(global_state, &pending)?;
+    global_state.pending_settings = Some(pending);
+    Ok(executable_at)
+}
+
+/// Queue `change` into its pending slot, replacing the value and timelock already there
+fn queue_change<T>(slot: &mut Option<Queued<T>>, change: Option<T>, executable_at: i64) {
+    if let Some(value) = change {
+        *slot = Some(Queued { value, executable_at });
+    }
+}
+
+/// Check a queued settings change against the current global state
+fn validate_settings(global_state: &GlobalState, pending: &PendingSettings) -> Result<()> {
+    if let Some(duration) = pending.arena_duration {
+        require!(duration.value > 0, CryptarenaError::InvalidDuration);
+    }
+    if let Some(timeout) = pending.lobby_timeout {
+        require!(timeout.value > 0, CryptarenaError::InvalidDuration);
+    }
+
+    // The treasury fee cap, crank bounty and maximum creator fee together must fit in the pool
+    let max_fee_bps = pending
+        .fee_schedule
+        .map_or(global_state.fee_schedule.max_fee_bps, |schedule| schedule.value.max_fee_bps);
+    let bounty_bps = pending
+        .crank_bounty_bps
+        .map_or(global_state.crank_bounty_bps, |bounty_bps| bounty_bps.value);
+    let max_creator_fee_bps = pending
+        .custom_arena_bounds
+        .map_or(global_state.max_creator_fee_bps, |bounds| bounds.value.max_creator_fee_bps);
+    let fees_fit = max_fee_bps + bounty_bps + max_creator_fee_bps <= 10000;
+    if pending.crank_bounty_bps.is_some() {
+        require!(fees_fit, CryptarenaError::InvalidCrankBounty);
+    }
+    if let Some(fee_schedule) = pending.fee_schedule {
+        require!(
+            fee_schedule.value.is_valid() && fees_fit,
+            CryptarenaError::InvalidFeeSchedule
+        );
+    }
+    if let Some(Queued { value: bounds, .. }) = pending.custom_arena_bounds {
+        require!(
+            bounds.min_duration > 0 && bounds.min_duration <= bounds.max_duration,
+            CryptarenaError::InvalidDuration
+        );
+        require!(fees_fit, CryptarenaError::CreatorFeeTooHigh);
+    }
+    if let Some(timelock) = pending.settings_timelock {
+        require!(timelock.value >= 0, CryptarenaError::InvalidTimelock);
+    }
+    if let Some(grace_period) = pending.oracle_grace_period {
+        require!(grace_period.value >= 0, CryptarenaError::InvalidDuration);
+    }
+    if let Some(share_bps) = pending.referral_share_bps {
+        require!(share_bps.value <= 10000, CryptarenaError::InvalidReferralShare);
+    }
+    Ok(())
+}
+
+/// Second PDA seed of an arena: the tier id for public arenas, the creator for custom ones
+pub fn arena_seed_key(tier_id: u8, creator: Option<Pubkey>) -> Vec<u8> {
+    match creator {
+        Some(creator) => creator.to_bytes().to_vec(),
+        None => vec![tier_id],
+    }
+}
+
+/// Shared body of `enter_arena` and `enter_arena_from_vault`
+fn process_entry<'info>(
+    ctx: Context<'_, '_, 'info, 'info, EnterArena<'info>>,
+    asset_index: u8,
+    amount: u64,
+    arena_type: ArenaType,
+    tier_id: u8,
+    custom_arena: Option<CustomArenaKey>,
+    invite_secret: Option<Vec<u8>>,
+    referrer: Option<Pubkey>,
+    from_vault: bool,
+) -> Result<()> {
+    let global_state = &ctx.accounts.global_state;
+    let stake_tier = &mut ctx.accounts.stake_tier;
+    let clock = Clock::get()?;
+
+    // Validate protocol is not paused
+    require!(!global_state.is_paused, CryptarenaError::ProtocolPaused);
+
+    // Get price from Pyth oracle
+    let asset_config = &ctx.accounts.asset_config;
+    let price = read_fresh_pyth_price(&ctx.accounts.price_update, asset_config, &clock)?;
+
+    // Calculate USD value of entry
+    let price_value = price.price as u64;
+    let expo = price.exponent;
+    let usd_value = calculate_usd_value(amount, price_value, expo, asset_config.decimals)?;
+
+    // Validate entry amount is within the tier's bounds
+    require!(
+        (stake_tier.min_entry_usd..=stake_tier.max_entry_usd).contains(&usd_value),
+        CryptarenaError::InvalidEntryAmount
+    );
+
+    // Get or create arena
+    let arena = &mut ctx.accounts.arena;
+    
+    if arena.status == ArenaStatus::Uninitialized as u8 {
+        // Custom arenas only exist once `create_arena` has opened them
+        require!(custom_arena.is_none(), CryptarenaError::ArenaNotWaiting);
+
+        // Initialize new arena
+        arena.id = stake_tier.current_arena_id;
+        arena.tier_id = tier_id;
+        arena.creator = Pubkey::default();
+        arena.status = ArenaStatus::Waiting as u8;
+        arena.arena_type = arena_type as u8;
+        arena.max_players = MAX_PLAYERS_PER_ARENA as u8;
+        arena.duration = global_state.arena_duration;
+        arena.creator_fee_bps = 0;
+        arena.creator_fee = 0;
+        arena.asset_creator_fees = [0u64; MAX_ASSETS];
+        arena.creator_fees_claimed_bitmap = 0;
+        arena.fee_schedule = global_state.fee_schedule.snapshot(clock.unix_timestamp);
+        arena.referral_share_bps = global_state.referral_share_bps;
+        arena.crank_bounty_bps = global_state.crank_bounty_bps;
+        require!(arena.fees_fit_pool(), CryptarenaError::InvalidFeeSchedule);
+        arena.player_count = 0;
+        arena.asset_counts = [0u8; MAX_ASSETS];
+        arena.asset_usd_totals = [0u64; MAX_ASSETS];
+        arena.asset_mints = [Pubkey::default(); MAX_ASSETS];
+        arena.asset_amounts = [0u64; MAX_ASSETS];
+        arena.vault_claims = [0u8; MAX_ASSETS];
+        arena.vault_paid = [0u64; MAX_ASSETS];
+        arena.treasury_fee = 0;
+        arena.asset_treasury_fees = [0u64; MAX_ASSETS];
+        arena.treasury_fees_collected_bitmap = 0;
+        arena.treasury_collected = false;
+        arena.crank_bounty = 0;
+        arena.asset_crank_bounties = [0u64; MAX_ASSETS];
+        arena.crank_recipient = Pubkey::default();
+        arena.crank_bounties_claimed_bitmap = 0;
+        arena.created_timestamp = clock.unix_timestamp;
+        arena.start_timestamp = 0;
+        arena.end_timestamp = 0;
+        arena.total_pool = 0;
+        arena.winning_asset = 255; // Invalid/unset
+        arena.is_suspended = false;
+        arena.rent_payer = ctx.accounts.player.key();
+        arena.vault_payers = [Pubkey::default(); MAX_ASSETS];
+        arena.open_entries = 0;
+        arena.outstanding_claims = 0;
+        arena.asset_referral_fees = [0u64; MAX_ASSETS];
+        arena.referred_entries = 0;
+        arena.bump = ctx.bumps.arena;
+
+        stake_tier.waiting_arenas[arena_type as usize] = Some(arena.id);
+        stake_tier.current_arena_id += 1;
+
+        emit_cpi!(arena_created_event(arena));
+    }
+
+    // Validate arena is in waiting status
+    require!(
+        arena.status == ArenaStatus::Waiting as u8,
+        CryptarenaError::ArenaNotWaiting
+    );
+    require!(
+        arena.tier_id == tier_id && arena.arena_type == arena_type as u8,
+        CryptarenaError::ArenaMismatch
+    );
+
+    check_arena_access(
+        arena,
+        &ctx.accounts.player.key(),
+        ctx.accounts.arena_allowlist.as_deref(),
+        invite_secret.as_deref(),
+    )?;
+
+    // Stale lobbies must be expired (and refunded) before a new one can open
+    require!(
+        clock.unix_timestamp < arena.created_timestamp + global_state.lobby_timeout,
+        CryptarenaError::LobbyExpired
+    );
+
+    // Check if max same asset limit reached
+    require!(
+        arena.asset_counts[asset_index as usize] < MAX_SAME_ASSET_PER_ARENA,
+        CryptarenaError::MaxAssetLimitReached
+    );
+
+    // First entrant on an asset fixes its mint; each mint backs exactly one asset
+    let mint = ctx.accounts.mint.key();
+    if arena.asset_counts[asset_index as usize] == 0 {
+        require!(
+            !arena.asset_mints.contains(&mint),
+            CryptarenaError::InvalidMint
+        );
+        arena.asset_mints[asset_index as usize] = mint;
+    } else {
+        require!(
+            arena.asset_mints[asset_index as usize] == mint,
+            CryptarenaError::InvalidMint
+        );
+    }
+
+    // Initialize player entry
+    let player_entry = &mut ctx.accounts.player_entry;
+    player_entry.arena = arena.key();
+    player_entry.player = ctx.accounts.player.key();
+    player_entry.asset_index = asset_index;
+    player_entry.amount = amount;
+    player_entry.usd_value = usd_value;
+    player_entry.entry_price = price_value;
+    player_entry.entry_timestamp = clock.unix_timestamp;
+    player_entry.is_winner = false;
+    player_entry.reward_claimed = false;
+    player_entry.claimed_assets_bitmap = 0;
+    player_entry.season_credited = false;
+    player_entry.referrer = Pubkey::default();
+    player_entry.referral_fee = 0;
+    player_entry.bump = ctx.bumps.player_entry;
+
+    // Created on the wallet's first entry, then carried across arenas
+    let player_profile = &mut ctx.accounts.player_profile;
+    if player_profile.player == Pubkey::default() {
+        player_profile.player = ctx.accounts.player.key();
+        player_profile.favorite_asset = 255; // Unset
+        player_profile.bump = ctx.bumps.player_profile;
+    }
+    player_profile.record_entry(asset_index, usd_value);
+
+    // A player's referrer is bound once, on their first entry
+    let referral = &mut ctx.accounts.referral;
+    if referral.player == Pubkey::default() {
+        let referrer = referrer.unwrap_or_default();
+        require!(referrer != ctx.accounts.player.key(), CryptarenaError::SelfReferral);
+        referral.player = ctx.accounts.player.key();
+        referral.referrer = referrer;
+        referral.bump = ctx.bumps.referral;
+
+        if referrer != Pubkey::default() {
+            emit_cpi!(ReferralBound {
+                player: referral.player,
+                referrer,
+            });
+        }
+    }
+
+    // The referrer's cut of this entry's treasury fee is fixed at entry and carved out at settlement;
+    // arenas only start once full, so the fee is already known from the arena's schedule
+    let referral_fee = if referral.referrer == Pubkey::default() {
+        0
+    } else {
+        let treasury_fee_bps = arena.fee_schedule.fee_bps(arena.max_players);
+        (amount * treasury_fee_bps / 10000) * arena.referral_share_bps / 10000
+    };
+    player_entry.referrer = referral.referrer;
+    player_entry.referral_fee = referral_fee;
+    if referral_fee > 0 {
+        arena.asset_referral_fees[asset_index as usize] += referral_fee;
+        arena.referred_entries += 1;
+    }
+
+    // Update arena state
+    let player_count = arena.player_count as usize;
+    arena.players[player_count] = ctx.accounts.player.key();
+    arena.player_assets[player_count] = asset_index;
+    arena.asset_counts[asset_index as usize] += 1;
+    arena.asset_usd_totals[asset_index as usize] += usd_value;
+    arena.asset_amounts[asset_index as usize] += amount;
+    arena.player_count += 1;
+    arena.open_entries += 1;
+    arena.total_pool += usd_value;
+
+    // The entrant that opened this asset's vault gets its rent back when it is closed
+    if arena.vault_payers[asset_index as usize] == Pubkey::default() {
+        arena.vault_payers[asset_index as usize] = ctx.accounts.player.key();
+    }
+
+    // Transfer tokens to arena vault, from the user vault balance or the player's wallet
+    if from_vault {
+        let user_vault = ctx
+            .accounts
+            .user_vault
+            .as_mut()
+            .ok_or(CryptarenaError::MissingFundingAccount)?;
+        require!(
+            user_vault.available_balance >= amount,
+            CryptarenaError::InsufficientBalance
+        );
+        user_vault.available_balance -= amount;
+
+        let protocol_vault = ctx
+            .accounts
+            .protocol_vault
+            .as_ref()
+            .ok_or(CryptarenaError::MissingFundingAccount)?;
+        let seeds = &[
+            b"global_state".as_ref(),
+            &[global_state.bump],
+        ];
+        let signer = &[&seeds[..]];
+
+        let transfer_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            Transfer {
+                from: protocol_vault.to_account_info(),
+                to: ctx.accounts.arena_vault.to_account_info(),
+                authority: global_state.to_account_info(),
+            },
+            signer,
+        );
+        token::transfer(transfer_ctx, amount)?;
+    } else {
+        let player_token_account = ctx
+            .accounts
+            .player_token_account
+            .as_ref()
+            .ok_or(CryptarenaError::MissingFundingAccount)?;
+        let transfer_ctx = CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            Transfer {
+                from: player_token_account.to_account_info(),
+                to: ctx.accounts.arena_vault.to_account_info(),
+                authority: ctx.accounts.player.to_account_info(),
+            },
+        );
+        token::transfer(transfer_ctx, amount)?;
+    }
+
+    emit_cpi!(PlayerEntered {
+        arena: arena.key(),
+        arena_id: arena.id,
+        player: ctx.accounts.player.key(),
+        asset_index,
+        mint,
+        amount,
+        usd_value,
+        entry_price: price_value,
+        from_vault,
+        player_count: arena.player_count,
+        total_pool: arena.total_pool,
+    });
+
+    msg!("Player {} entered arena {} with asset {}", 
+        ctx.accounts.player.key(), arena.id, asset_index);
+
+    // Check if arena is full and should start
+    if arena.player_count == arena.max_players {
+        // Snapshot start prices for every represented asset in the activating transaction. Each one must
+        // be published right before activation, so the entrant filling the arena cannot pick an older price.
+        require!(
+            price.publish_time >= clock.unix_timestamp - START_PRICE_WINDOW,
+            CryptarenaError::StartPriceOutsideWindow
+        );
+        arena.start_prices[asset_index as usize] = price_value;
+        let mut start_timestamp = price.publish_time;
+
+        let pairs = ctx.remaining_accounts.chunks_exact(2);
+        require!(
+            pairs.remainder().is_empty(),
+            CryptarenaError::InvalidRemainingAccounts
+        );
+        // Each asset is priced once, so a later pair can never overwrite an earlier start price
+        let mut priced_assets = 1u16 << asset_index;
+        for pair in pairs {
+            let feed_config: Account<AssetConfig> = Account::try_from(&pair[0])?;
+            let feed_update: Account<PriceUpdateV2> = Account::try_from(&pair[1])?;
+            let feed_asset = feed_config.asset_index;
+            require!(
+                arena.asset_counts[feed_asset as usize] > 0,
+                CryptarenaError::AssetNotInArena
+            );
+            let feed_bit = 1u16 << feed_asset;
+            require!(priced_assets & feed_bit == 0, CryptarenaError::DuplicateStartPrice);
+            priced_assets |= feed_bit;
+
+            let feed_price = read_fresh_pyth_price(&feed_update, &feed_config, &clock)?;
+            require!(
+                feed_price.publish_time >= clock.unix_timestamp - START_PRICE_WINDOW,
+                CryptarenaError::StartPriceOutsideWindow
+            );
+            arena.start_prices[feed_asset as usize] = feed_price.price as u64;
+            start_timestamp = start_timestamp.max(feed_price.publish_time);
+        }
+
+        require!(
+            (0..MAX_ASSETS).all(|idx| arena.asset_counts[idx] == 0 || arena.start_prices[idx] > 0),
+            CryptarenaError::MissingStartPrice
+        );
+
+        // Align the arena window to the price snapshot rather than the entry time
+        arena.status = ArenaStatus::Active as u8;
+        arena.start_timestamp = start_timestamp;
+        arena.end_timestamp = start_timestamp + arena.duration;
+        if arena.is_public() {
+            stake_tier.waiting_arenas[arena.arena_type as usize] = None;
+        }
+
+        emit_cpi!(ArenaStarted {
+            arena: arena.key(),
+            arena_id: arena.id,
+            start_prices: arena.start_prices,
+            start_timestamp: arena.start_timestamp,
+            end_timestamp: arena.end_timestamp,
+            total_pool: arena.total_pool,
+        });
+
+        msg!("Arena {} started! Ends at {}", arena.id, arena.end_timestamp);
+    }
+
+    Ok(())
+}
+
+/// Enforce a private arena's access policy for `player`
+fn check_arena_access(
+    arena: &Arena,
+    player: &Pubkey,
+    allowlist: Option<&ArenaAllowlist>,
+    invite_secret: Option<&[u8]>,
+) -> Result<()> {
+    if arena.access_policy == AccessPolicy::Allowlist as u8 {
+        let allowlist = allowlist.ok_or(CryptarenaError::NotInvited)?;
+        require!(allowlist.players.contains(player), CryptarenaError::NotInvited);
+    } else if arena.access_policy == AccessPolicy::InviteCode as u8 {
+        let invite_secret = invite_secret.ok_or(CryptarenaError::InvalidInviteSecret)?;
+        require!(
+            hash(invite_secret).to_bytes() == arena.invite_hash,
+            CryptarenaError::InvalidInviteSecret
+        );
+    }
+    Ok(())
+}
+
+/// Read an asset's Pyth price through the shared oracle checks: minimum verification level,
+/// strictly positive price, and confidence interval within the asset's `max_confidence_bps`
+pub fn read_pyth_price(price_update: &PriceUpdateV2, asset_config: &AssetConfig) -> Result<Price> {
+    require!(
+        price_update.verification_level.gte(MIN_VERIFICATION_LEVEL),
+        CryptarenaError::InsufficientVerification
+    );
+
+    let price = price_update.get_price_unchecked(&asset_config.feed_id)?;
+    require!(price.price > 0, CryptarenaError::NonPositivePrice);
+    require!(
+        (price.conf as u128) * 10000
+            <= (price.price as u128) * (asset_config.max_confidence_bps as u128),
+        CryptarenaError::ConfidenceTooWide
+    );
+
+    Ok(price)
+}
+
+/// `read_pyth_price` that also rejects updates older than the asset's `max_age`
+/// The faucet prices its claims through this as well, so both programs apply the same checks.
+pub fn read_fresh_pyth_price(
+    price_update: &PriceUpdateV2,
+    asset_config: &AssetConfig,
+    clock: &Clock,
+) -> Result<Price> {
+    let price = read_pyth_price(price_update, asset_config)?;
+    require!(
+        price.publish_time.saturating_add(asset_config.max_age as i64) >= clock.unix_timestamp,
+        CryptarenaError::StalePrice
+    );
+    Ok(price)
+}
+
+/// Record an asset's end price, judging freshness against `end_timestamp` rather than
+/// against when the crank runs. The window spans the oracle's cadence at one-second
+/// `publish_time` resolution, so a keeper cannot shop for a favourable later update.
+fn record_end_price(arena: &mut Arena, asset_index: u8, price: &Price) -> Result<()> {
+    require!(
+        price.publish_time >= arena.end_timestamp
+            && price.publish_time <= arena.end_timestamp + END_PRICE_WINDOW,
+        CryptarenaError::EndPriceOutsideWindow
+    );
+
+    // Only the first update at or after the deadline counts; a later one can never replace it
+    let recorded_publish_time = arena.end_price_publish_times[asset_index as usize];
+    require!(
+        recorded_publish_time == 0 || price.publish_time < recorded_publish_time,
+        CryptarenaError::EndPriceAlreadySet
+    );
+
+    arena.end_prices[asset_index as usize] = price.price as u64;
+    arena.end_price_publish_times[asset_index as usize] = price.publish_time;
+    Ok(())
+}
+
+/// Void every represented asset still missing an end price once the oracle grace period has passed
+/// Until then resolution fails, so a late feed can never hand the win to another asset.
+fn void_missing_end_prices(arena: &mut Arena, now: i64, grace_period: i64) -> Result<()> {
+    let missing = (0..MAX_ASSETS)
+        .filter(|idx| arena.asset_counts[*idx] > 0 && arena.end_prices[*idx] == 0)
+        .fold(0u16, |bitmap, asset_idx| bitmap | (1u16 << asset_idx));
+    if missing != 0 {
+        require!(
+            now >= arena.end_timestamp + grace_period,
+            CryptarenaError::MissingEndPrice
+        );
+        arena.void_assets_bitmap = missing;
+        msg!("Arena {} voided assets {:#018b} for missing end prices", arena.id, missing);
+    }
+    Ok(())
+}
+
+/// Record the loss of every player of an ended arena whose asset lost, on the `PlayerProfile`
+/// accounts passed in `arena.players` order. Losers never claim, so resolution is where it can't be skipped.
+fn record_losses<'info>(arena: &Arena, profiles: &'info [AccountInfo<'info>]) -> Result<()> {
+    require!(
+        profiles.len() == arena.player_count as usize,
+        CryptarenaError::InvalidRemainingAccounts
+    );
+    if arena.status != ArenaStatus::Ended as u8 {
+        return Ok(());
+    }
+
+    for (slot, profile_info) in profiles.iter().enumerate() {
+        let asset_index = arena.player_assets[slot];
+        if asset_index == arena.winning_asset || arena.is_asset_void(asset_index) {
+            continue;
+        }
+        let mut profile: Account<PlayerProfile> = Account::try_from(profile_info)?;
+        require_keys_eq!(
+            profile.player,
+            arena.players[slot],
+            CryptarenaError::InvalidRemainingAccounts
+        );
+        profile.record_loss(arena.end_timestamp);
+        profile.exit(&crate::ID)?;
+    }
+    Ok(())
+}
+
+/// Score price movements, pick the winning asset and record treasury fees and the crank bounty
+/// Bullish: highest movement wins. Bearish: largest negative movement wins. Ties suspend the arena.
+/// Voided assets are not scored and their vaults owe no fees; their players are refunded in full.
+fn resolve_arena(arena: &mut Arena, caller: Pubkey) {
+    // Process each unique asset in the arena
+    let mut best_movement: i64 = i64::MIN;
+    let mut winning_asset: u8 = 255;
+    let mut tie_detected = false;
+    let is_bearish = arena.arena_type == ArenaType::Bearish as u8;
+
+    for asset_idx in 0..MAX_ASSETS {
+        if arena.asset_counts[asset_idx] > 0 {
+            let start_price = arena.start_prices[asset_idx] as i64;
+            let end_price = arena.end_prices[asset_idx] as i64;
+
+            if start_price > 0 && end_price > 0 {
+                // Calculate percentage movement (scaled by 10000 for precision)
+                let movement = ((end_price - start_price) * 10000) / start_price;
+                arena.price_movements[asset_idx] = movement;
+
+                // Bearish arenas rank by the inverted movement so the steepest drop scores highest
+                let score = if is_bearish { -movement } else { movement };
+
+                if score > best_movement {
+                    best_movement = score;
+                    winning_asset = asset_idx as u8;
+                    tie_detected = false;
+                } else if score == best_movement && winning_asset != 255 {
+                    tie_detected = true;
+                }
+            }
+        }
+    }
+
+    // Handle tie, or every asset voided - suspend arena for withdrawals
+    if tie_detected || winning_asset == 255 {
+        arena.is_suspended = true;
+        arena.status = ArenaStatus::Suspended as u8;
+        arena.outstanding_claims = arena.player_count as u16;
+        msg!("Arena {} suspended: tie or no asset with an end price", arena.id);
+        return;
+    }
+
+    arena.winning_asset = winning_asset;
+    arena.status = ArenaStatus::Ended as u8;
+
+    // Record the treasury fee owed at the arena's snapshotted rate: USD total for reporting,
+    // raw token amount per vault for collection
+    // Referrers' cuts of the fee were set aside at entry and are paid through `credit_referral`
+    // Voided vaults are refunded whole, so they neither pay fees nor set aside referral cuts
+    let settled_pool = arena.settled_pool();
+    let treasury_fee_bps = arena.fee_schedule.fee_bps(arena.player_count);
+    arena.treasury_fee = (settled_pool * treasury_fee_bps) / 10000;
+    for asset_idx in 0..MAX_ASSETS {
+        if arena.is_asset_void(asset_idx as u8) {
+            arena.asset_referral_fees[asset_idx] = 0;
+            continue;
+        }
+        arena.asset_treasury_fees[asset_idx] =
+            (arena.asset_amounts[asset_idx] * treasury_fee_bps) / 10000
+                - arena.asset_referral_fees[asset_idx];
+    }
+
+    // Custom arenas pay their creator's fee out of each vault as well
+    arena.creator_fee = (settled_pool * arena.creator_fee_bps) / 10000;
+    for asset_idx in 0..MAX_ASSETS {
+        if !arena.is_asset_void(asset_idx as u8) {
+            arena.asset_creator_fees[asset_idx] =
+                (arena.asset_amounts[asset_idx] * arena.creator_fee_bps) / 10000;
+        }
+    }
+
+    // The caller completing settlement earns the crank bounty, carved out of each vault the same way
+    arena.crank_recipient = caller;
+    arena.crank_bounty = (settled_pool * arena.crank_bounty_bps) / 10000;
+    for asset_idx in 0..MAX_ASSETS {
+        if !arena.is_asset_void(asset_idx as u8) {
+            arena.asset_crank_bounties[asset_idx] =
+                (arena.asset_amounts[asset_idx] * arena.crank_bounty_bps) / 10000;
+        }
+    }
+
+    // Every winner claims each non-void vault; fees are only owed where they are non-zero, and every
+    // player of a voided asset is owed a refund. Voided referred entries settle with their refund.
+    let represented_assets = arena.payout_assets_bitmap().count_ones() as u16;
+    let voided_entries = (0..MAX_ASSETS)
+        .filter(|idx| arena.is_asset_void(*idx as u8))
+        .map(|idx| arena.asset_counts[idx] as u16)
+        .sum::<u16>();
+    let fees_owed = (0..MAX_ASSETS)
+        .map(|idx| {
+            [
+                arena.asset_treasury_fees[idx],
+                arena.asset_crank_bounties[idx],
+                arena.asset_creator_fees[idx],
+            ]
+            .iter()
+            .filter(|fee| **fee > 0)
+            .count() as u16
+        })
+        .sum::<u16>();
+    let winner_count = arena.asset_counts.get(winning_asset as usize).copied().unwrap_or(0);
+    arena.outstanding_claims = winner_count as u16 * represented_assets
+        + fees_owed
+        + arena.referred_entries as u16
+        + voided_entries;
+
+    let winning_movement = if is_bearish { best_movement.saturating_neg() } else { best_movement };
+    msg!("Arena {} ended. Winning asset: {}, Movement: {}bps",
+        arena.id, winning_asset, winning_movement);
+}
+
+/// Lead of the winning asset's score over the best other represented asset, in bps
+fn margin_of_victory_bps(arena: &Arena) -> u64 {
+    let is_bearish = arena.arena_type == ArenaType::Bearish as u8;
+    let score = |asset_idx: usize| {
+        let movement = arena.price_movements[asset_idx];
+        if is_bearish { movement.saturating_neg() } else { movement }
+    };
+
+    let winning_asset = arena.winning_asset as usize;
+    (0..MAX_ASSETS)
+        .filter(|idx| {
+            *idx != winning_asset && arena.asset_counts[*idx] > 0 && !arena.is_asset_void(*idx as u8)
+        })
+        .map(score)
+        .max()
+        .map_or(0, |runner_up| score(winning_asset).saturating_sub(runner_up).max(0) as u64)
+}
+
+/// Event describing a newly opened arena
+fn arena_created_event(arena: &Account<Arena>) -> ArenaCreated {
+    ArenaCreated {
+        arena: arena.key(),
+        arena_id: arena.id,
+        tier_id: arena.tier_id,
+        creator: arena.creator,
+        arena_type: arena.arena_type,
+        max_players: arena.max_players,
+        duration: arena.duration,
+        creator_fee_bps: arena.creator_fee_bps,
+        treasury_fee_bps: arena.fee_schedule.fee_bps(arena.max_players),
+        access_policy: arena.access_policy,
+        created_timestamp: arena.created_timestamp,
+    }
+}
+
+/// Event describing the outcome of `resolve_arena`
+fn arena_resolved_event(arena: &Account<Arena>) -> ArenaResolved {
+    ArenaResolved {
+        arena: arena.key(),
+        arena_id: arena.id,
+        status: arena.status,
+        winning_asset: arena.winning_asset,
+        end_prices: arena.end_prices,
+        price_movements: arena.price_movements,
+        treasury_fee: arena.treasury_fee,
+        crank_bounty: arena.crank_bounty,
+        crank_recipient: arena.crank_recipient,
+        creator_fee: arena.creator_fee,
+        void_assets_bitmap: arena.void_assets_bitmap,
+    }
+}
+
+/// Share of `pool` owed to a stake of `stake` out of `total_stake`, rounded down
+fn calculate_pro_rata_share(pool: u64, stake: u64, total_stake: u64) -> u64 {
+    if total_stake == 0 {
+        return 0;
+    }
+    ((pool as u128 * stake as u128) / total_stake as u128) as u64
+}
+
+fn calculate_usd_value(amount: u64, price: u64, expo: i32, decimals: u8) -> Result<u64> {
+    // Pyth prices have variable exponents, normalize to 6 decimals (USD cents * 10000)
+    let raw_value = amount as u128 * price as u128;
+
+    // amount / 10^decimals * price * 10^expo, expressed with 6 decimals
+    let scale = 6 + expo - decimals as i32;
+    let usd_value = if scale < 0 {
+        raw_value / 10u128.pow((-scale) as u32)
+    } else {
+        raw_value
+            .checked_mul(10u128.pow(scale as u32))
+            .ok_or(CryptarenaError::InvalidEntryAmount)?
+    };
+
+    u64::try_from(usd_value).map_err(|_| error!(CryptarenaError::InvalidEntryAmount))
+}
+
+// ============================================================================
+// ACCOUNTS
+// ============================================================================
+
+#[derive(Accounts)]
+pub struct Initialize<'info> {
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + GlobalState::INIT_SPACE,
+        seeds = [b"global_state"],
+        bump
+    )]
+    pub global_state: Account<'info, GlobalState>,
+    
+    /// CHECK: Treasury wallet address
+    pub treasury: UncheckedAccount<'info>,
+    
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateSettings<'info> {
+    #[account(
+        mut,
+        seeds = [b"global_state"],
+        bump = global_state.bump,
+        has_one = admin
+    )]
+    pub global_state: Account<'info, GlobalState>,
+    
+    pub admin: Signer<'info>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct ExecuteSettings<'info> {
+    #[account(
+        mut,
+        seeds = [b"global_state"],
+        bump = global_state.bump,
+        has_one = admin
+    )]
+    pub global_state: Account<'info, GlobalState>,
+
+    /// Stake tier whose queued `set_stake_tier` change to apply or discard
+    #[account(mut)]
+    pub stake_tier: Option<Account<'info, StakeTier>>,
+
+    /// Registry entry whose queued `set_asset_config` change to apply or discard
+    #[account(mut)]
+    pub asset_config: Option<Account<'info, AssetConfig>>,
+
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct AcceptAdmin<'info> {
+    #[account(
+        mut,
+        seeds = [b"global_state"],
+        bump = global_state.bump,
+        constraint = global_state.pending_admin == Some(new_admin.key()) @ CryptarenaError::Unauthorized
+    )]
+    pub global_state: Account<'info, GlobalState>,
+
+    pub new_admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(asset_index: u8)]
+pub struct SetAssetConfig<'info> {
+    #[account(
+        seeds = [b"global_state"],
+        bump = global_state.bump,
+        has_one = admin
+    )]
+    pub global_state: Account<'info, GlobalState>,
+
+    #[account(
+        init_if_needed,
+        payer = admin,
+        space = 8 + AssetConfig::INIT_SPACE,
+        seeds = [b"asset_config", asset_index.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub asset_config: Account<'info, AssetConfig>,
+
+    pub mint: Account<'info, Mint>,
+
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(nonce: u64, tier_id: u8)]
+pub struct CreateArena<'info> {
+    #[account(
+        seeds = [b"global_state"],
+        bump = global_state.bump
+    )]
+    pub global_state: Account<'info, GlobalState>,
+
+    #[account(
+        seeds = [b"stake_tier", tier_id.to_le_bytes().as_ref()],
+        bump = stake_tier.bump,
+        constraint = stake_tier.enabled @ CryptarenaError::StakeTierDisabled
+    )]
+    pub stake_tier: Account<'info, StakeTier>,
+
+    #[account(
+        init,
+        payer = creator,
+        space = 8 + Arena::INIT_SPACE,
+        seeds = [b"arena", creator.key().as_ref(), nonce.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub arena: Account<'info, Arena>,
+
+    #[account(mut)]
+    pub creator: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetArenaAllowlist<'info> {
+    #[account(
+        seeds = [b"arena", arena.seed_key().as_slice(), arena.id.to_le_bytes().as_ref()],
+        bump = arena.bump,
+        constraint = arena.creator == creator.key() @ CryptarenaError::Unauthorized
+    )]
+    pub arena: Account<'info, Arena>,
+
+    #[account(
+        init_if_needed,
+        payer = creator,
+        space = 8 + ArenaAllowlist::INIT_SPACE,
+        seeds = [b"arena_allowlist", arena.key().as_ref()],
+        bump
+    )]
+    pub arena_allowlist: Account<'info, ArenaAllowlist>,
+
+    #[account(mut)]
+    pub creator: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(tier_id: u8)]
+pub struct SetStakeTier<'info> {
+    #[account(
+        seeds = [b"global_state"],
+        bump = global_state.bump,
+        has_one = admin
+    )]
+    pub global_state: Account<'info, GlobalState>,
+
+    #[account(
+        init_if_needed,
+        payer = admin,
+        space = 8 + StakeTier::INIT_SPACE,
+        seeds = [b"stake_tier", tier_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub stake_tier: Account<'info, StakeTier>,
+
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(
+    asset_index: u8,
+    amount: u64,
+    arena_type: ArenaType,
+    tier_id: u8,
+    custom_arena: Option<CustomArenaKey>
+)]
+pub struct EnterArena<'info> {
+    #[account(
+        seeds = [b"global_state"],
+        bump = global_state.bump
+    )]
+    pub global_state: Account<'info, GlobalState>,
+
+    #[account(
+        mut,
+        seeds = [b"stake_tier", tier_id.to_le_bytes().as_ref()],
+        bump = stake_tier.bump,
+        constraint = stake_tier.enabled @ CryptarenaError::StakeTierDisabled
+    )]
+    pub stake_tier: Account<'info, StakeTier>,
+
+    #[account(
+        init_if_needed,
+        payer = player,
+        space = 8 + Arena::INIT_SPACE,
+        seeds = [
+            b"arena",
+            arena_seed_key(tier_id, custom_arena.map(|key| key.creator)).as_slice(),
+            custom_arena
+                .map_or(stake_tier.lobby_arena_id(arena_type), |key| key.nonce)
+                .to_le_bytes()
+                .as_ref()
+        ],
+        bump
+    )]
+    pub arena: Account<'info, Arena>,
+
+    #[account(
+        init,
+        payer = player,
+        space = 8 + PlayerEntry::INIT_SPACE,
+        seeds = [b"player_entry", arena.key().as_ref(), player.key().as_ref()],
+        bump
+    )]
+    pub player_entry: Account<'info, PlayerEntry>,
+
+    #[account(
+        init_if_needed,
+        payer = player,
+        space = 8 + PlayerProfile::INIT_SPACE,
+        seeds = [b"player_profile", player.key().as_ref()],
+        bump
+    )]
+    pub player_profile: Account<'info, PlayerProfile>,
+
+    #[account(
+        init_if_needed,
+        payer = player,
+        space = 8 + Referral::INIT_SPACE,
+        seeds = [b"referral", player.key().as_ref()],
+        bump
+    )]
+    pub referral: Account<'info, Referral>,
+
+    #[account(mut)]
+    pub player: Signer<'info>,
+
+    #[account(
+        seeds = [b"asset_config", asset_index.to_le_bytes().as_ref()],
+        bump = asset_config.bump,
+        constraint = asset_config.enabled @ CryptarenaError::AssetDisabled
+    )]
+    pub asset_config: Account<'info, AssetConfig>,
+
+    #[account(
+        constraint = mint.key() == asset_config.mint @ CryptarenaError::InvalidMint
+    )]
+    pub mint: Account<'info, Mint>,
+
+    /// Required by `enter_arena`
+    #[account(
+        mut,
+        constraint = player_token_account.mint == mint.key() @ CryptarenaError::InvalidMint
+    )]
+    pub player_token_account: Option<Account<'info, TokenAccount>>,
+
+    /// Required by `enter_arena_from_vault`
+    #[account(
+        mut,
+        seeds = [b"user_vault", player.key().as_ref(), mint.key().as_ref()],
+        bump = user_vault.bump,
+        constraint = user_vault.owner == player.key() @ CryptarenaError::Unauthorized
+    )]
+    pub user_vault: Option<Account<'info, UserVault>>,
+
+    /// Required by `enter_arena_from_vault`
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = global_state,
+        seeds = [b"protocol_vault", mint.key().as_ref()],
+        bump
+    )]
+    pub protocol_vault: Option<Account<'info, TokenAccount>>,
+
+    /// Per-mint arena vault, owned by the arena PDA
+    #[account(
+        init_if_needed,
+        payer = player,
+        token::mint = mint,
+        token::authority = arena,
+        seeds = [b"arena_vault", arena.key().as_ref(), mint.key().as_ref()],
+        bump
+    )]
+    pub arena_vault: Account<'info, TokenAccount>,
+
+    /// Required when entering an allowlist arena
+    #[account(
+        seeds = [b"arena_allowlist", arena.key().as_ref()],
+        bump = arena_allowlist.bump
+    )]
+    pub arena_allowlist: Option<Account<'info, ArenaAllowlist>>,
+
+    pub price_update: Account<'info, PriceUpdateV2>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct EndArena<'info> {
+    #[account(
+        seeds = [b"global_state"],
+        bump = global_state.bump
+    )]
+    pub global_state: Account<'info, GlobalState>,
+
+    #[account(
+        mut,
+        seeds = [b"arena", arena.seed_key().as_slice(), arena.id.to_le_bytes().as_ref()],
+        bump = arena.bump
+    )]
+    pub arena: Account<'info, Arena>,
+
+    /// CHECK: Anyone can call end_arena after duration
+    pub caller: Signer<'info>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct SettleArena<'info> {
+    #[account(
+        seeds = [b"global_state"],
+        bump = global_state.bump
+    )]
+    pub global_state: Account<'info, GlobalState>,
+
+    #[account(
+        mut,
+        seeds = [b"arena", arena.seed_key().as_slice(), arena.id.to_le_bytes().as_ref()],
+        bump = arena.bump
+    )]
+    pub arena: Account<'info, Arena>,
+
+    /// CHECK: Anyone can settle after duration
+    pub caller: Signer<'info>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(asset_index: u8)]
+pub struct UpdateEndPrices<'info> {
+    #[account(
+        seeds = [b"asset_config", asset_index.to_le_bytes().as_ref()],
+        bump = asset_config.bump
+    )]
+    pub asset_config: Account<'info, AssetConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"arena", arena.seed_key().as_slice(), arena.id.to_le_bytes().as_ref()],
+        bump = arena.bump
+    )]
+    pub arena: Account<'info, Arena>,
+
+    pub price_update: Account<'info, PriceUpdateV2>,
+
+    /// CHECK: Anyone can update prices
+    pub caller: Signer<'info>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(asset_index: u8)]
+pub struct ClaimReward<'info> {
+    #[account(
+        seeds = [b"global_state"],
+        bump = global_state.bump
+    )]
+    pub global_state: Account<'info, GlobalState>,
+
+    #[account(
+        mut,
+        seeds = [b"arena", arena.seed_key().as_slice(), arena.id.to_le_bytes().as_ref()],
+        bump = arena.bump,
+        constraint = (asset_index as usize) < MAX_ASSETS @ CryptarenaError::InvalidAsset
+    )]
+    pub arena: Account<'info, Arena>,
+
+    #[account(
+        mut,
+        seeds = [b"player_entry", arena.key().as_ref(), player.key().as_ref()],
+        bump = player_entry.bump,
+        has_one = player
+    )]
+    pub player_entry: Account<'info, PlayerEntry>,
+
+    #[account(
+        mut,
+        seeds = [b"player_profile", player.key().as_ref()],
+        bump = player_profile.bump
+    )]
+    pub player_profile: Account<'info, PlayerProfile>,
+
+    /// Vault holding the claimed asset's deposits
+    #[account(
+        mut,
+        seeds = [b"arena_vault", arena.key().as_ref(), arena.asset_mints[asset_index as usize].as_ref()],
+        bump
+    )]
+    pub arena_vault: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = player_token_account.mint == arena_vault.mint @ CryptarenaError::InvalidMint,
+        constraint = player_token_account.owner == player.key() @ CryptarenaError::Unauthorized
+    )]
+    pub player_token_account: Account<'info, TokenAccount>,
+
+    pub player: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct WithdrawSuspended<'info> {
+    #[account(
+        mut,
+        seeds = [b"arena", arena.seed_key().as_slice(), arena.id.to_le_bytes().as_ref()],
+        bump = arena.bump
+    )]
+    pub arena: Account<'info, Arena>,
+
+    #[account(
+        mut,
+        seeds = [b"player_entry", arena.key().as_ref(), player.key().as_ref()],
+        bump = player_entry.bump,
+        has_one = player
+    )]
+    pub player_entry: Account<'info, PlayerEntry>,
+
+    #[account(
+        mut,
+        seeds = [b"player_profile", player.key().as_ref()],
+        bump = player_profile.bump
+    )]
+    pub player_profile: Account<'info, PlayerProfile>,
+
+    /// Vault holding the player's deposited mint
+    #[account(
+        mut,
+        seeds = [b"arena_vault", arena.key().as_ref(), arena.asset_mints[player_entry.asset_index as usize].as_ref()],
+        bump
+    )]
+    pub arena_vault: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = player_token_account.mint == arena_vault.mint @ CryptarenaError::InvalidMint,
+        constraint = player_token_account.owner == player.key() @ CryptarenaError::Unauthorized
+    )]
+    pub player_token_account: Account<'info, TokenAccount>,
+
+    pub player: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct LeaveArena<'info> {
+    #[account(
+        mut,
+        seeds = [b"arena", arena.seed_key().as_slice(), arena.id.to_le_bytes().as_ref()],
+        bump = arena.bump
+    )]
+    pub arena: Account<'info, Arena>,
+
+    #[account(
+        mut,
+        close = player,
+        seeds = [b"player_entry", arena.key().as_ref(), player.key().as_ref()],
+        bump = player_entry.bump,
+        has_one = player
+    )]
+    pub player_entry: Account<'info, PlayerEntry>,
+
+    #[account(
+        mut,
+        seeds = [b"player_profile", player.key().as_ref()],
+        bump = player_profile.bump
+    )]
+    pub player_profile: Account<'info, PlayerProfile>,
+
+    /// Vault holding the player's deposited mint
+    #[account(
+        mut,
+        seeds = [b"arena_vault", arena.key().as_ref(), arena.asset_mints[player_entry.asset_index as usize].as_ref()],
+        bump
+    )]
+    pub arena_vault: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = player_token_account.mint == arena_vault.mint @ CryptarenaError::InvalidMint,
+        constraint = player_token_account.owner == player.key() @ CryptarenaError::Unauthorized
+    )]
+    pub player_token_account: Account<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub player: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct ExpireArena<'info> {
+    #[account(
+        seeds = [b"global_state"],
+        bump = global_state.bump
+    )]
+    pub global_state: Account<'info, GlobalState>,
+
+    #[account(
+        mut,
+        seeds = [b"stake_tier", arena.tier_id.to_le_bytes().as_ref()],
+        bump = stake_tier.bump
+    )]
+    pub stake_tier: Account<'info, StakeTier>,
+
+    #[account(
+        mut,
+        seeds = [b"arena", arena.seed_key().as_slice(), arena.id.to_le_bytes().as_ref()],
+        bump = arena.bump
+    )]
+    pub arena: Account<'info, Arena>,
+
+    /// CHECK: Anyone can expire a stale lobby
+    pub caller: Signer<'info>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct RefundExpired<'info> {
+    #[account(
+        mut,
+        seeds = [b"arena", arena.seed_key().as_slice(), arena.id.to_le_bytes().as_ref()],
+        bump = arena.bump
+    )]
+    pub arena: Account<'info, Arena>,
+
+    #[account(
+        mut,
+        seeds = [b"player_entry", arena.key().as_ref(), player_entry.player.as_ref()],
+        bump = player_entry.bump
+    )]
+    pub player_entry: Account<'info, PlayerEntry>,
+
+    #[account(
+        mut,
+        seeds = [b"player_profile", player_entry.player.as_ref()],
+        bump = player_profile.bump
+    )]
+    pub player_profile: Account<'info, PlayerProfile>,
+
+    /// Vault holding the player's deposited mint
+    #[account(
+        mut,
+        seeds = [b"arena_vault", arena.key().as_ref(), arena.asset_mints[player_entry.asset_index as usize].as_ref()],
+        bump
+    )]
+    pub arena_vault: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = player_token_account.mint == arena_vault.mint @ CryptarenaError::InvalidMint,
+        constraint = player_token_account.owner == player_entry.player @ CryptarenaError::Unauthorized
+    )]
+    pub player_token_account: Account<'info, TokenAccount>,
+
+    /// CHECK: Anyone can crank refunds; tokens always go to the entry's player
+    pub caller: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct ClosePlayerEntry<'info> {
+    #[account(
+        seeds = [b"global_state"],
+        bump = global_state.bump
+    )]
+    pub global_state: Account<'info, GlobalState>,
+
+    #[account(
+        mut,
+        seeds = [b"arena", arena.seed_key().as_slice(), arena.id.to_le_bytes().as_ref()],
+        bump = arena.bump
+    )]
+    pub arena: Account<'info, Arena>,
+
+    #[account(
+        mut,
+        close = player,
+        seeds = [b"player_entry", arena.key().as_ref(), player.key().as_ref()],
+        bump = player_entry.bump,
+        has_one = player
+    )]
+    pub player_entry: Account<'info, PlayerEntry>,
+
+    /// CHECK: The entry's player, who paid its rent
+    #[account(mut)]
+    pub player: UncheckedAccount<'info>,
+
+    /// CHECK: Anyone can close settled entries no season is waiting to credit; rent always goes to the player
+    pub caller: Signer<'info>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(asset_index: u8)]
+pub struct CloseArenaVault<'info> {
+    #[account(
+        seeds = [b"global_state"],
+        bump = global_state.bump
+    )]
+    pub global_state: Account<'info, GlobalState>,
+
+    #[account(
+        mut,
+        seeds = [b"arena", arena.seed_key().as_slice(), arena.id.to_le_bytes().as_ref()],
+        bump = arena.bump,
+        constraint = (asset_index as usize) < MAX_ASSETS @ CryptarenaError::InvalidAsset
+    )]
+    pub arena: Account<'info, Arena>,
+
+    /// The mint the arena holds for the asset, or one no asset holds once every entrant left and
+    /// cleared it; the asset may have been relisted under another mint since
+    #[account(
+        constraint = mint.key() == arena.asset_mints[asset_index as usize]
+            || (arena.asset_mints[asset_index as usize] == Pubkey::default()
+                && !arena.asset_mints.contains(&mint.key())) @ CryptarenaError::InvalidMint
+    )]
+    pub mint: Account<'info, Mint>,
+
+    /// The asset's vault; its mint may have been cleared from the arena if every entrant left
+    #[account(
+        mut,
+        seeds = [b"arena_vault", arena.key().as_ref(), mint.key().as_ref()],
+        bump
+    )]
+    pub arena_vault: Account<'info, TokenAccount>,
+
+    /// Per-mint treasury vault receiving any leftover tokens
+    #[account(
+        init_if_needed,
+        payer = caller,
+        token::mint = mint,
+        token::authority = global_state,
+        seeds = [b"treasury_vault", mint.key().as_ref()],
+        bump
+    )]
+    pub treasury_vault: Account<'info, TokenAccount>,
+
+    /// CHECK: Entrant that opened the vault and paid its rent
+    #[account(
+        mut,
+        constraint = rent_receiver.key() == arena.vault_payers[asset_index as usize] @ CryptarenaError::InvalidRentReceiver
+    )]
+    pub rent_receiver: UncheckedAccount<'info>,
+
+    /// CHECK: Anyone can close settled vaults
+    #[account(mut)]
+    pub caller: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct CloseArena<'info> {
+    #[account(
+        mut,
+        close = rent_payer,
+        seeds = [b"arena", arena.seed_key().as_slice(), arena.id.to_le_bytes().as_ref()],
+        bump = arena.bump,
+        has_one = rent_payer
+    )]
+    pub arena: Account<'info, Arena>,
+
+    /// Only custom arenas have one; paid by the creator, who is also the arena's rent payer
+    #[account(
+        mut,
+        close = rent_payer,
+        seeds = [b"arena_allowlist", arena.key().as_ref()],
+        bump = arena_allowlist.bump
+    )]
+    pub arena_allowlist: Option<Account<'info, ArenaAllowlist>>,
+
+    /// CHECK: Paid the arena's rent
+    #[account(mut)]
+    pub rent_payer: UncheckedAccount<'info>,
+
+    /// CHECK: Anyone can close finished arenas
+    pub caller: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct InitUserVault<'info> {
+    #[account(
+        init,
+        payer = user,
+        space = 8 + UserVault::INIT_SPACE,
+        seeds = [b"user_vault", user.key().as_ref(), mint.key().as_ref()],
+        bump
+    )]
+    pub user_vault: Account<'info, UserVault>,
+
+    pub mint: Account<'info, Mint>,
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct DepositToVault<'info> {
+    #[account(
+        seeds = [b"global_state"],
+        bump = global_state.bump
+    )]
+    pub global_state: Account<'info, GlobalState>,
+
+    #[account(
+        mut,
+        seeds = [b"user_vault", user.key().as_ref(), mint.key().as_ref()],
+        bump = user_vault.bump,
+        constraint = user_vault.owner == user.key() @ CryptarenaError::Unauthorized
+    )]
+    pub user_vault: Account<'info, UserVault>,
+
+    pub mint: Account<'info, Mint>,
+
+    /// Per-mint token account holding user vault balances, owned by global state
+    #[account(
+        init_if_needed,
+        payer = user,
+        token::mint = mint,
+        token::authority = global_state,
+        seeds = [b"protocol_vault", mint.key().as_ref()],
+        bump
+    )]
+    pub protocol_vault: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = user_token_account.mint == mint.key() @ CryptarenaError::InvalidMint
+    )]
+    pub user_token_account: Account<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct WithdrawFromVault<'info> {
+    #[account(
+        seeds = [b"global_state"],
+        bump = global_state.bump
+    )]
+    pub global_state: Account<'info, GlobalState>,
+
+    #[account(
+        mut,
+        seeds = [b"user_vault", user.key().as_ref(), user_vault.mint.as_ref()],
+        bump = user_vault.bump,
+        constraint = user_vault.owner == user.key() @ CryptarenaError::Unauthorized
+    )]
+    pub user_vault: Account<'info, UserVault>,
+
+    #[account(
+        mut,
+        seeds = [b"protocol_vault", user_vault.mint.as_ref()],
+        bump,
+        constraint = protocol_vault.mint == user_vault.mint @ CryptarenaError::InvalidMint
+    )]
+    pub protocol_vault: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = user_token_account.mint == user_vault.mint @ CryptarenaError::InvalidMint
+    )]
+    pub user_token_account: Account<'info, TokenAccount>,
+
+    pub user: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(asset_index: u8)]
+pub struct CollectArenaFee<'info> {
+    #[account(
+        seeds = [b"global_state"],
+        bump = global_state.bump
+    )]
+    pub global_state: Account<'info, GlobalState>,
+
+    #[account(
+        mut,
+        seeds = [b"arena", arena.seed_key().as_slice(), arena.id.to_le_bytes().as_ref()],
+        bump = arena.bump,
+        constraint = (asset_index as usize) < MAX_ASSETS @ CryptarenaError::InvalidAsset
+    )]
+    pub arena: Account<'info, Arena>,
+
+    #[account(
+        constraint = mint.key() == arena.asset_mints[asset_index as usize] @ CryptarenaError::InvalidMint
+    )]
+    pub mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [b"arena_vault", arena.key().as_ref(), mint.key().as_ref()],
+        bump
+    )]
+    pub arena_vault: Account<'info, TokenAccount>,
+
+    /// Per-mint treasury vault, owned by the global state PDA
+    #[account(
+        init_if_needed,
+        payer = caller,
+        token::mint = mint,
+        token::authority = global_state,
+        seeds = [b"treasury_vault", mint.key().as_ref()],
+        bump
+    )]
+    pub treasury_vault: Account<'info, TokenAccount>,
+
+    /// CHECK: Anyone can collect fees into the treasury vault
+    #[account(mut)]
+    pub caller: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(asset_index: u8)]
+pub struct ClaimCrankBounty<'info> {
+    #[account(
+        mut,
+        seeds = [b"arena", arena.seed_key().as_slice(), arena.id.to_le_bytes().as_ref()],
+        bump = arena.bump,
+        constraint = (asset_index as usize) < MAX_ASSETS @ CryptarenaError::InvalidAsset
+    )]
+    pub arena: Account<'info, Arena>,
+
+    /// Vault holding the asset's deposits
+    #[account(
+        mut,
+        seeds = [b"arena_vault", arena.key().as_ref(), arena.asset_mints[asset_index as usize].as_ref()],
+        bump
+    )]
+    pub arena_vault: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = keeper_token_account.mint == arena_vault.mint @ CryptarenaError::InvalidMint,
+        constraint = keeper_token_account.owner == keeper.key() @ CryptarenaError::Unauthorized
+    )]
+    pub keeper_token_account: Account<'info, TokenAccount>,
+
+    #[account(
+        constraint = keeper.key() == arena.crank_recipient @ CryptarenaError::Unauthorized
+    )]
+    pub keeper: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(asset_index: u8)]
+pub struct ClaimCreatorFee<'info> {
+    #[account(
+        mut,
+        seeds = [b"arena", arena.seed_key().as_slice(), arena.id.to_le_bytes().as_ref()],
+        bump = arena.bump,
+        constraint = (asset_index as usize) < MAX_ASSETS @ CryptarenaError::InvalidAsset
+    )]
+    pub arena: Account<'info, Arena>,
+
+    /// Vault holding the asset's deposits
+    #[account(
+        mut,
+        seeds = [b"arena_vault", arena.key().as_ref(), arena.asset_mints[asset_index as usize].as_ref()],
+        bump
+    )]
+    pub arena_vault: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = creator_token_account.mint == arena_vault.mint @ CryptarenaError::InvalidMint,
+        constraint = creator_token_account.owner == creator.key() @ CryptarenaError::Unauthorized
+    )]
+    pub creator_token_account: Account<'info, TokenAccount>,
+
+    #[account(
+        constraint = creator.key() == arena.creator @ CryptarenaError::Unauthorized
+    )]
+    pub creator: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[event_cpi]
 #[derive(Accounts)]
-pub struct Initialize<'info> {
+pub struct CreditReferral<'info> {
     #[account(
-        init,
-        payer = admin,
-        space = 8 + GlobalState::INIT_SPACE,
         seeds = [b"global_state"],
-        bump
+        bump = global_state.bump
     )]
     pub global_state: Account<'info, GlobalState>,
-    
-    /// CHECK: Treasury wallet address
-    pub treasury: UncheckedAccount<'info>,
-    
+
+    #[account(
+        mut,
+        seeds = [b"arena", arena.seed_key().as_slice(), arena.id.to_le_bytes().as_ref()],
+        bump = arena.bump
+    )]
+    pub arena: Account<'info, Arena>,
+
+    #[account(
+        mut,
+        seeds = [b"player_entry", arena.key().as_ref(), player_entry.player.as_ref()],
+        bump = player_entry.bump
+    )]
+    pub player_entry: Account<'info, PlayerEntry>,
+
+    #[account(
+        constraint = mint.key() == arena.asset_mints[player_entry.asset_index as usize] @ CryptarenaError::InvalidMint
+    )]
+    pub mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [b"arena_vault", arena.key().as_ref(), mint.key().as_ref()],
+        bump
+    )]
+    pub arena_vault: Account<'info, TokenAccount>,
+
+    /// Per-mint pool backing referrer balances, owned by the global state PDA
+    #[account(
+        init_if_needed,
+        payer = caller,
+        token::mint = mint,
+        token::authority = global_state,
+        seeds = [b"referral_vault", mint.key().as_ref()],
+        bump
+    )]
+    pub referral_vault: Account<'info, TokenAccount>,
+
+    #[account(
+        init_if_needed,
+        payer = caller,
+        space = 8 + ReferrerBalance::INIT_SPACE,
+        seeds = [b"referrer_balance", player_entry.referrer.as_ref(), mint.key().as_ref()],
+        bump
+    )]
+    pub referrer_balance: Account<'info, ReferrerBalance>,
+
+    /// CHECK: Anyone can credit referrals; the fee always goes to the entry's referrer
     #[account(mut)]
-    pub admin: Signer<'info>,
-    
+    pub caller: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
     pub system_program: Program<'info, System>,
 }
 
+#[event_cpi]
 #[derive(Accounts)]
-pub struct UpdateSettings<'info> {
+pub struct ClaimReferralRewards<'info> {
+    #[account(
+        seeds = [b"global_state"],
+        bump = global_state.bump
+    )]
+    pub global_state: Account<'info, GlobalState>,
+
+    #[account(
+        mut,
+        seeds = [b"referrer_balance", referrer.key().as_ref(), referrer_balance.mint.as_ref()],
+        bump = referrer_balance.bump,
+        has_one = referrer
+    )]
+    pub referrer_balance: Account<'info, ReferrerBalance>,
+
+    #[account(
+        mut,
+        seeds = [b"referral_vault", referrer_balance.mint.as_ref()],
+        bump
+    )]
+    pub referral_vault: Account<'info, TokenAccount>,
+
     #[account(
         mut,
+        constraint = referrer_token_account.mint == referrer_balance.mint @ CryptarenaError::InvalidMint,
+        constraint = referrer_token_account.owner == referrer.key() @ CryptarenaError::Unauthorized
+    )]
+    pub referrer_token_account: Account<'info, TokenAccount>,
+
+    pub referrer: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct TransferTreasury<'info> {
+    #[account(
         seeds = [b"global_state"],
         bump = global_state.bump,
         has_one = admin
     )]
     pub global_state: Account<'info, GlobalState>,
-    
+
+    #[account(
+        mut,
+        seeds = [b"treasury_vault", treasury_vault.mint.as_ref()],
+        bump
+    )]
+    pub treasury_vault: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = destination.owner == global_state.treasury @ CryptarenaError::InvalidTreasury
+    )]
+    pub destination: Account<'info, TokenAccount>,
+
     pub admin: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
 }
 
+#[event_cpi]
 #[derive(Accounts)]
-#[instruction(asset_index: u8, amount: u64)]
-pub struct EnterArena<'info> {
+#[instruction(season_id: u64)]
+pub struct CreateSeason<'info> {
     #[account(
         mut,
         seeds = [b"global_state"],
-        bump = global_state.bump
+        bump = global_state.bump,
+        has_one = admin
     )]
     pub global_state: Account<'info, GlobalState>,
 
     #[account(
-        init_if_needed,
-        payer = player,
-        space = 8 + Arena::INIT_SPACE,
-        seeds = [b"arena", global_state.current_arena_id.to_le_bytes().as_ref()],
+        init,
+        payer = admin,
+        space = 8 + Season::INIT_SPACE,
+        seeds = [b"season", season_id.to_le_bytes().as_ref()],
         bump
     )]
-    pub arena: Account<'info, Arena>,
+    pub season: Account<'info, Season>,
 
+    pub mint: Account<'info, Mint>,
+
+    /// Prize vault, owned by the season PDA
     #[account(
         init,
-        payer = player,
-        space = 8 + PlayerEntry::INIT_SPACE,
-        seeds = [b"player_entry", arena.key().as_ref(), player.key().as_ref()],
+        payer = admin,
+        token::mint = mint,
+        token::authority = season,
+        seeds = [b"season_vault", season.key().as_ref()],
         bump
     )]
-    pub player_entry: Account<'info, PlayerEntry>,
+    pub season_vault: Account<'info, TokenAccount>,
 
     #[account(mut)]
-    pub player: Signer<'info>,
+    pub admin: Signer<'info>,
 
-    #[account(mut)]
-    pub player_token_account: Account<'info, TokenAccount>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct FundSeason<'info> {
+    #[account(
+        seeds = [b"season", season.id.to_le_bytes().as_ref()],
+        bump = season.bump
+    )]
+    pub season: Account<'info, Season>,
 
     #[account(
         mut,
-        seeds = [b"arena_vault", arena.key().as_ref()],
+        seeds = [b"season_vault", season.key().as_ref()],
         bump
     )]
-    pub arena_vault: Account<'info, TokenAccount>,
+    pub season_vault: Account<'info, TokenAccount>,
 
-    pub price_update: Account<'info, PriceUpdateV2>,
+    #[account(
+        mut,
+        constraint = funder_token_account.mint == season.mint @ CryptarenaError::InvalidMint,
+        constraint = funder_token_account.owner == funder.key() @ CryptarenaError::Unauthorized
+    )]
+    pub funder_token_account: Account<'info, TokenAccount>,
+
+    pub funder: Signer<'info>,
 
     pub token_program: Program<'info, Token>,
-    pub system_program: Program<'info, System>,
 }
 
+#[event_cpi]
 #[derive(Accounts)]
-pub struct EndArena<'info> {
+pub struct RecordSeasonScore<'info> {
     #[account(
         mut,
-        seeds = [b"arena", arena.id.to_le_bytes().as_ref()],
+        seeds = [b"season", season.id.to_le_bytes().as_ref()],
+        bump = season.bump
+    )]
+    pub season: Account<'info, Season>,
+
+    #[account(
+        seeds = [b"arena", arena.seed_key().as_slice(), arena.id.to_le_bytes().as_ref()],
         bump = arena.bump
     )]
     pub arena: Account<'info, Arena>,
 
-    /// CHECK: Anyone can call end_arena after duration
+    #[account(
+        mut,
+        seeds = [b"player_entry", arena.key().as_ref(), player_entry.player.as_ref()],
+        bump = player_entry.bump
+    )]
+    pub player_entry: Account<'info, PlayerEntry>,
+
+    #[account(
+        init_if_needed,
+        payer = caller,
+        space = 8 + SeasonScore::INIT_SPACE,
+        seeds = [b"season_score", season.key().as_ref(), player_entry.player.as_ref()],
+        bump
+    )]
+    pub season_score: Account<'info, SeasonScore>,
+
+    /// CHECK: Anyone can credit finished entries; points always go to the entry's player
+    #[account(mut)]
     pub caller: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
 }
 
+#[event_cpi]
 #[derive(Accounts)]
-#[instruction(asset_index: u8)]
-pub struct UpdateEndPrices<'info> {
+pub struct RankSeasonScores<'info> {
     #[account(
         mut,
-        seeds = [b"arena", arena.id.to_le_bytes().as_ref()],
-        bump = arena.bump
+        seeds = [b"season", season.id.to_le_bytes().as_ref()],
+        bump = season.bump
     )]
-    pub arena: Account<'info, Arena>,
-
-    pub price_update: Account<'info, PriceUpdateV2>,
-
-    /// CHECK: Anyone can update prices
-    pub caller: Signer<'info>,
+    pub season: Account<'info, Season>,
 }
 
+#[event_cpi]
 #[derive(Accounts)]
-pub struct ClaimReward<'info> {
+pub struct ForceCloseSeason<'info> {
     #[account(
         seeds = [b"global_state"],
-        bump = global_state.bump
+        bump = global_state.bump,
+        has_one = admin
     )]
     pub global_state: Account<'info, GlobalState>,
 
     #[account(
-        seeds = [b"arena", arena.id.to_le_bytes().as_ref()],
-        bump = arena.bump
+        mut,
+        seeds = [b"season", season.id.to_le_bytes().as_ref()],
+        bump = season.bump,
+        has_one = mint,
+        has_one = rent_payer @ CryptarenaError::InvalidRentReceiver
     )]
-    pub arena: Account<'info, Arena>,
+    pub season: Account<'info, Season>,
+
+    pub mint: Account<'info, Mint>,
 
     #[account(
         mut,
-        seeds = [b"player_entry", arena.key().as_ref(), player.key().as_ref()],
-        bump = player_entry.bump,
-        has_one = player
+        seeds = [b"season_vault", season.key().as_ref()],
+        bump
     )]
-    pub player_entry: Account<'info, PlayerEntry>,
+    pub season_vault: Account<'info, TokenAccount>,
 
+    /// Per-mint treasury vault, owned by the global state PDA
     #[account(
-        mut,
-        seeds = [b"user_vault", player.key().as_ref()],
-        bump = user_vault.bump
+        init_if_needed,
+        payer = admin,
+        token::mint = mint,
+        token::authority = global_state,
+        seeds = [b"treasury_vault", mint.key().as_ref()],
+        bump
     )]
-    pub user_vault: Account<'info, UserVault>,
+    pub treasury_vault: Account<'info, TokenAccount>,
 
-    pub player: Signer<'info>,
+    /// CHECK: Admin that created the season, receives the prize vault rent
+    #[account(mut)]
+    pub rent_payer: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
 }
 
+#[event_cpi]
 #[derive(Accounts)]
-pub struct WithdrawSuspended<'info> {
+pub struct CloseSeason<'info> {
     #[account(
-        seeds = [b"arena", arena.id.to_le_bytes().as_ref()],
-        bump = arena.bump
+        seeds = [b"global_state"],
+        bump = global_state.bump
     )]
-    pub arena: Account<'info, Arena>,
+    pub global_state: Account<'info, GlobalState>,
 
     #[account(
         mut,
-        seeds = [b"player_entry", arena.key().as_ref(), player.key().as_ref()],
-        bump = player_entry.bump,
-        has_one = player
+        seeds = [b"season", season.id.to_le_bytes().as_ref()],
+        bump = season.bump,
+        has_one = mint,
+        has_one = rent_payer @ CryptarenaError::InvalidRentReceiver
     )]
-    pub player_entry: Account<'info, PlayerEntry>,
+    pub season: Account<'info, Season>,
+
+    pub mint: Account<'info, Mint>,
 
     #[account(
         mut,
-        seeds = [b"user_vault", player.key().as_ref()],
-        bump = user_vault.bump
+        seeds = [b"season_vault", season.key().as_ref()],
+        bump
     )]
-    pub user_vault: Account<'info, UserVault>,
-
-    pub player: Signer<'info>,
-}
+    pub season_vault: Account<'info, TokenAccount>,
 
-#[derive(Accounts)]
-pub struct InitUserVault<'info> {
+    /// Per-mint treasury vault, owned by the global state PDA
     #[account(
-        init,
-        payer = user,
-        space = 8 + UserVault::INIT_SPACE,
-        seeds = [b"user_vault", user.key().as_ref()],
+        init_if_needed,
+        payer = caller,
+        token::mint = mint,
+        token::authority = global_state,
+        seeds = [b"treasury_vault", mint.key().as_ref()],
         bump
     )]
-    pub user_vault: Account<'info, UserVault>,
+    pub treasury_vault: Account<'info, TokenAccount>,
 
+    /// CHECK: Admin that created the season, receives the prize vault rent
     #[account(mut)]
-    pub user: Signer<'info>,
+    pub rent_payer: UncheckedAccount<'info>,
+
+    /// CHECK: Anyone can close a ranked season; prizes always go to the leaderboard
+    #[account(mut)]
+    pub caller: Signer<'info>,
 
+    pub token_program: Program<'info, Token>,
     pub system_program: Program<'info, System>,
 }
 
+#[event_cpi]
 #[derive(Accounts)]
-pub struct WithdrawFromVault<'info> {
+pub struct CloseSeasonScore<'info> {
     #[account(
-        seeds = [b"global_state"],
-        bump = global_state.bump
+        seeds = [b"season", season.id.to_le_bytes().as_ref()],
+        bump = season.bump
     )]
-    pub global_state: Account<'info, GlobalState>,
+    pub season: Account<'info, Season>,
 
     #[account(
         mut,
-        seeds = [b"user_vault", user.key().as_ref()],
-        bump = user_vault.bump,
-        constraint = user_vault.owner == user.key() @ CryptarenaError::Unauthorized
+        close = rent_payer,
+        seeds = [b"season_score", season.key().as_ref(), season_score.player.as_ref()],
+        bump = season_score.bump,
+        has_one = rent_payer @ CryptarenaError::InvalidRentReceiver
     )]
-    pub user_vault: Account<'info, UserVault>,
+    pub season_score: Account<'info, SeasonScore>,
 
+    /// CHECK: Whoever recorded the score first and paid its rent
     #[account(mut)]
-    pub protocol_vault: Account<'info, TokenAccount>,
+    pub rent_payer: UncheckedAccount<'info>,
 
-    #[account(mut)]
-    pub user_token_account: Account<'info, TokenAccount>,
+    /// CHECK: Anyone can close scores of a closed season; rent always goes to the rent payer
+    pub caller: Signer<'info>,
+}
 
-    pub user: Signer<'info>,
+// ============================================================================
+// STATE
+// ============================================================================
 
-    pub token_program: Program<'info, Token>,
+#[account]
+#[derive(InitSpace)]
+pub struct GlobalState {
+    pub admin: Pubkey,
+    pub treasury: Pubkey,
+    pub arena_duration: i64,
+    /// Seconds a waiting arena may stay open before it can be expired
+    pub lobby_timeout: i64,
+    /// Share of each settled pool paid to the keeper that completes settlement, in bps
+    pub crank_bounty_bps: u64,
+    /// Duration bounds for custom arenas, in seconds
+    pub min_custom_duration: i64,
+    pub max_custom_duration: i64,
+    /// Maximum creator fee a custom arena may charge, in bps
+    pub max_creator_fee_bps: u64,
+    /// Share of a referred entry's treasury fee paid to the referrer, in bps of the fee
+    pub referral_share_bps: u64,
+    /// Treasury fee rates new arenas snapshot when they are created
+    pub fee_schedule: FeeSchedule,
+    /// Seconds after an arena's end before assets still missing an end price are voided
+    pub oracle_grace_period: i64,
+    /// Earliest start and latest end of any season, bounding the arena ends a season may credit
+    pub seasons_start: i64,
+    pub seasons_end: i64,
+    /// Admin nominated by `propose_admin`, waiting to accept
+    pub pending_admin: Option<Pubkey>,
+    /// Seconds a queued settings change waits before it can be executed
+    pub settings_timelock: i64,
+    pub pending_settings: Option<PendingSettings>,
+    pub is_paused: bool,
+    pub bump: u8,
 }
 
-#[derive(Accounts)]
-pub struct TransferTreasury<'info> {
-    #[account(
-        seeds = [b"global_state"],
-        bump = global_state.bump,
-        has_one = admin
-    )]
-    pub global_state: Account<'info, GlobalState>,
+impl GlobalState {
+    /// Widen the span seasons cover to include a new season's window
+    pub fn cover_season(&mut self, start_timestamp: i64, end_timestamp: i64) {
+        if self.seasons_end == 0 {
+            self.seasons_start = start_timestamp;
+            self.seasons_end = end_timestamp;
+        } else {
+            self.seasons_start = self.seasons_start.min(start_timestamp);
+            self.seasons_end = self.seasons_end.max(end_timestamp);
+        }
+    }
+
+    /// Whether an arena ending at `end_timestamp` may fall within some season's window
+    pub fn season_may_cover(&self, end_timestamp: i64) -> bool {
+        (self.seasons_start..self.seasons_end).contains(&end_timestamp)
+    }
+}
+
+/// Settings changes queued by `update_settings` and `set_custom_arena_bounds`; `None` fields are left unchanged
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, InitSpace)]
+pub struct PendingSettings {
+    pub arena_duration: Option<Queued<i64>>,
+    pub treasury: Option<Queued<Pubkey>>,
+    pub is_paused: Option<Queued<bool>>,
+    pub lobby_timeout: Option<Queued<i64>>,
+    pub crank_bounty_bps: Option<Queued<u64>>,
+    pub settings_timelock: Option<Queued<i64>>,
+    pub referral_share_bps: Option<Queued<u64>>,
+    pub fee_schedule: Option<Queued<FeeSchedule>>,
+    pub oracle_grace_period: Option<Queued<i64>>,
+    pub custom_arena_bounds: Option<Queued<CustomArenaBounds>>,
+}
+
+impl PendingSettings {
+    /// Take out the changes whose timelock has elapsed, leaving the rest queued
+    fn take_ready(&mut self, now: i64) -> PendingSettings {
+        fn take<T: Copy>(slot: &mut Option<Queued<T>>, now: i64) -> Option<Queued<T>> {
+            match slot {
+                Some(queued) if now >= queued.executable_at => slot.take(),
+                _ => None,
+            }
+        }
+
+        PendingSettings {
+            arena_duration: take(&mut self.arena_duration, now),
+            treasury: take(&mut self.treasury, now),
+            is_paused: take(&mut self.is_paused, now),
+            lobby_timeout: take(&mut self.lobby_timeout, now),
+            crank_bounty_bps: take(&mut self.crank_bounty_bps, now),
+            settings_timelock: take(&mut self.settings_timelock, now),
+            referral_share_bps: take(&mut self.referral_share_bps, now),
+            fee_schedule: take(&mut self.fee_schedule, now),
+            oracle_grace_period: take(&mut self.oracle_grace_period, now),
+            custom_arena_bounds: take(&mut self.custom_arena_bounds, now),
+        }
+    }
 
-    #[account(mut)]
-    pub treasury_vault: Account<'info, TokenAccount>,
+    fn is_empty(&self) -> bool {
+        self.arena_duration.is_none()
+            && self.treasury.is_none()
+            && self.is_paused.is_none()
+            && self.lobby_timeout.is_none()
+            && self.crank_bounty_bps.is_none()
+            && self.settings_timelock.is_none()
+            && self.referral_share_bps.is_none()
+            && self.fee_schedule.is_none()
+            && self.oracle_grace_period.is_none()
+            && self.custom_arena_bounds.is_none()
+    }
+}
 
-    #[account(mut)]
-    pub destination: Account<'info, TokenAccount>,
+/// Queued change together with the time its own timelock elapses
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+pub struct Queued<T> {
+    pub value: T,
+    pub executable_at: i64,
+}
 
-    pub admin: Signer<'info>,
+/// `InitSpace` cannot size a generic over primitives, so each queued type is sized here
+macro_rules! impl_queued_space {
+    ($($ty:ty => $len:expr),* $(,)?) => {
+        $(impl Space for Queued<$ty> {
+            const INIT_SPACE: usize = $len + 8;
+        })*
+    };
+}
 
-    pub token_program: Program<'info, Token>,
+impl_queued_space!(
+    bool => 1,
+    i64 => 8,
+    u64 => 8,
+    Pubkey => 32,
+    FeeSchedule => FeeSchedule::INIT_SPACE,
+    CustomArenaBounds => CustomArenaBounds::INIT_SPACE,
+    StakeTierSettings => StakeTierSettings::INIT_SPACE,
+    AssetConfigSettings => AssetConfigSettings::INIT_SPACE,
+);
+
+/// Custom arena bounds queued by `set_custom_arena_bounds`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, InitSpace)]
+pub struct CustomArenaBounds {
+    pub min_duration: i64,
+    pub max_duration: i64,
+    pub max_creator_fee_bps: u64,
 }
 
-// ============================================================================
-// STATE
-// ============================================================================
+/// Stake tier change queued by `set_stake_tier`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, InitSpace)]
+pub struct StakeTierSettings {
+    pub min_entry_usd: u64,
+    pub max_entry_usd: u64,
+    pub enabled: bool,
+}
+
+/// Asset registry change queued by `set_asset_config`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, InitSpace)]
+pub struct AssetConfigSettings {
+    pub feed_id: [u8; 32],
+    pub mint: Pubkey,
+    pub decimals: u8,
+    pub max_age: u64,
+    pub max_confidence_bps: u16,
+    pub enabled: bool,
+}
 
+/// Entry range with its own matchmaking lobbies and arena id sequence
 #[account]
 #[derive(InitSpace)]
-pub struct GlobalState {
-    pub admin: Pubkey,
-    pub treasury: Pubkey,
-    pub arena_duration: i64,
+pub struct StakeTier {
+    pub tier_id: u8,
+    /// Minimum entry value in USD (6 decimals)
+    pub min_entry_usd: u64,
+    /// Maximum entry value in USD (6 decimals)
+    pub max_entry_usd: u64,
     pub current_arena_id: u64,
-    pub waiting_arena: Option<Pubkey>,
-    pub is_paused: bool,
+    /// Id of the open lobby for each arena type, indexed by `ArenaType`
+    pub waiting_arenas: [Option<u64>; ARENA_TYPE_COUNT],
+    pub enabled: bool,
+    /// Change queued by `set_stake_tier`, waiting for `execute_settings`
+    pub pending_change: Option<Queued<StakeTierSettings>>,
+    pub bump: u8,
+}
+
+impl StakeTier {
+    /// Arena id a new entrant joins: the waiting lobby for the type, or the next fresh id
+    pub fn lobby_arena_id(&self, arena_type: ArenaType) -> u64 {
+        self.waiting_arenas[arena_type as usize].unwrap_or(self.current_arena_id)
+    }
+}
+
+/// Registry entry for one listed asset
+#[account]
+#[derive(InitSpace)]
+pub struct AssetConfig {
+    pub asset_index: u8,
+    pub feed_id: [u8; 32],
+    pub mint: Pubkey,
+    pub decimals: u8,
+    /// Maximum age in seconds of a price used at entry
+    pub max_age: u64,
+    /// Maximum oracle confidence as bps of price
+    pub max_confidence_bps: u16,
+    pub enabled: bool,
+    /// Change queued by `set_asset_config`, waiting for `execute_settings`
+    pub pending_change: Option<Queued<AssetConfigSettings>>,
     pub bump: u8,
 }
 
 #[account]
 #[derive(InitSpace)]
 pub struct Arena {
+    /// Sequence id within the tier for public arenas, creator nonce for custom ones
     pub id: u64,
+    pub tier_id: u8,
+    /// Creator of a custom arena; default for public arenas
+    pub creator: Pubkey,
+    pub max_players: u8,
+    /// Seconds between start and end
+    pub duration: i64,
     pub status: u8,
+    pub arena_type: u8,
     pub player_count: u8,
     #[max_len(10)]
     pub players: [Pubkey; MAX_PLAYERS_PER_ARENA],
-    pub asset_counts: [u8; TOTAL_ASSETS],
-    pub start_prices: [u64; TOTAL_ASSETS],
-    pub end_prices: [u64; TOTAL_ASSETS],
-    pub price_movements: [i64; TOTAL_ASSETS],
+    /// Asset each player entered on, by slot in `players`
+    pub player_assets: [u8; MAX_PLAYERS_PER_ARENA],
+    pub asset_counts: [u8; MAX_ASSETS],
+    /// Total USD value staked on each asset (6 decimals)
+    pub asset_usd_totals: [u64; MAX_ASSETS],
+    /// Mint deposited for each asset (one arena vault per mint)
+    pub asset_mints: [Pubkey; MAX_ASSETS],
+    /// Raw token amount deposited into each asset's vault
+    pub asset_amounts: [u64; MAX_ASSETS],
+    /// Number of winners that have claimed from each asset's vault
+    pub vault_claims: [u8; MAX_ASSETS],
+    /// Raw token amount paid to winners from each asset's vault
+    pub vault_paid: [u64; MAX_ASSETS],
+    pub start_prices: [u64; MAX_ASSETS],
+    pub end_prices: [u64; MAX_ASSETS],
+    /// Pyth publish time of each recorded end price
+    pub end_price_publish_times: [i64; MAX_ASSETS],
+    pub price_movements: [i64; MAX_ASSETS],
     pub start_timestamp: i64,
     pub end_timestamp: i64,
     pub total_pool: u64,
+    /// Global fee schedule as it stood when the arena was created; later changes never apply
+    pub fee_schedule: FeeSchedule,
+    /// Treasury fee owed on the pool in USD (6 decimals), set at settlement
+    pub treasury_fee: u64,
+    /// Raw token amount owed to the treasury from each asset's vault
+    pub asset_treasury_fees: [u64; MAX_ASSETS],
+    pub treasury_fees_collected_bitmap: u16,
+    pub treasury_collected: bool,
+    /// Crank bounty snapshotted when the arena is created, in bps of the pool
+    pub crank_bounty_bps: u64,
+    /// Crank bounty owed on the pool in USD (6 decimals), set at settlement
+    pub crank_bounty: u64,
+    /// Raw token amount owed to the keeper from each asset's vault
+    pub asset_crank_bounties: [u64; MAX_ASSETS],
+    /// Caller that completed settlement and earned the bounty
+    pub crank_recipient: Pubkey,
+    pub crank_bounties_claimed_bitmap: u16,
+    pub creator_fee_bps: u64,
+    /// Creator fee owed on the pool in USD (6 decimals), set at settlement
+    pub creator_fee: u64,
+    /// Raw token amount owed to the creator from each asset's vault
+    pub asset_creator_fees: [u64; MAX_ASSETS],
+    pub creator_fees_claimed_bitmap: u16,
+    /// Who may enter, as `AccessPolicy`
+    pub access_policy: u8,
+    /// sha256 of the invite secret for `InviteCode` arenas
+    pub invite_hash: [u8; 32],
+    pub created_timestamp: i64,
     pub winning_asset: u8,
     pub is_suspended: bool,
+    /// Paid the arena's rent: the first entrant of a public arena, the creator of a custom one
+    pub rent_payer: Pubkey,
+    /// Paid the rent of each asset's arena vault; default once the vault is closed
+    pub vault_payers: [Pubkey; MAX_ASSETS],
+    /// Player entries not yet closed
+    pub open_entries: u8,
+    /// Claims, refunds and fee collections still owed; the arena can only close at zero
+    pub outstanding_claims: u16,
+    /// Raw token amount of each asset's treasury fee owed to referrers, set aside at entry
+    pub asset_referral_fees: [u64; MAX_ASSETS],
+    /// Entries whose referral fee is still owed once the arena ends
+    pub referred_entries: u8,
+    /// Referral share snapshotted with the fee schedule, in bps of the treasury fee
+    pub referral_share_bps: u64,
+    /// Bit per asset voided at resolution for lacking an end price; its players are refunded in full
+    pub void_assets_bitmap: u16,
+    pub bump: u8,
+}
+
+/// Players allowed into an allowlist arena
+#[account]
+#[derive(InitSpace)]
+pub struct ArenaAllowlist {
+    pub arena: Pubkey,
+    #[max_len(MAX_ALLOWLIST_SIZE)]
+    pub players: Vec<Pubkey>,
     pub bump: u8,
 }
 
+impl Arena {
+    /// Public arenas are opened by `enter_arena` and tracked in their tier's lobbies
+    pub fn is_public(&self) -> bool {
+        self.creator == Pubkey::default()
+    }
+
+    /// Second PDA seed of this arena, see `arena_seed_key`
+    pub fn seed_key(&self) -> Vec<u8> {
+        arena_seed_key(self.tier_id, (!self.is_public()).then_some(self.creator))
+    }
+
+    /// Ended, suspended and expired arenas take no more entries or price updates
+    pub fn is_finished(&self) -> bool {
+        self.status == ArenaStatus::Ended as u8
+            || self.status == ArenaStatus::Suspended as u8
+            || self.status == ArenaStatus::Expired as u8
+    }
+
+    /// Bitmap with one bit set per asset that has at least one player
+    pub fn represented_assets_bitmap(&self) -> u16 {
+        self.asset_counts
+            .iter()
+            .enumerate()
+            .filter(|(_, count)| **count > 0)
+            .fold(0u16, |bitmap, (asset_idx, _)| bitmap | (1u16 << asset_idx))
+    }
+
+    /// Whether the snapshotted fees fit in the pool; referral cuts come out of the treasury fee
+    pub fn fees_fit_pool(&self) -> bool {
+        self.referral_share_bps <= 10000
+            && self.fee_schedule.max_fee_bps + self.crank_bounty_bps + self.creator_fee_bps <= 10000
+    }
+
+    /// Whether the asset was voided at resolution for lacking an end price
+    pub fn is_asset_void(&self, asset_index: u8) -> bool {
+        self.void_assets_bitmap & (1u16 << asset_index) != 0
+    }
+
+    /// Represented assets whose vaults pay out to winners, i.e. all but the voided ones
+    pub fn payout_assets_bitmap(&self) -> u16 {
+        self.represented_assets_bitmap() & !self.void_assets_bitmap
+    }
+
+    /// USD value of the pool that is played for, excluding voided assets (6 decimals)
+    pub fn settled_pool(&self) -> u64 {
+        self.total_pool
+            - (0..MAX_ASSETS)
+                .filter(|idx| self.is_asset_void(*idx as u8))
+                .map(|idx| self.asset_usd_totals[idx])
+                .sum::<u64>()
+    }
+}
+
 #[account]
 #[derive(InitSpace)]
 pub struct PlayerEntry {
@@ -813,6 +4245,186 @@ pub struct PlayerEntry {
     pub entry_timestamp: i64,
     pub is_winner: bool,
     pub reward_claimed: bool,
+    pub claimed_assets_bitmap: u16, // Bit per asset vault the winner has claimed from
+    pub season_credited: bool, // Points already recorded to a season
+    pub referrer: Pubkey, // Referrer bound to the player when they entered
+    pub referral_fee: u64, // Referrer's cut of this entry's treasury fee, zeroed once credited
+    pub bump: u8,
+}
+
+/// Lifetime stats of one wallet across every arena it entered
+#[account]
+#[derive(InitSpace)]
+pub struct PlayerProfile {
+    pub player: Pubkey,
+    pub arenas_played: u32,
+    pub wins: u32,
+    /// Recorded when the arena resolves
+    pub losses: u32,
+    /// Suspended (tied) withdrawals and expired-lobby refunds
+    pub ties: u32,
+    /// USD value entered across all arenas (6 decimals)
+    pub total_staked: u64,
+    /// USD value of winnings at entry prices (6 decimals)
+    pub total_won: u64,
+    pub current_streak: u32,
+    pub best_streak: u32,
+    /// End of the latest arena counted into the streak
+    pub streak_timestamp: i64,
+    /// Entries per asset index
+    pub asset_entries: [u32; MAX_ASSETS],
+    /// Most entered asset, 255 until the first entry
+    pub favorite_asset: u8,
+    pub bump: u8,
+}
+
+impl PlayerProfile {
+    pub fn record_entry(&mut self, asset_index: u8, usd_value: u64) {
+        self.arenas_played += 1;
+        self.total_staked += usd_value;
+        self.asset_entries[asset_index as usize] += 1;
+        self.refresh_favorite_asset();
+    }
+
+    /// Leaving a waiting arena does not count as playing it
+    pub fn undo_entry(&mut self, asset_index: u8, usd_value: u64) {
+        self.arenas_played -= 1;
+        self.total_staked -= usd_value;
+        self.asset_entries[asset_index as usize] -= 1;
+        self.refresh_favorite_asset();
+    }
+
+    pub fn record_win(&mut self, won_usd: u64, end_timestamp: i64) {
+        self.wins += 1;
+        self.total_won += won_usd;
+        if self.advance_streak(end_timestamp) {
+            self.current_streak += 1;
+            self.best_streak = self.best_streak.max(self.current_streak);
+        }
+    }
+
+    pub fn record_loss(&mut self, end_timestamp: i64) {
+        self.losses += 1;
+        if self.advance_streak(end_timestamp) {
+            self.current_streak = 0;
+        }
+    }
+
+    /// Wins are recorded on claim, which can come after a later arena resolved; only a result
+    /// from an arena ending no earlier than the last one counted moves the streak
+    fn advance_streak(&mut self, end_timestamp: i64) -> bool {
+        if end_timestamp < self.streak_timestamp {
+            return false;
+        }
+        self.streak_timestamp = end_timestamp;
+        true
+    }
+
+    /// Ties and refunds leave the streak untouched
+    pub fn record_refund(&mut self) {
+        self.ties += 1;
+    }
+
+    fn refresh_favorite_asset(&mut self) {
+        self.favorite_asset = self
+            .asset_entries
+            .iter()
+            .enumerate()
+            .filter(|(_, entries)| **entries > 0)
+            .max_by_key(|(asset_idx, entries)| (**entries, std::cmp::Reverse(*asset_idx)))
+            .map_or(255, |(asset_idx, _)| asset_idx as u8);
+    }
+}
+
+/// Referrer bound to a player on their first entry; never changes afterwards
+#[account]
+#[derive(InitSpace)]
+pub struct Referral {
+    pub player: Pubkey,
+    /// Default when the player joined without a referrer
+    pub referrer: Pubkey,
+    pub bump: u8,
+}
+
+/// Referral fees a referrer has accrued in one mint
+#[account]
+#[derive(InitSpace)]
+pub struct ReferrerBalance {
+    pub referrer: Pubkey,
+    pub mint: Pubkey,
+    /// Claimable from the mint's referral vault
+    pub balance: u64,
+    pub total_earned: u64,
+    pub bump: u8,
+}
+
+/// Competition window whose top players split the prize vault
+#[account]
+#[derive(InitSpace)]
+pub struct Season {
+    pub id: u64,
+    /// Mint of the prize vault
+    pub mint: Pubkey,
+    /// Arenas ending in `[start_timestamp, end_timestamp)` count towards the season
+    pub start_timestamp: i64,
+    pub end_timestamp: i64,
+    /// Number of paid ranks
+    pub winner_count: u8,
+    /// Prize vault share of each rank in bps, best first
+    pub payout_bps: [u16; MAX_SEASON_WINNERS],
+    /// Players with a `SeasonScore`; all must be ranked before `close_season`
+    pub participants: u32,
+    /// Scores counted into the leaderboard by `rank_season_scores`
+    pub ranked_participants: u32,
+    /// Best ranked players so far, best first; only the first `winner_count` slots are used
+    pub leaders: [Pubkey; MAX_SEASON_WINNERS],
+    pub leader_points: [u64; MAX_SEASON_WINNERS],
+    /// `SeasonStatus`
+    pub status: u8,
+    /// Admin that created the season and paid the prize vault rent
+    pub rent_payer: Pubkey,
+    pub bump: u8,
+}
+
+impl Season {
+    /// Count a score into the leaderboard, keeping the top `winner_count` ordered by points
+    /// with ties broken by ascending player key
+    pub fn rank(&mut self, player: Pubkey, points: u64) {
+        let winner_count = self.winner_count as usize;
+        let filled = (self.ranked_participants as usize).min(winner_count);
+        let position = (0..filled)
+            .find(|idx| {
+                points > self.leader_points[*idx]
+                    || (points == self.leader_points[*idx] && player < self.leaders[*idx])
+            })
+            .unwrap_or(filled);
+
+        if position < winner_count {
+            // Shift lower ranks down, dropping the last one once the leaderboard is full
+            for idx in (position..filled.min(winner_count - 1)).rev() {
+                self.leaders[idx + 1] = self.leaders[idx];
+                self.leader_points[idx + 1] = self.leader_points[idx];
+            }
+            self.leaders[position] = player;
+            self.leader_points[position] = points;
+        }
+        self.ranked_participants += 1;
+    }
+}
+
+/// Points one player earned in a season
+#[account]
+#[derive(InitSpace)]
+pub struct SeasonScore {
+    pub season: Pubkey,
+    pub player: Pubkey,
+    pub points: u64,
+    pub arenas_played: u32,
+    pub wins: u32,
+    /// Counted into the season leaderboard by `rank_season_scores`
+    pub ranked: bool,
+    /// Caller of the score's first `record_season_score`, refunded by `close_season_score`
+    pub rent_payer: Pubkey,
     pub bump: u8,
 }
 
@@ -820,10 +4432,277 @@ pub struct PlayerEntry {
 #[derive(InitSpace)]
 pub struct UserVault {
     pub owner: Pubkey,
+    pub mint: Pubkey, // Balance is denominated in this mint
     pub available_balance: u64,
     pub bump: u8,
 }
 
+// ============================================================================
+// EVENTS
+// ============================================================================
+
+#[event]
+pub struct AssetConfigSet {
+    pub asset_index: u8,
+    pub feed_id: [u8; 32],
+    pub mint: Pubkey,
+    pub decimals: u8,
+    pub max_age: u64,
+    pub max_confidence_bps: u16,
+    pub enabled: bool,
+}
+
+#[event]
+pub struct ArenaCreated {
+    pub arena: Pubkey,
+    pub arena_id: u64,
+    pub tier_id: u8,
+    pub creator: Pubkey, // Default for public arenas
+    pub arena_type: u8,
+    pub max_players: u8,
+    pub duration: i64,
+    pub creator_fee_bps: u64,
+    pub treasury_fee_bps: u64, // Rate charged once the arena fills, from its fee schedule
+    pub access_policy: u8,
+    pub created_timestamp: i64,
+}
+
+#[event]
+pub struct PlayerEntered {
+    pub arena: Pubkey,
+    pub arena_id: u64,
+    pub player: Pubkey,
+    pub asset_index: u8,
+    pub mint: Pubkey,
+    pub amount: u64,
+    pub usd_value: u64,
+    pub entry_price: u64,
+    pub from_vault: bool,
+    pub player_count: u8,
+    pub total_pool: u64,
+}
+
+#[event]
+pub struct ArenaStarted {
+    pub arena: Pubkey,
+    pub arena_id: u64,
+    pub start_prices: [u64; MAX_ASSETS],
+    pub start_timestamp: i64,
+    pub end_timestamp: i64,
+    pub total_pool: u64,
+}
+
+#[event]
+pub struct EndPriceRecorded {
+    pub arena: Pubkey,
+    pub arena_id: u64,
+    pub asset_index: u8,
+    pub price: u64,
+    pub publish_time: i64,
+}
+
+/// Emitted by `end_arena` and `settle_arena`; a tie leaves the arena `Suspended` with no winner
+#[event]
+pub struct ArenaResolved {
+    pub arena: Pubkey,
+    pub arena_id: u64,
+    pub status: u8,
+    pub winning_asset: u8,
+    pub end_prices: [u64; MAX_ASSETS],
+    pub price_movements: [i64; MAX_ASSETS],
+    pub treasury_fee: u64,
+    pub crank_bounty: u64,
+    pub crank_recipient: Pubkey,
+    pub creator_fee: u64,
+    pub void_assets_bitmap: u16, // Assets voided for a missing end price; their players are refunded
+}
+
+#[event]
+pub struct ArenaExpired {
+    pub arena: Pubkey,
+    pub arena_id: u64,
+    pub player_count: u8,
+    pub total_pool: u64,
+}
+
+/// Emitted when an entry is returned: leaving a waiting arena, or withdrawing from a suspended or expired one
+#[event]
+pub struct EntryRefunded {
+    pub arena: Pubkey,
+    pub arena_id: u64,
+    pub player: Pubkey,
+    pub asset_index: u8,
+    pub mint: Pubkey,
+    pub amount: u64,
+    pub arena_status: u8,
+}
+
+#[event]
+pub struct RewardClaimed {
+    pub arena: Pubkey,
+    pub arena_id: u64,
+    pub player: Pubkey,
+    pub asset_index: u8,
+    pub mint: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct TreasuryFeeCollected {
+    pub arena: Pubkey,
+    pub arena_id: u64,
+    pub asset_index: u8,
+    pub mint: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct CrankBountyClaimed {
+    pub arena: Pubkey,
+    pub arena_id: u64,
+    pub keeper: Pubkey,
+    pub asset_index: u8,
+    pub mint: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct CreatorFeeClaimed {
+    pub arena: Pubkey,
+    pub arena_id: u64,
+    pub creator: Pubkey,
+    pub asset_index: u8,
+    pub mint: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct TreasuryTransferred {
+    pub mint: Pubkey,
+    pub destination: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct UserVaultDeposited {
+    pub owner: Pubkey,
+    pub mint: Pubkey,
+    pub amount: u64,
+    pub available_balance: u64,
+}
+
+#[event]
+pub struct UserVaultWithdrawn {
+    pub owner: Pubkey,
+    pub mint: Pubkey,
+    pub amount: u64,
+    pub available_balance: u64,
+}
+
+#[event]
+pub struct PlayerEntryClosed {
+    pub arena: Pubkey,
+    pub arena_id: u64,
+    pub player: Pubkey,
+}
+
+#[event]
+pub struct ArenaVaultClosed {
+    pub arena: Pubkey,
+    pub arena_id: u64,
+    pub asset_index: u8,
+    pub mint: Pubkey,
+    pub rent_receiver: Pubkey,
+    pub swept_amount: u64, // Leftover tokens swept to the treasury vault
+}
+
+#[event]
+pub struct ArenaClosed {
+    pub arena: Pubkey,
+    pub arena_id: u64,
+    pub status: u8,
+    pub rent_payer: Pubkey,
+}
+
+#[event]
+pub struct ReferralBound {
+    pub player: Pubkey,
+    pub referrer: Pubkey,
+}
+
+#[event]
+pub struct ReferralCredited {
+    pub arena: Pubkey,
+    pub arena_id: u64,
+    pub player: Pubkey,
+    pub referrer: Pubkey,
+    pub mint: Pubkey,
+    pub amount: u64,
+    pub balance: u64,
+}
+
+#[event]
+pub struct ReferralRewardsClaimed {
+    pub referrer: Pubkey,
+    pub mint: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct SeasonCreated {
+    pub season: Pubkey,
+    pub season_id: u64,
+    pub mint: Pubkey,
+    pub start_timestamp: i64,
+    pub end_timestamp: i64,
+    pub payout_bps: Vec<u16>,
+}
+
+#[event]
+pub struct SeasonFunded {
+    pub season: Pubkey,
+    pub season_id: u64,
+    pub funder: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct SeasonScoreRecorded {
+    pub season: Pubkey,
+    pub season_id: u64,
+    pub arena: Pubkey,
+    pub arena_id: u64,
+    pub player: Pubkey,
+    pub points: u64,
+    pub total_points: u64,
+}
+
+#[event]
+pub struct SeasonScoresRanked {
+    pub season: Pubkey,
+    pub season_id: u64,
+    pub ranked_participants: u32,
+    pub participants: u32,
+}
+
+#[event]
+pub struct SeasonScoreClosed {
+    pub season: Pubkey,
+    pub season_id: u64,
+    pub player: Pubkey,
+    pub rent_payer: Pubkey,
+}
+
+#[event]
+pub struct SeasonClosed {
+    pub season: Pubkey,
+    pub season_id: u64,
+    pub participants: u32,
+    pub winners: Vec<Pubkey>,
+    pub payouts: Vec<u64>,
+    pub treasury_amount: u64,
+}
+
 // ============================================================================
 // ENUMS
 // ============================================================================
@@ -835,6 +4714,14 @@ pub enum ArenaStatus {
     Active = 2,
     Ended = 3,
     Suspended = 4,
+    Expired = 5,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum SeasonStatus {
+    Active = 0,
+    Closed = 1,
+    Ranking = 2,
 }
 
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
@@ -843,6 +4730,80 @@ pub enum ArenaType {
     Bearish = 1,
 }
 
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum AccessPolicy {
+    Open = 0,
+    Allowlist = 1,
+    InviteCode = 2,
+}
+
+/// Identifies a custom arena created by `create_arena`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub struct CustomArenaKey {
+    pub creator: Pubkey,
+    pub nonce: u64,
+}
+
+/// Treasury fee rates, set through `update_settings` and snapshotted by each arena at creation
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, InitSpace)]
+pub struct FeeSchedule {
+    /// Fee when no override matches the player count, in bps of the pool
+    pub base_fee_bps: u64,
+    /// Fees for specific player counts; slots with a zero player count are unused
+    pub player_count_fees: [PlayerCountFee; MAX_FEE_OVERRIDES],
+    /// Ceiling on the fee whatever the base rate or overrides say, in bps
+    pub max_fee_bps: u64,
+    /// Arenas created within `[promo_start, promo_end)` charge no fee
+    pub promo_start: i64,
+    pub promo_end: i64,
+}
+
+/// Fee override for arenas that settle with exactly `player_count` players
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, InitSpace)]
+pub struct PlayerCountFee {
+    pub player_count: u8,
+    pub fee_bps: u64,
+}
+
+impl FeeSchedule {
+    /// Same fee for every arena, with no overrides or promotion
+    pub fn flat(fee_bps: u64) -> Self {
+        Self {
+            base_fee_bps: fee_bps,
+            max_fee_bps: fee_bps,
+            ..Default::default()
+        }
+    }
+
+    /// Copy stored on an arena created at `now`; a running promotion caps its fee at zero
+    pub fn snapshot(&self, now: i64) -> Self {
+        let mut snapshot = *self;
+        if (self.promo_start..self.promo_end).contains(&now) {
+            snapshot.max_fee_bps = 0;
+        }
+        snapshot
+    }
+
+    /// Fee in bps for an arena settling with `player_count` players
+    pub fn fee_bps(&self, player_count: u8) -> u64 {
+        self.player_count_fees
+            .iter()
+            .find(|fee| fee.player_count != 0 && fee.player_count == player_count)
+            .map_or(self.base_fee_bps, |fee| fee.fee_bps)
+            .min(self.max_fee_bps)
+    }
+
+    /// Rates fit in a pool, overrides name reachable player counts and the promotion window is ordered
+    pub fn is_valid(&self) -> bool {
+        self.max_fee_bps <= 10000
+            && self.promo_start <= self.promo_end
+            && self
+                .player_count_fees
+                .iter()
+                .all(|fee| fee.player_count as usize <= MAX_PLAYERS_PER_ARENA)
+    }
+}
+
 // ============================================================================
 // ERRORS
 // ============================================================================
@@ -853,7 +4814,7 @@ pub enum CryptarenaError {
     ProtocolPaused,
     #[msg("Invalid asset selection")]
     InvalidAsset,
-    #[msg("Entry amount must be between $10 and $20")]
+    #[msg("Entry value is outside the stake tier's range")]
     InvalidEntryAmount,
     #[msg("Maximum 3 players with same asset per arena")]
     MaxAssetLimitReached,
@@ -875,10 +4836,128 @@ pub enum CryptarenaError {
     AlreadyWithdrawn,
     #[msg("Insufficient balance in vault")]
     InsufficientBalance,
+    #[msg("Deposit amount must be positive")]
+    InvalidDepositAmount,
+    #[msg("Funding account for this entry was not provided")]
+    MissingFundingAccount,
     #[msg("Invalid arena duration")]
     InvalidDuration,
+    #[msg("Settings timelock cannot be negative")]
+    InvalidTimelock,
+    #[msg("No pending settings change")]
+    NoPendingSettings,
+    #[msg("Settings timelock has not elapsed")]
+    TimelockNotElapsed,
+    #[msg("Stake tier minimum must be positive and not above its maximum")]
+    InvalidStakeTier,
+    #[msg("Stake tier is not enabled")]
+    StakeTierDisabled,
     #[msg("Unauthorized")]
     Unauthorized,
     #[msg("Asset not represented in arena")]
     AssetNotInArena,
+    #[msg("Token mint does not match the arena asset")]
+    InvalidMint,
+    #[msg("Treasury fee already collected for this asset")]
+    TreasuryFeeAlreadyCollected,
+    #[msg("Crank bounty already claimed for this asset")]
+    CrankBountyAlreadyClaimed,
+    #[msg("Crank bounty plus treasury fee cannot exceed 100%")]
+    InvalidCrankBounty,
+    #[msg("Player capacity must be between 2 and 10")]
+    InvalidPlayerCapacity,
+    #[msg("Creator fee exceeds the allowed maximum")]
+    CreatorFeeTooHigh,
+    #[msg("Creator fee already claimed for this asset")]
+    CreatorFeeAlreadyClaimed,
+    #[msg("Tier or arena type does not match the arena")]
+    ArenaMismatch,
+    #[msg("Player is not on the arena's allowlist")]
+    NotInvited,
+    #[msg("Invite secret does not match the arena")]
+    InvalidInviteSecret,
+    #[msg("Arena does not use this access policy")]
+    InvalidAccessPolicy,
+    #[msg("Allowlist exceeds the maximum size")]
+    AllowlistTooLong,
+    #[msg("Destination is not owned by the treasury wallet")]
+    InvalidTreasury,
+    #[msg("Lobby has timed out and must be expired")]
+    LobbyExpired,
+    #[msg("Lobby timeout has not elapsed")]
+    LobbyNotExpired,
+    #[msg("Arena is not expired")]
+    ArenaNotExpired,
+    #[msg("Missing start price for a represented asset")]
+    MissingStartPrice,
+    #[msg("Price update is below the minimum verification level")]
+    InsufficientVerification,
+    #[msg("Oracle price must be positive")]
+    NonPositivePrice,
+    #[msg("Oracle confidence interval exceeds the asset's bound")]
+    ConfidenceTooWide,
+    #[msg("Oracle price is too old")]
+    StalePrice,
+    #[msg("Confidence bound must be between 1 and 10000 bps")]
+    InvalidConfidenceBound,
+    #[msg("Asset is not enabled in the registry")]
+    AssetDisabled,
+    #[msg("Price max age must be positive")]
+    InvalidMaxAge,
+    #[msg("Remaining accounts must be (AssetConfig, PriceUpdateV2) pairs followed by every player's PlayerProfile")]
+    InvalidRemainingAccounts,
+    #[msg("End price missing for a represented asset and the oracle grace period has not passed")]
+    MissingEndPrice,
+    #[msg("End price was not published within the window after arena end")]
+    EndPriceOutsideWindow,
+    #[msg("An earlier qualifying end price is already recorded")]
+    EndPriceAlreadySet,
+    #[msg("Arena has not finished")]
+    ArenaNotFinished,
+    #[msg("Arena still has outstanding claims")]
+    OutstandingClaims,
+    #[msg("Player entry still has an unclaimed reward or refund")]
+    EntryNotSettled,
+    #[msg("Arena still has open player entries or vaults")]
+    ArenaAccountsOpen,
+    #[msg("Rent receiver did not pay for this account")]
+    InvalidRentReceiver,
+    #[msg("Season must end after it starts")]
+    InvalidSeasonWindow,
+    #[msg("Season payouts must cover 1-10 ranks and sum to 10000 bps")]
+    InvalidPayoutSplit,
+    #[msg("Season is not active")]
+    SeasonNotActive,
+    #[msg("Season has not ended")]
+    SeasonNotEnded,
+    #[msg("Arena did not end within the season")]
+    ArenaOutsideSeason,
+    #[msg("Entry already credited to a season")]
+    AlreadyCredited,
+    #[msg("Season score belongs to another season")]
+    InvalidRanking,
+    #[msg("Referral share cannot exceed 10000 bps")]
+    InvalidReferralShare,
+    #[msg("Players cannot refer themselves")]
+    SelfReferral,
+    #[msg("No referral fee owed")]
+    NoReferralFee,
+    #[msg("Fee schedule is invalid or leaves no room for the crank bounty and creator fees")]
+    InvalidFeeSchedule,
+    #[msg("Asset was voided for a missing end price; its players are refunded")]
+    AssetVoided,
+    #[msg("Start price was not published within the window before activation")]
+    StartPriceOutsideWindow,
+    #[msg("Only the player may close an entry before its season score is recorded")]
+    SeasonScoreNotRecorded,
+    #[msg("Season score is already ranked")]
+    AlreadyRanked,
+    #[msg("Every season score must be ranked before the season closes")]
+    RankingIncomplete,
+    #[msg("Season can only be force-closed once the force-close delay after its end has passed")]
+    ForceCloseTooEarly,
+    #[msg("Start price supplied more than once for the same asset")]
+    DuplicateStartPrice,
+    #[msg("Season has not closed")]
+    SeasonNotClosed,
 }
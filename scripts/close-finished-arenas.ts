@@ -0,0 +1,81 @@
+/**
+ * Close Finished Arenas
+ *
+ * Crank that sweeps ended arenas whose claims are all paid: closes every
+ * player entry and arena asset, then the arena itself. Rent always goes back
+ * to the account that paid it, so anyone can run this.
+ */
+
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { CryptarenaSvmTest } from "../target/types/cryptarena_svm_test";
+import { Keypair } from "@solana/web3.js";
+
+const ARENA_STATUS_ENDED = 4;
+
+// ============================================================================
+// MAIN
+// ============================================================================
+
+async function main() {
+  console.log("\n" + "═".repeat(80));
+  console.log("🧹 CLOSE FINISHED ARENAS");
+  console.log("═".repeat(80) + "\n");
+
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.CryptarenaSvmTest as Program<CryptarenaSvmTest>;
+  const caller = (provider.wallet as any).payer as Keypair;
+
+  const arenas = await program.account.arena.all();
+  const finished = arenas.filter(
+    ({ account }) => account.status === ARENA_STATUS_ENDED && account.outstandingClaims === 0
+  );
+  console.log(`Found ${finished.length} finished arena(s) out of ${arenas.length}\n`);
+
+  for (const { publicKey: arenaPda, account: arena } of finished) {
+    console.log(`Arena ${arena.id.toString()}:`);
+    const arenaFilter = [{ memcmp: { offset: 8, bytes: arenaPda.toBase58() } }];
+
+    const entries = await program.account.playerEntry.all(arenaFilter);
+    for (const { publicKey, account } of entries) {
+      await program.methods
+        .closePlayerEntry()
+        .accountsPartial({
+          arena: arenaPda,
+          playerEntry: publicKey,
+          player: account.player,
+          caller: caller.publicKey,
+        })
+        .rpc();
+    }
+    console.log(`   ✅ Closed ${entries.length} player entries`);
+
+    const assets = await program.account.arenaAsset.all(arenaFilter);
+    for (const { publicKey, account } of assets) {
+      await program.methods
+        .closeArenaAsset()
+        .accountsPartial({
+          arena: arenaPda,
+          arenaAsset: publicKey,
+          rentPayer: account.rentPayer,
+          caller: caller.publicKey,
+        })
+        .rpc();
+    }
+    console.log(`   ✅ Closed ${assets.length} arena assets`);
+
+    await program.methods
+      .closeArena()
+      .accountsPartial({
+        arena: arenaPda,
+        rentPayer: arena.rentPayer,
+        caller: caller.publicKey,
+      })
+      .rpc();
+    console.log(`   ✅ Closed arena, rent returned to ${arena.rentPayer.toBase58()}\n`);
+  }
+}
+
+main().catch(console.error);
@@ -33,6 +33,8 @@ interface ArenaData {
   startTimestamp: anchor.BN;
   endTimestamp: anchor.BN;
   totalPool: anchor.BN;
+  treasuryFeeBps: anchor.BN;
+  referralFees: anchor.BN;
   tokenSlots: number[];
   playerAddresses: PublicKey[];
 }
@@ -139,9 +141,10 @@ async function main() {
       process.exit(1);
     }
 
-    // Calculate treasury fee (10% of total pool)
+    // Treasury fee at the rate resolved from the arena's fee schedule, less the referrers' cuts
     const totalPool = Number(arena.totalPool);
-    const treasuryFee = Math.floor((totalPool * 1000) / 10000); // 10%
+    const treasuryFeeBps = Number(arena.treasuryFeeBps);
+    const treasuryFee = Math.floor((totalPool * treasuryFeeBps) / 10000) - Number(arena.referralFees);
     console.log(`\nTreasury Fee to Claim: ${treasuryFee / LAMPORTS_PER_SOL} SOL (${treasuryFee} lamports)`);
 
     // Get vault balance before
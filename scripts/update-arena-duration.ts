@@ -103,6 +103,16 @@ async function main() {
       .signers([admin])
       .rpc();
 
+    // Duration changes are queued behind the settings timelock
+    await program.methods
+      .executeSettings()
+      .accountsStrict({
+        globalState: globalStatePda,
+        admin: admin.publicKey,
+      })
+      .signers([admin])
+      .rpc();
+
     console.log("✅ Arena duration updated successfully!");
     console.log(`   Transaction: ${tx}\n`);
 
@@ -1,7 +1,7 @@
 /**
  * Collect Treasury Fees Script
  * 
- * Collects 10% treasury fees from losers in ended arenas.
+ * Collects treasury fees from losers in ended arenas, at each arena's snapshotted fee rate.
  * INDEPENDENT of whether winners have claimed - treasury can collect anytime.
  * 
  * Usage:
@@ -176,6 +176,12 @@ async function main() {
         continue;
       }
 
+      // Zero-fee arenas (promotions, overrides) owe the treasury nothing
+      if (arena.treasuryFeeBps.toNumber() === 0) {
+        console.log(`⏭️  Arena ${arenaId} - No treasury fee, skipping`);
+        continue;
+      }
+
       console.log(`${"─".repeat(60)}`);
       console.log(`🏟️  Arena ${arenaId} | Winner: ${ASSET_NAMES[arena.winningAsset]} | Players: ${arena.playerCount}`);
 
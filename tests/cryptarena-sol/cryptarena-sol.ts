@@ -11,10 +11,10 @@ import { expect } from "chai";
 import BN from "bn.js";
 import * as fs from "fs";
 import * as path from "path";
+import { createHash } from "crypto";
 
 // Constants matching the program
-const TREASURY_FEE_BPS = 1000; // 10%
-const WINNER_SHARE_BPS = 9000; // 90%
+const TREASURY_FEE_BPS = 1000; // 10% default, waived for single-player arenas
 const DEFAULT_ENTRY_FEE = 50_000_000; // 0.05 SOL
 const DEFAULT_ARENA_DURATION = 180; // 3 minutes for testing
 const MIN_ARENA_DURATION = 180; // 3 minutes minimum
@@ -91,9 +91,8 @@ describe("Cryptarena SOL Protocol", () => {
   };
 
   // Helper to get arena PDA
-  const getArenaPda = (arenaId: number) => {
-    const idBuffer = Buffer.alloc(8);
-    idBuffer.writeBigUInt64LE(BigInt(arenaId));
+  const getArenaPda = (arenaId: number | BN) => {
+    const idBuffer = new BN(arenaId).toArrayLike(Buffer, "le", 8);
     return PublicKey.findProgramAddressSync(
       [Buffer.from("arena"), idBuffer],
       program.programId
@@ -101,15 +100,45 @@ describe("Cryptarena SOL Protocol", () => {
   };
 
   // Helper to get arena vault PDA
-  const getArenaVaultPda = (arenaId: number) => {
-    const idBuffer = Buffer.alloc(8);
-    idBuffer.writeBigUInt64LE(BigInt(arenaId));
+  const getArenaVaultPda = (arenaId: number | BN) => {
+    const idBuffer = new BN(arenaId).toArrayLike(Buffer, "le", 8);
     return PublicKey.findProgramAddressSync(
       [Buffer.from("arena_vault"), idBuffer],
       program.programId
     )[0];
   };
 
+  // Helper to decode the events a transaction emitted through `emit_cpi!`
+  const getCpiEvents = async (signature: string) => {
+    await provider.connection.confirmTransaction(signature, "confirmed");
+    const tx = await provider.connection.getTransaction(signature, {
+      commitment: "confirmed",
+      maxSupportedTransactionVersion: 0,
+    });
+    const keys = tx.transaction.message.getAccountKeys({ accountKeysFromLookups: tx.meta.loadedAddresses });
+    return (tx.meta.innerInstructions ?? [])
+      .flatMap(({ instructions }) => instructions)
+      .filter((ix) => keys.get(ix.programIdIndex).equals(program.programId))
+      // Skip the 8-byte event instruction tag to reach the event's own discriminator and data
+      .map((ix) =>
+        program.coder.events.decode(
+          Buffer.from(anchor.utils.bytes.bs58.decode(ix.data)).subarray(8).toString("base64")
+        )
+      )
+      .filter((event) => event !== null);
+  };
+
+  // Settings changes are queued; with no settings timelock they can be executed right away
+  const executeSettings = () =>
+    program.methods
+      .executeSettings()
+      .accounts({
+        globalState: globalStatePda,
+        admin: admin.publicKey,
+      })
+      .signers([admin])
+      .rpc();
+
   // Helper to get player entry PDA
   const getPlayerEntryPda = (arenaPda: PublicKey, player: PublicKey) => {
     return PublicKey.findProgramAddressSync(
@@ -118,6 +147,9 @@ describe("Cryptarena SOL Protocol", () => {
     )[0];
   };
 
+  const getPlayerProfilePda = (player: PublicKey) =>
+    PublicKey.findProgramAddressSync([Buffer.from("player_profile"), player.toBuffer()], program.programId)[0];
+
   before(async () => {
     // Load player wallets from test-wallets folder
     try {
@@ -247,6 +279,7 @@ describe("Cryptarena SOL Protocol", () => {
         })
         .signers([admin])
         .rpc();
+      await executeSettings();
 
       const globalState = await program.account.globalState.fetch(globalStatePda);
       expect(globalState.treasuryWallet.toString()).to.equal(newTreasury.publicKey.toString());
@@ -260,6 +293,7 @@ describe("Cryptarena SOL Protocol", () => {
         })
         .signers([admin])
         .rpc();
+      await executeSettings();
 
       console.log("✓ Treasury wallet updated successfully");
     });
@@ -275,6 +309,7 @@ describe("Cryptarena SOL Protocol", () => {
         })
         .signers([admin])
         .rpc();
+      await executeSettings();
 
       const globalState = await program.account.globalState.fetch(globalStatePda);
       expect(globalState.arenaDuration.toNumber()).to.equal(newDuration);
@@ -288,6 +323,7 @@ describe("Cryptarena SOL Protocol", () => {
         })
         .signers([admin])
         .rpc();
+      await executeSettings();
 
       console.log("✓ Arena duration updated successfully");
     });
@@ -321,6 +357,7 @@ describe("Cryptarena SOL Protocol", () => {
         })
         .signers([admin])
         .rpc();
+      await executeSettings();
 
       const globalState = await program.account.globalState.fetch(globalStatePda);
       expect(globalState.entryFee.toNumber()).to.equal(newFee);
@@ -334,6 +371,7 @@ describe("Cryptarena SOL Protocol", () => {
         })
         .signers([admin])
         .rpc();
+      await executeSettings();
 
       console.log("✓ Entry fee updated successfully");
     });
@@ -348,6 +386,7 @@ describe("Cryptarena SOL Protocol", () => {
         })
         .signers([admin])
         .rpc();
+      await executeSettings();
 
       let globalState = await program.account.globalState.fetch(globalStatePda);
       expect(globalState.isPaused).to.be.true;
@@ -361,6 +400,7 @@ describe("Cryptarena SOL Protocol", () => {
         })
         .signers([admin])
         .rpc();
+      await executeSettings();
 
       globalState = await program.account.globalState.fetch(globalStatePda);
       expect(globalState.isPaused).to.be.false;
@@ -385,6 +425,183 @@ describe("Cryptarena SOL Protocol", () => {
         console.log("✓ Non-admin correctly rejected");
       }
     });
+
+    it("2.7 should hold queued settings until the timelock elapses", async () => {
+      const TEST_TIMELOCK = 5;
+      const updateSettingsTimelock = (timelock: number) =>
+        program.methods
+          .updateSettingsTimelock(new BN(timelock))
+          .accounts({
+            globalState: globalStatePda,
+            admin: admin.publicKey,
+          })
+          .signers([admin])
+          .rpc();
+      const cancelSettings = () =>
+        program.methods
+          .cancelSettings()
+          .accounts({
+            globalState: globalStatePda,
+            admin: admin.publicKey,
+          })
+          .signers([admin])
+          .rpc();
+
+      try {
+        await cancelSettings();
+        expect.fail("Should have thrown an error");
+      } catch (error: any) {
+        expect(error.message).to.include("NoPendingSettings");
+      }
+      try {
+        await updateSettingsTimelock(-1);
+        expect.fail("Should have thrown an error");
+      } catch (error: any) {
+        expect(error.message).to.include("InvalidTimelock");
+      }
+
+      // With no timelock yet, raising it applies right away
+      await updateSettingsTimelock(TEST_TIMELOCK);
+      await executeSettings();
+      expect((await program.account.globalState.fetch(globalStatePda)).settingsTimelock.toNumber())
+        .to.equal(TEST_TIMELOCK);
+
+      const updateEntryFee = (entryFee: number) =>
+        program.methods
+          .updateEntryFee(new BN(entryFee))
+          .accounts({
+            globalState: globalStatePda,
+            admin: admin.publicKey,
+          })
+          .signers([admin])
+          .rpc();
+
+      // A queued change waits out the timelock and can be discarded meanwhile
+      await updateEntryFee(DEFAULT_ENTRY_FEE * 2);
+      try {
+        await executeSettings();
+        expect.fail("Should have thrown an error");
+      } catch (error: any) {
+        expect(error.message).to.include("TimelockNotElapsed");
+      }
+      await cancelSettings();
+      let globalState = await program.account.globalState.fetch(globalStatePda);
+      expect(globalState.pendingSettings).to.be.null;
+      expect(globalState.entryFee.toNumber()).to.equal(DEFAULT_ENTRY_FEE);
+
+      // Lowering the timelock back to 0 is itself held for the current timelock,
+      // and a change queued after it does not restart its timer
+      await updateSettingsTimelock(0);
+      try {
+        await executeSettings();
+        expect.fail("Should have thrown an error");
+      } catch (error: any) {
+        expect(error.message).to.include("TimelockNotElapsed");
+      }
+      const executableAt = (await program.account.globalState.fetch(globalStatePda)).pendingSettings.settingsTimelock
+        .executableAt;
+      await new Promise((resolve) => setTimeout(resolve, 4000));
+      await updateEntryFee(DEFAULT_ENTRY_FEE);
+      const { pendingSettings } = await program.account.globalState.fetch(globalStatePda);
+      expect(pendingSettings.settingsTimelock.executableAt.toString()).to.equal(executableAt.toString());
+      expect(pendingSettings.entryFee.executableAt.toNumber()).to.be.greaterThan(executableAt.toNumber());
+      await new Promise((resolve) => setTimeout(resolve, (executableAt.toNumber() + 2) * 1000 - Date.now()));
+      await executeSettings();
+      globalState = await program.account.globalState.fetch(globalStatePda);
+      expect(globalState.settingsTimelock.toNumber()).to.equal(0);
+      expect(globalState.pendingSettings.settingsTimelock).to.be.null;
+      expect(globalState.pendingSettings.entryFee).to.not.be.null;
+      await cancelSettings();
+
+      console.log("✓ Settings changes held for their own timelocks");
+    });
+
+    it("2.8 should hand the admin role over only once the nominee accepts", async () => {
+      const proposeAdmin = (newAdmin: PublicKey, signer: Keypair) =>
+        program.methods
+          .proposeAdmin(newAdmin)
+          .accounts({
+            globalState: globalStatePda,
+            admin: signer.publicKey,
+          })
+          .signers([signer])
+          .rpc();
+      const acceptAdmin = (signer: Keypair) =>
+        program.methods
+          .acceptAdmin()
+          .accounts({
+            globalState: globalStatePda,
+            newAdmin: signer.publicKey,
+          })
+          .signers([signer])
+          .rpc();
+
+      for (const attempt of [() => proposeAdmin(nonAdmin.publicKey, nonAdmin), () => acceptAdmin(nonAdmin)]) {
+        try {
+          await attempt();
+          expect.fail("Should have thrown an error");
+        } catch (error: any) {
+          expect(error.message).to.include("Unauthorized");
+        }
+      }
+
+      await proposeAdmin(nonAdmin.publicKey, admin);
+      let globalState = await program.account.globalState.fetch(globalStatePda);
+      expect(globalState.admin.toString()).to.equal(admin.publicKey.toString());
+      expect(globalState.pendingAdmin.toString()).to.equal(nonAdmin.publicKey.toString());
+
+      await acceptAdmin(nonAdmin);
+      globalState = await program.account.globalState.fetch(globalStatePda);
+      expect(globalState.admin.toString()).to.equal(nonAdmin.publicKey.toString());
+      expect(globalState.pendingAdmin).to.be.null;
+
+      // Hand the role back for the remaining tests
+      await proposeAdmin(admin.publicKey, nonAdmin);
+      await acceptAdmin(admin);
+      expect((await program.account.globalState.fetch(globalStatePda)).admin.toString())
+        .to.equal(admin.publicKey.toString());
+
+      console.log("✓ Admin role handed over in two steps");
+    });
+
+    it("2.9 should reject a referral share above the whole treasury fee", async () => {
+      try {
+        await program.methods
+          .updateReferralShare(new BN(10001))
+          .accounts({
+            globalState: globalStatePda,
+            admin: admin.publicKey,
+          })
+          .signers([admin])
+          .rpc();
+
+        expect.fail("Should have thrown an error");
+      } catch (error: any) {
+        expect(error.message).to.include("InvalidReferralShare");
+        console.log("✓ Referral share above 10000 bps rejected");
+      }
+    });
+
+    it("2.10 should reject a fee schedule capped above the whole pool", async () => {
+      const globalState = await program.account.globalState.fetch(globalStatePda);
+      expect(globalState.feeSchedule.baseFeeBps.toNumber()).to.equal(TREASURY_FEE_BPS);
+
+      try {
+        await program.methods
+          .updateFeeSchedule({ ...globalState.feeSchedule, maxFeeBps: new BN(10_001) })
+          .accounts({
+            globalState: globalStatePda,
+            admin: admin.publicKey,
+          })
+          .signers([admin])
+          .rpc();
+
+        expect.fail("Should have thrown an error");
+      } catch (error: any) {
+        expect(error.message).to.include("InvalidFeeSchedule");
+        console.log("✓ Invalid fee schedule rejected");
+      }
+    });
   });
 
   // ============================================================================
@@ -585,8 +802,8 @@ describe("Cryptarena SOL Protocol", () => {
       const playerEntryPda = getPlayerEntryPda(arenaPda, player1.publicKey);
       const whitelistPda = getWhitelistTokenPda(ASSET_SOL);
 
-      await program.methods
-        .enterArena(ASSET_SOL)
+      const signature = await program.methods
+        .enterArena(ASSET_SOL, null, null, null)
         .accounts({
           globalState: globalStatePda,
           arena: arenaPda,
@@ -604,6 +821,14 @@ describe("Cryptarena SOL Protocol", () => {
       expect(arena.status).to.equal(1); // Waiting (Uninitialized=0, Waiting=1)
       expect(arena.playerCount).to.equal(1);
 
+      // Indexers see the arena created and the entry in the same transaction
+      const events = await getCpiEvents(signature);
+      const created = events.find((event) => event.name === "ArenaCreated");
+      const entered = events.find((event) => event.name === "PlayerEntered");
+      expect(created.data.arenaId.toNumber()).to.equal(arenaId);
+      expect(entered.data.player.toBase58()).to.equal(player1.publicKey.toBase58());
+      expect(entered.data.playerCount).to.equal(1);
+
       console.log("✓ First player auto-created arena");
     });
 
@@ -617,13 +842,14 @@ describe("Cryptarena SOL Protocol", () => {
         })
         .signers([admin])
         .rpc();
+      await executeSettings();
 
       try {
         const playerEntryPda = getPlayerEntryPda(arenaPda, player2.publicKey);
         const whitelistPda = getWhitelistTokenPda(ASSET_PYTH);
         
         await program.methods
-          .enterArena(ASSET_PYTH)
+          .enterArena(ASSET_PYTH, null, null, null)
           .accounts({
             globalState: globalStatePda,
             arena: arenaPda,
@@ -651,6 +877,7 @@ describe("Cryptarena SOL Protocol", () => {
         })
         .signers([admin])
         .rpc();
+      await executeSettings();
     });
   });
 
@@ -665,7 +892,7 @@ describe("Cryptarena SOL Protocol", () => {
         const whitelistPda = getWhitelistTokenPda(ASSET_SOL); // Same as player1
 
         await program.methods
-          .enterArena(ASSET_SOL)
+          .enterArena(ASSET_SOL, null, null, null)
           .accounts({
             globalState: globalStatePda,
             arena: arenaPda,
@@ -690,7 +917,7 @@ describe("Cryptarena SOL Protocol", () => {
       const whitelistPda = getWhitelistTokenPda(ASSET_PYTH);
 
       await program.methods
-        .enterArena(ASSET_PYTH)
+        .enterArena(ASSET_PYTH, null, null, player1.publicKey)
         .accounts({
           globalState: globalStatePda,
           arena: arenaPda,
@@ -716,7 +943,7 @@ describe("Cryptarena SOL Protocol", () => {
         const whitelistPda = getWhitelistTokenPda(50); // Non-existent
 
         await program.methods
-          .enterArena(50)
+          .enterArena(50, null, null, null)
           .accounts({
             globalState: globalStatePda,
             arena: arenaPda,
@@ -747,6 +974,262 @@ describe("Cryptarena SOL Protocol", () => {
 
       console.log(`✓ Arena vault has ${vaultBalance / LAMPORTS_PER_SOL} SOL`);
     });
+
+    it("5.5 should require the last player's entry to fill a vacated slot", async () => {
+      // Player3 takes the last slot, so player1 leaving slot 0 must move player3 into it
+      await program.methods
+        .enterArena(ASSET_BONK, null, null, null)
+        .accounts({
+          globalState: globalStatePda,
+          arena: arenaPda,
+          arenaVault: arenaVaultPda,
+          playerEntry: getPlayerEntryPda(arenaPda, player3.publicKey),
+          whitelistedToken: getWhitelistTokenPda(ASSET_BONK),
+          player: player3.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([player3])
+        .rpc();
+
+      try {
+        await program.methods
+          .leaveArena()
+          .accounts({
+            arena: arenaPda,
+            arenaVault: arenaVaultPda,
+            playerEntry: getPlayerEntryPda(arenaPda, player1.publicKey),
+            movedEntry: null,
+            player: player1.publicKey,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([player1])
+          .rpc();
+
+        expect.fail("Should have thrown an error");
+      } catch (error: any) {
+        expect(error.message).to.include("MissingMovedEntry");
+        console.log("✓ Leaving without the last player's entry rejected");
+      }
+    });
+
+    it("5.6 should refund a leaving player and compact the slots", async () => {
+      const player1EntryPda = getPlayerEntryPda(arenaPda, player1.publicKey);
+      const player3EntryPda = getPlayerEntryPda(arenaPda, player3.publicKey);
+      const vaultBefore = await provider.connection.getBalance(arenaVaultPda);
+
+      await program.methods
+        .leaveArena()
+        .accounts({
+          arena: arenaPda,
+          arenaVault: arenaVaultPda,
+          playerEntry: player1EntryPda,
+          movedEntry: player3EntryPda,
+          player: player1.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([player1])
+        .rpc();
+
+      const arena = await program.account.arena.fetch(arenaPda);
+      expect(arena.playerCount).to.equal(2);
+      expect(arena.tokenSlots[0]).to.equal(ASSET_BONK);
+      expect(arena.tokenSlots[2]).to.equal(255);
+      expect(arena.playerAddresses[0].toString()).to.equal(player3.publicKey.toString());
+      expect(arena.totalPool.toNumber()).to.equal(DEFAULT_ENTRY_FEE * 2);
+      expect((await program.account.playerEntry.fetch(player3EntryPda)).playerIndex).to.equal(0);
+      expect(await program.account.playerEntry.fetchNullable(player1EntryPda)).to.be.null;
+
+      const vaultAfter = await provider.connection.getBalance(arenaVaultPda);
+      expect(vaultBefore - vaultAfter).to.equal(DEFAULT_ENTRY_FEE);
+
+      // Player1 rejoins for the price tests
+      await program.methods
+        .enterArena(ASSET_SOL, null, null, null)
+        .accounts({
+          globalState: globalStatePda,
+          arena: arenaPda,
+          arenaVault: arenaVaultPda,
+          playerEntry: player1EntryPda,
+          whitelistedToken: getWhitelistTokenPda(ASSET_SOL),
+          player: player1.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([player1])
+        .rpc();
+      expect((await program.account.arena.fetch(arenaPda)).playerCount).to.equal(3);
+
+      console.log("✓ Player refunded and last player moved into the vacated slot");
+    });
+
+    // Private arenas take ids from 2^63 upwards and are joined by id
+    const PRIVATE_ARENA_ID_OFFSET = new BN(1).shln(63);
+
+    const createPrivateArena = async (accessPolicy: object, inviteHash: number[]) => {
+      const globalState = await program.account.globalState.fetch(globalStatePda);
+      const arenaId = PRIVATE_ARENA_ID_OFFSET.add(globalState.privateArenaCount);
+      await program.methods
+        .createPrivateArena(accessPolicy as any, inviteHash)
+        .accounts({
+          globalState: globalStatePda,
+          arena: getArenaPda(arenaId),
+          arenaVault: getArenaVaultPda(arenaId),
+          creator: admin.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([admin])
+        .rpc();
+      return arenaId;
+    };
+
+    const getArenaAllowlistPda = (privateArenaPda: PublicKey) =>
+      PublicKey.findProgramAddressSync(
+        [Buffer.from("arena_allowlist"), privateArenaPda.toBuffer()],
+        program.programId
+      )[0];
+
+    const enterPrivateArena = (
+      player: Keypair,
+      arenaId: BN,
+      inviteSecret: Buffer | null,
+      withAllowlist: boolean
+    ) =>
+      program.methods
+        .enterArena(ASSET_SOL, arenaId, inviteSecret, null)
+        .accounts({
+          globalState: globalStatePda,
+          arena: getArenaPda(arenaId),
+          arenaVault: getArenaVaultPda(arenaId),
+          arenaAllowlist: withAllowlist ? getArenaAllowlistPda(getArenaPda(arenaId)) : null,
+          playerEntry: getPlayerEntryPda(getArenaPda(arenaId), player.publicKey),
+          whitelistedToken: getWhitelistTokenPda(ASSET_SOL),
+          player: player.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([player])
+        .rpc();
+
+    const leavePrivateArena = (player: Keypair, arenaId: BN) =>
+      program.methods
+        .leaveArena()
+        .accounts({
+          arena: getArenaPda(arenaId),
+          arenaVault: getArenaVaultPda(arenaId),
+          playerEntry: getPlayerEntryPda(getArenaPda(arenaId), player.publicKey),
+          movedEntry: null,
+          player: player.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([player])
+        .rpc();
+
+    it("5.7 should only admit allowlisted players to a private arena", async () => {
+      try {
+        await createPrivateArena({ open: {} }, new Array(32).fill(0));
+        expect.fail("Should have thrown an error");
+      } catch (error: any) {
+        expect(error.message).to.include("InvalidAccessPolicy");
+      }
+
+      const arenaId = await createPrivateArena({ allowlist: {} }, new Array(32).fill(0));
+      const privateArenaPda = getArenaPda(arenaId);
+      const setAllowlist = (creator: Keypair) =>
+        program.methods
+          .setArenaAllowlist([player1.publicKey])
+          .accounts({
+            arena: privateArenaPda,
+            arenaAllowlist: getArenaAllowlistPda(privateArenaPda),
+            creator: creator.publicKey,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([creator])
+          .rpc();
+
+      try {
+        await setAllowlist(nonAdmin);
+        expect.fail("Should have thrown an error");
+      } catch (error: any) {
+        expect(error.message).to.include("Unauthorized");
+      }
+      await setAllowlist(admin);
+
+      for (const [player, withAllowlist] of [
+        [nonAdmin, true],
+        [player1, false], // The allowlist account must be passed even by an allowlisted player
+      ] as [Keypair, boolean][]) {
+        try {
+          await enterPrivateArena(player, arenaId, null, withAllowlist);
+          expect.fail("Should have thrown an error");
+        } catch (error: any) {
+          expect(error.message).to.include("NotInvited");
+        }
+      }
+      await enterPrivateArena(player1, arenaId, null, true);
+
+      const arena = await program.account.arena.fetch(privateArenaPda);
+      expect(arena.id.toString()).to.equal(arenaId.toString());
+      expect(arena.creator.toString()).to.equal(admin.publicKey.toString());
+      expect(arena.accessPolicy).to.equal(1); // Allowlist
+      expect(arena.playerCount).to.equal(1);
+
+      // The public sequence is untouched by private arenas
+      expect((await program.account.globalState.fetch(globalStatePda)).currentArenaId.toString())
+        .to.equal((await program.account.arena.fetch(arenaPda)).id.toString());
+
+      await leavePrivateArena(player1, arenaId);
+      console.log("✓ Allowlist arena admitted only its allowlisted player");
+    });
+
+    it("5.8 should only admit holders of the invite secret to a private arena", async () => {
+      const secret = Buffer.from("cryptarena-invite");
+      const inviteHash = Array.from(createHash("sha256").update(secret).digest());
+      const arenaId = await createPrivateArena({ inviteCode: {} }, inviteHash);
+
+      for (const inviteSecret of [null, Buffer.from("wrong")]) {
+        try {
+          await enterPrivateArena(player2, arenaId, inviteSecret, false);
+          expect.fail("Should have thrown an error");
+        } catch (error: any) {
+          expect(error.message).to.include("InvalidInviteSecret");
+        }
+      }
+      await enterPrivateArena(player2, arenaId, secret, false);
+
+      const arena = await program.account.arena.fetch(getArenaPda(arenaId));
+      expect(arena.accessPolicy).to.equal(2); // InviteCode
+      expect(arena.playerCount).to.equal(1);
+
+      await leavePrivateArena(player2, arenaId);
+      console.log("✓ Invite code arena admitted only the secret's holder");
+    });
+
+    it("5.9 should record the entry on the player profile", async () => {
+      const profile = await program.account.playerProfile.fetch(getPlayerProfilePda(player2.publicKey));
+
+      // Profiles persist across runs, so only lower bounds are checked
+      expect(profile.player.toBase58()).to.equal(player2.publicKey.toBase58());
+      expect(profile.arenasPlayed).to.be.greaterThanOrEqual(1);
+      expect(profile.assetEntries[ASSET_PYTH]).to.be.greaterThanOrEqual(1);
+      expect(profile.totalStaked.toNumber()).to.be.greaterThanOrEqual(DEFAULT_ENTRY_FEE);
+
+      console.log(`✓ Player profile: ${profile.arenasPlayed} arenas, favorite asset ${profile.favoriteAsset}`);
+    });
+
+    it("5.10 should bind the referrer on the player's first entry", async () => {
+      const [referralPda] = PublicKey.findProgramAddressSync(
+        [Buffer.from("referral"), player2.publicKey.toBuffer()],
+        program.programId
+      );
+      const referral = await program.account.referral.fetch(referralPda);
+      const playerEntry = await program.account.playerEntry.fetch(
+        getPlayerEntryPda(arenaPda, player2.publicKey)
+      );
+
+      // The binding is permanent, so a wallet that entered on an earlier run keeps its first referrer
+      expect(referral.player.toBase58()).to.equal(player2.publicKey.toBase58());
+      expect(playerEntry.referrer.toBase58()).to.equal(referral.referrer.toBase58());
+
+      console.log(`✓ Referral bound to ${referral.referrer.toBase58()}`);
+    });
   });
 
   // ============================================================================
@@ -872,6 +1355,28 @@ describe("Cryptarena SOL Protocol", () => {
         console.log("✓ End price before duration rejected");
       }
     });
+
+    it("7.4 should reject closing entries of an unfinished arena", async () => {
+      try {
+        const playerEntryPda = getPlayerEntryPda(arenaPda, player1.publicKey);
+
+        await program.methods
+          .closePlayerEntry()
+          .accounts({
+            arena: arenaPda,
+            playerEntry: playerEntryPda,
+            player: player1.publicKey,
+            caller: admin.publicKey,
+          })
+          .signers([admin])
+          .rpc();
+
+        expect.fail("Should have thrown an error");
+      } catch (error: any) {
+        expect(error.message).to.include("ArenaNotFinished");
+        console.log("✓ Closing entry of running arena rejected");
+      }
+    });
   });
 
   // ============================================================================
@@ -881,7 +1386,7 @@ describe("Cryptarena SOL Protocol", () => {
     it("8.1 should calculate correct reward splits", () => {
       const totalPool = 100_000_000; // 0.1 SOL total (2 players x 0.05 SOL)
       const treasuryFee = Math.floor((totalPool * TREASURY_FEE_BPS) / 10000);
-      const winnerReward = Math.floor((totalPool * WINNER_SHARE_BPS) / 10000);
+      const winnerReward = totalPool - treasuryFee;
       
       expect(treasuryFee).to.equal(10_000_000); // 10%
       expect(winnerReward).to.equal(90_000_000); // 90%
@@ -901,6 +1406,189 @@ describe("Cryptarena SOL Protocol", () => {
       
       console.log("✓ Price movement: 15% = 1500 basis points");
     });
+
+    it("8.3 should charge the fee from the arena's schedule and hold referrers' cuts out of it", async () => {
+      let arena = await program.account.arena.fetch(arenaPda);
+
+      // Player3 took the slot player1 vacated in 5.6 and still needs a start price
+      await program.methods
+        .setStartPrice(new BN(1_000))
+        .accounts({
+          globalState: globalStatePda,
+          arena: arenaPda,
+          playerEntry: getPlayerEntryPda(arenaPda, player3.publicKey),
+          admin: admin.publicKey,
+        })
+        .signers([admin])
+        .rpc();
+
+      const waitMs = (arena.endTimestamp.toNumber() + 5) * 1000 - Date.now();
+      if (waitMs > 0) {
+        console.log(`  Waiting ${Math.ceil(waitMs / 1000)}s for arena ${arena.id.toNumber()} to end...`);
+        await new Promise(resolve => setTimeout(resolve, waitMs));
+      }
+
+      // Player1's SOL (+6.67%) beats player2's PYTH (+5%) and player3's BONK (+1%)
+      const endPrices: [Keypair, number][] = [[player1, 160_000_000], [player2, 10_500_000], [player3, 1_010]];
+      for (const [player, endPrice] of endPrices) {
+        await program.methods
+          .setEndPrice(new BN(endPrice))
+          .accounts({
+            globalState: globalStatePda,
+            arena: arenaPda,
+            playerEntry: getPlayerEntryPda(arenaPda, player.publicKey),
+            admin: admin.publicKey,
+          })
+          .signers([admin])
+          .rpc();
+      }
+
+      // Each player's entry is followed by their profile, which records a loss as the arena ends
+      const endArena = (players: Keypair[]) =>
+        program.methods
+          .endArena()
+          .accounts({
+            globalState: globalStatePda,
+            arena: arenaPda,
+            admin: admin.publicKey,
+          })
+          .remainingAccounts(players.flatMap((player) => [
+            { pubkey: getPlayerEntryPda(arenaPda, player.publicKey), isWritable: false, isSigner: false },
+            { pubkey: getPlayerProfilePda(player.publicKey), isWritable: true, isSigner: false },
+          ]))
+          .signers([admin])
+          .rpc();
+      const players = endPrices.map(([player]) => player);
+      const lossesBefore = await Promise.all(
+        players.map(async (player) => (await program.account.playerProfile.fetch(getPlayerProfilePda(player.publicKey))).losses)
+      );
+
+      // Every entry must be passed once
+      try {
+        await endArena([player1, player2, player2]);
+        expect.fail("Should have thrown an error");
+      } catch (error: any) {
+        expect(error.message).to.include("InvalidRemainingAccounts");
+      }
+      await endArena(players);
+
+      arena = await program.account.arena.fetch(arenaPda);
+      expect(arena.status).to.equal(3); // Ended
+      expect(arena.winningAsset).to.equal(ASSET_SOL);
+      for (const [i, player] of players.entries()) {
+        const profile = await program.account.playerProfile.fetch(getPlayerProfilePda(player.publicKey));
+        if (player === player1) {
+          expect(profile.losses).to.equal(lossesBefore[i]);
+        } else {
+          expect(profile.losses - lossesBefore[i]).to.equal(1);
+          expect(profile.currentStreak).to.equal(0);
+          expect(profile.streakTimestamp.toNumber()).to.equal(arena.endTimestamp.toNumber());
+        }
+      }
+      // The default schedule only exempts single-player arenas, so three players pay the base rate
+      expect(arena.feeSchedule.baseFeeBps.toNumber()).to.equal(TREASURY_FEE_BPS);
+      expect(arena.treasuryFeeBps.toNumber()).to.equal(TREASURY_FEE_BPS);
+
+      const totalPool = arena.totalPool.toNumber();
+      const treasuryFee = Math.floor((totalPool * arena.treasuryFeeBps.toNumber()) / 10000);
+
+      const winnerBefore = await provider.connection.getBalance(player1.publicKey);
+      await program.methods
+        .claimWinnerRewards()
+        .accounts({
+          arena: arenaPda,
+          arenaVault: arenaVaultPda,
+          playerEntry: getPlayerEntryPda(arenaPda, player1.publicKey),
+          winner: player1.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([player1])
+        .rpc();
+      const winnerAfter = await provider.connection.getBalance(player1.publicKey);
+      expect(winnerAfter - winnerBefore).to.equal(totalPool - treasuryFee);
+
+      // Referrers' cuts stay in the vault until they are credited
+      const globalState = await program.account.globalState.fetch(globalStatePda);
+      const treasuryBefore = await provider.connection.getBalance(globalState.treasuryWallet);
+      await program.methods
+        .claimTreasuryFee()
+        .accounts({
+          globalState: globalStatePda,
+          arena: arenaPda,
+          arenaVault: arenaVaultPda,
+          treasuryWallet: globalState.treasuryWallet,
+          admin: admin.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([admin])
+        .rpc();
+      const treasuryAfter = await provider.connection.getBalance(globalState.treasuryWallet);
+      expect(treasuryAfter - treasuryBefore).to.equal(treasuryFee - arena.referralFees.toNumber());
+
+      console.log(`✓ Treasury paid ${(treasuryFee - arena.referralFees.toNumber()) / LAMPORTS_PER_SOL} SOL, referral cuts held back`);
+    });
+
+    it("8.4 should credit the referrer's cut of the treasury fee", async () => {
+      const arena = await program.account.arena.fetch(arenaPda);
+      const playerEntryPda = getPlayerEntryPda(arenaPda, player2.publicKey);
+      const playerEntry = await program.account.playerEntry.fetch(playerEntryPda);
+      const [referrerBalancePda] = PublicKey.findProgramAddressSync(
+        [Buffer.from("referrer_balance"), playerEntry.referrer.toBuffer()],
+        program.programId
+      );
+      const accruedBefore = await program.account.referrerBalance
+        .fetch(referrerBalancePda)
+        .then(balance => balance.balance.toNumber())
+        .catch(() => 0);
+
+      const creditReferral = () =>
+        program.methods
+          .creditReferral()
+          .accounts({
+            arena: arenaPda,
+            arenaVault: arenaVaultPda,
+            playerEntry: playerEntryPda,
+            referrerBalance: referrerBalancePda,
+            caller: admin.publicKey,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([admin])
+          .rpc();
+      await creditReferral();
+
+      // 20% of the entry's 10% treasury fee, already reserved out of the treasury's claim in 8.3
+      const referralFee = Math.floor(
+        Math.floor((DEFAULT_ENTRY_FEE * TREASURY_FEE_BPS) / 10000) * arena.referralShareBps.toNumber() / 10000
+      );
+      const referrerBalance = await program.account.referrerBalance.fetch(referrerBalancePda);
+      expect(arena.referralFees.toNumber()).to.equal(referralFee);
+      expect(referrerBalance.balance.toNumber() - accruedBefore).to.equal(referralFee);
+      expect((await program.account.playerEntry.fetch(playerEntryPda)).referralFee.toNumber()).to.equal(0);
+
+      try {
+        await creditReferral();
+        expect.fail("Should have thrown an error");
+      } catch (error: any) {
+        expect(error.message).to.include("NoReferralFee");
+      }
+
+      // Player1 referred player2 in 5.2, so it can withdraw the balance
+      if (playerEntry.referrer.equals(player1.publicKey)) {
+        const referrerBefore = await provider.connection.getBalance(player1.publicKey);
+        await program.methods
+          .claimReferralRewards()
+          .accounts({
+            referrerBalance: referrerBalancePda,
+            referrer: player1.publicKey,
+          })
+          .signers([player1])
+          .rpc();
+        const referrerAfter = await provider.connection.getBalance(player1.publicKey);
+        expect(referrerAfter - referrerBefore).to.equal(referrerBalance.balance.toNumber());
+      }
+
+      console.log(`✓ Referrer credited ${referralFee / LAMPORTS_PER_SOL} SOL`);
+    });
   });
 
   // ============================================================================
@@ -922,8 +1610,9 @@ describe("Cryptarena SOL Protocol", () => {
         ║  7. Wait for duration (min 10 minutes)                        ║
         ║  8. Admin sets end prices for all tokens                      ║
         ║  9. Admin ends arena (determines winner)                      ║
-        ║  10. Winner claims 90% of pool                                ║
-        ║  11. Admin claims 10% treasury fee                            ║
+        ║  10. Winner claims the pool less the treasury fee             ║
+        ║  11. Admin claims the treasury fee (10% by default)           ║
+        ║  12. Anyone closes entries and arena to reclaim rent          ║
         ╠═══════════════════════════════════════════════════════════════╣
         ║  Edge Cases:                                                  ║
         ║  - Tie: Arena canceled, players claim refunds                 ║
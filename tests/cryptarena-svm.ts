@@ -6,10 +6,20 @@ import {
   Keypair,
   PublicKey,
   SystemProgram,
+  Transaction,
   LAMPORTS_PER_SOL,
 } from "@solana/web3.js";
+import {
+  createMint,
+  getAccount,
+  getOrCreateAssociatedTokenAccount,
+  mintTo,
+} from "@solana/spl-token";
+import { PythSolanaReceiver } from "@pythnetwork/pyth-solana-receiver";
 import { expect } from "chai";
 import BN from "bn.js";
+import https from "https";
+import { createHash } from "crypto";
 
 // Asset indices
 const ASSET_SOL = 0;
@@ -33,6 +43,11 @@ const MAX_PLAYERS_PER_ARENA = 10;
 const MIN_ENTRY_USD = 10_000_000; // $10 with 6 decimals
 const MAX_ENTRY_USD = 20_000_000; // $20 with 6 decimals
 const DEFAULT_ARENA_DURATION = 600; // 10 minutes
+const END_PRICE_WINDOW = 1; // End prices must be published within 1 second after the arena ends
+const MAX_ASSETS = 16; // Registry capacity
+const MAX_PRICE_AGE = 60; // Seconds, for test listings
+const DEFAULT_MAX_CONFIDENCE_BPS = 200; // Confidence interval up to 2% of price, for test listings
+const TEST_TIER_ID = 200; // $10-$20 stake tier the arena tests play in
 
 // Pyth Price Feed IDs (hex strings)
 const PYTH_FEEDS = {
@@ -52,6 +67,61 @@ const PYTH_FEEDS = {
   W: "eff7446475e218517566ea99e72a4abec2e1bd8498b43b7d8331e29dcb059389",
 };
 
+// Hermes serves Pyth price updates as signed binaries for posting, with parsed prices alongside
+function fetchHermes(path: string): Promise<any> {
+  return new Promise((resolve, reject) => {
+    https
+      .get({ hostname: "hermes.pyth.network", path }, (res) => {
+        let data = "";
+        res.on("data", (chunk) => { data += chunk; });
+        res.on("end", () => {
+          try {
+            resolve(JSON.parse(data));
+          } catch {
+            reject(new Error(`Unexpected Hermes response: ${data}`));
+          }
+        });
+      })
+      .on("error", reject);
+  });
+}
+
+const hermesQuery = (feedIds: string[]) => feedIds.map((feedId) => `ids[]=${feedId}`).join("&");
+
+// Base64 price updates for `feedIds`: the latest ones, or the first published at or after `publishTime`
+async function fetchPriceUpdates(feedIds: string[], publishTime?: number): Promise<string[]> {
+  const response = await fetchHermes(
+    `/v2/updates/price/${publishTime ?? "latest"}?${hermesQuery(feedIds)}&encoding=base64`
+  );
+  return response.binary.data;
+}
+
+// Latest price, confidence and exponent per feed id
+async function fetchLatestPrices(
+  feedIds: string[]
+): Promise<{ [feedId: string]: { price: bigint; conf: bigint; expo: number } }> {
+  const response = await fetchHermes(`/v2/updates/price/latest?${hermesQuery(feedIds)}&parsed=true`);
+  return Object.fromEntries(
+    response.parsed.map((feed: any) => [
+      feed.id,
+      { price: BigInt(feed.price.price), conf: BigInt(feed.price.conf), expo: feed.price.expo },
+    ])
+  );
+}
+
+const sleep = (ms: number) => new Promise((resolve) => setTimeout(resolve, ms));
+
+// Run `promise` and expect it to fail with the program error `code`
+async function expectError(promise: Promise<unknown>, code: string) {
+  try {
+    await promise;
+  } catch (error: any) {
+    expect(error.error?.errorCode?.code ?? error.message).to.contain(code);
+    return;
+  }
+  expect.fail(`Expected ${code}`);
+}
+
 describe("Cryptarena SVM Protocol", () => {
   // Configure the client to use the local cluster
   const provider = anchor.AnchorProvider.env();
@@ -70,6 +140,337 @@ describe("Cryptarena SVM Protocol", () => {
   let globalStatePda: PublicKey;
   let globalStateBump: number;
 
+  // Helpers shared by the arena tests
+  const FEEDS = Object.values(PYTH_FEEDS); // Indexed by asset
+  const ARENA_TYPES = [{ bullish: {} }, { bearish: {} }];
+  type ArenaType = (typeof ARENA_TYPES)[number];
+
+  const pythSolanaReceiver = new PythSolanaReceiver({
+    connection: provider.connection,
+    wallet: provider.wallet as anchor.Wallet,
+  });
+
+  const pda = (...seeds: Buffer[]) => PublicKey.findProgramAddressSync(seeds, program.programId)[0];
+  const ata = async (owner: PublicKey, mint: PublicKey) =>
+    (await getOrCreateAssociatedTokenAccount(provider.connection, admin, mint, owner)).address;
+
+  // Token units worth `usd` dollars at `price`: the program values entries at amount / 10^decimals * price * 10^expo
+  const amountForUsd = (usd: number, { price, expo }: { price: bigint; expo: number }, decimals = 6) =>
+    (BigInt(usd) * 10n ** BigInt(decimals - expo)) / price;
+
+  // Post fresh price updates and run `instructions` against them in one batch
+  const withPriceUpdates = async (
+    priceUpdates: string[],
+    instructions: (priceUpdate: (asset: number) => PublicKey) => Promise<anchor.web3.TransactionInstruction[]>,
+    signers: Keypair[] = []
+  ) => {
+    const builder = pythSolanaReceiver.newTransactionBuilder({ closeUpdateAccounts: true });
+    await builder.addPostPriceUpdates(priceUpdates);
+    await builder.addPriceConsumerInstructions(async (getPriceUpdateAccount) =>
+      (await instructions((asset) => getPriceUpdateAccount("0x" + FEEDS[asset]))).map((instruction) => ({
+        instruction,
+        signers,
+      }))
+    );
+    return pythSolanaReceiver.provider.sendAll(
+      await builder.buildVersionedTransactions({ computeUnitPriceMicroLamports: 50_000 })
+    );
+  };
+
+  // Events the program emitted through `emit_cpi!` in the given transactions
+  const cpiEvents = async (signatures: string | string[]) => {
+    const events = [];
+    for (const signature of [signatures].flat()) {
+      await provider.connection.confirmTransaction(signature, "confirmed");
+      const tx = await provider.connection.getTransaction(signature, {
+        commitment: "confirmed",
+        maxSupportedTransactionVersion: 0,
+      });
+      const keys = tx.transaction.message.getAccountKeys({ accountKeysFromLookups: tx.meta.loadedAddresses });
+      for (const { instructions } of tx.meta.innerInstructions ?? []) {
+        for (const instruction of instructions) {
+          if (!keys.get(instruction.programIdIndex).equals(program.programId)) continue;
+          // Skip the 8-byte event instruction tag to reach the event's own discriminator and data
+          const data = Buffer.from(anchor.utils.bytes.bs58.decode(instruction.data)).subarray(8);
+          const event = program.coder.events.decode(data.toString("base64"));
+          if (event) events.push(event);
+        }
+      }
+    }
+    return events;
+  };
+
+  const cpiEvent = async (signatures: string | string[], name: string) => {
+    const event = (await cpiEvents(signatures)).find((candidate) => candidate.name === name);
+    expect(event, `${name} event`).to.exist;
+    return event.data;
+  };
+
+  // Lamports for the accounts each keypair pays rent on
+  const fund = (keypairs: Keypair[]) =>
+    provider.sendAndConfirm(
+      new Transaction().add(
+        ...keypairs.map((keypair) =>
+          SystemProgram.transfer({
+            fromPubkey: admin.publicKey,
+            toPubkey: keypair.publicKey,
+            lamports: 0.05 * LAMPORTS_PER_SOL,
+          })
+        )
+      )
+    );
+
+  // Apply or discard the pending settings; changes queued on a stake tier or registry entry need its account
+  type SettingsAccounts = { stakeTier?: PublicKey; assetConfig?: PublicKey };
+  const settingsCall =
+    (method: "executeSettings" | "cancelSettings") =>
+    ({ stakeTier, assetConfig }: SettingsAccounts = {}, signer = admin) =>
+      program.methods[method]()
+        .accountsPartial({
+          globalState: globalStatePda,
+          stakeTier: stakeTier ?? null,
+          assetConfig: assetConfig ?? null,
+          admin: signer.publicKey,
+        })
+        .signers([signer])
+        .rpc();
+  const executeSettings = settingsCall("executeSettings");
+  const cancelSettings = settingsCall("cancelSettings");
+
+  // Treasury fee rates; unused override slots have a zero player count
+  type FeeSchedule = {
+    baseFeeBps: BN;
+    playerCountFees: { playerCount: number; feeBps: BN }[];
+    maxFeeBps: BN;
+    promoStart: BN;
+    promoEnd: BN;
+  };
+
+  type Settings = {
+    arenaDuration?: BN;
+    lobbyTimeout?: BN;
+    crankBountyBps?: BN;
+    settingsTimelock?: BN;
+    referralShareBps?: BN;
+    feeSchedule?: FeeSchedule;
+    oracleGracePeriod?: BN;
+  };
+  const queueSettings = (settings: Settings) =>
+    program.methods
+      .updateSettings(
+        settings.arenaDuration ?? null,
+        null,
+        null,
+        settings.lobbyTimeout ?? null,
+        settings.crankBountyBps ?? null,
+        settings.settingsTimelock ?? null,
+        settings.referralShareBps ?? null,
+        settings.feeSchedule ?? null,
+        settings.oracleGracePeriod ?? null
+      )
+      .accounts({
+        globalState: globalStatePda,
+        admin: admin.publicKey,
+      })
+      .signers([admin])
+      .rpc();
+
+  // Queue and apply a settings change; the tests run with the settings timelock at 0
+  const updateSettings = async (settings: Settings) => {
+    await queueSettings(settings);
+    await executeSettings();
+  };
+
+  const assetConfigPda = (asset: number) => pda(Buffer.from("asset_config"), Buffer.from([asset]));
+
+  // List `asset` under `listing.mint`; unset fields take the asset's Pyth feed and the test defaults
+  type AssetListing = {
+    mint: PublicKey;
+    feedId?: number[];
+    maxAge?: BN;
+    maxConfidenceBps?: number;
+    enabled?: boolean;
+  };
+  const queueAssetConfig = (asset: number, listing: AssetListing, signer = admin) =>
+    program.methods
+      .setAssetConfig(
+        asset,
+        listing.feedId ?? Array.from(Buffer.from(FEEDS[asset] ?? "", "hex")),
+        listing.maxAge ?? new BN(MAX_PRICE_AGE),
+        listing.maxConfidenceBps ?? DEFAULT_MAX_CONFIDENCE_BPS,
+        listing.enabled ?? true
+      )
+      .accountsPartial({
+        globalState: globalStatePda,
+        assetConfig: assetConfigPda(asset),
+        mint: listing.mint,
+        admin: signer.publicKey,
+      })
+      .signers([signer])
+      .rpc();
+
+  // Queue a listing change and apply it; the tests run with the settings timelock at 0
+  const setAssetConfig = async (asset: number, listing: AssetListing, signer = admin) => {
+    await queueAssetConfig(asset, listing, signer);
+    await executeSettings({ assetConfig: assetConfigPda(asset) });
+  };
+
+  // Put back a listing a test replaced, or delist the test mint if the asset was not listed before
+  const restoreAssetConfig = (asset: number, previous: AssetListing | null, testMint: PublicKey) =>
+    setAssetConfig(asset, previous ?? { mint: testMint, enabled: false });
+
+  const stakeTierPda = (tierId: number) => pda(Buffer.from("stake_tier"), Buffer.from([tierId]));
+
+  // Create a tier or queue an update to it; its lobbies and arena ids carry over an update
+  const queueStakeTier = (tierId: number, minEntryUsd: number, maxEntryUsd: number, enabled = true, signer = admin) =>
+    program.methods
+      .setStakeTier(tierId, new BN(minEntryUsd), new BN(maxEntryUsd), enabled)
+      .accountsPartial({
+        globalState: globalStatePda,
+        stakeTier: stakeTierPda(tierId),
+        admin: signer.publicKey,
+      })
+      .signers([signer])
+      .rpc();
+
+  const setStakeTier = async (tierId: number, minEntryUsd: number, maxEntryUsd: number, enabled = true, signer = admin) => {
+    await queueStakeTier(tierId, minEntryUsd, maxEntryUsd, enabled, signer);
+    await executeSettings({ stakeTier: stakeTierPda(tierId) });
+  };
+
+  const playerProfilePda = (player: PublicKey) => pda(Buffer.from("player_profile"), player.toBuffer());
+  const fetchProfile = (player: PublicKey) => program.account.playerProfile.fetch(playerProfilePda(player));
+
+  const arenaPdaFor = (tierId: number, arenaId: BN) =>
+    pda(Buffer.from("arena"), Buffer.from([tierId]), arenaId.toArrayLike(Buffer, "le", 8));
+
+  // Custom arenas are keyed by their creator and a nonce the creator picks
+  type CustomArenaKey = { creator: PublicKey; nonce: BN };
+  const customArenaPda = ({ creator, nonce }: CustomArenaKey) =>
+    pda(Buffer.from("arena"), creator.toBuffer(), nonce.toArrayLike(Buffer, "le", 8));
+
+  // Nonces start from the clock so reruns never collide with arenas left on chain
+  let lastNonce = Date.now();
+  const customArenaKey = (creator = admin.publicKey): CustomArenaKey => ({ creator, nonce: new BN(lastNonce++) });
+
+  // Private arenas admit allowlisted players or holders of the secret behind `inviteHash`
+  type AccessPolicy = { open: {} } | { allowlist: {} } | { inviteCode: {} };
+  const inviteHashFor = (secret: Buffer) => Array.from(createHash("sha256").update(secret).digest());
+
+  const createArena = (
+    { creator, nonce }: CustomArenaKey,
+    tierId: number,
+    arenaType: ArenaType,
+    duration: number,
+    maxPlayers: number,
+    creatorFeeBps: number,
+    accessPolicy: AccessPolicy = { open: {} },
+    inviteHash: number[] = new Array(32).fill(0),
+    signer = admin
+  ) =>
+    program.methods
+      .createArena(
+        nonce,
+        tierId,
+        arenaType,
+        new BN(duration),
+        maxPlayers,
+        new BN(creatorFeeBps),
+        accessPolicy,
+        inviteHash
+      )
+      .accountsPartial({
+        globalState: globalStatePda,
+        stakeTier: stakeTierPda(tierId),
+        arena: customArenaPda({ creator, nonce }),
+        creator: signer.publicKey,
+      })
+      .signers([signer])
+      .rpc();
+
+  // Lobbies are shared, so an arena is only predictable when its lobby is empty or custom
+  type Lobby = { tierId: number; arenaType: ArenaType; arenaPda: PublicKey; custom?: CustomArenaKey };
+  const freeLobby = async (tierId: number): Promise<Lobby | null> => {
+    const stakeTier = await program.account.stakeTier.fetch(stakeTierPda(tierId));
+    const freeType = stakeTier.waitingArenas.findIndex((waiting) => waiting === null);
+    if (freeType < 0) {
+      return null;
+    }
+    return {
+      tierId,
+      arenaType: ARENA_TYPES[freeType],
+      arenaPda: arenaPdaFor(tierId, stakeTier.currentArenaId),
+    };
+  };
+
+  // (AssetConfig, PriceUpdateV2) pairs passed as remaining accounts
+  const feedPairs = (assets: number[], priceUpdate: (asset: number) => PublicKey) =>
+    assets.flatMap((asset) => [
+      { pubkey: assetConfigPda(asset), isWritable: false, isSigner: false },
+      { pubkey: priceUpdate(asset), isWritable: false, isSigner: false },
+    ]);
+
+  const arenaAllowlistPda = (arena: PublicKey) => pda(Buffer.from("arena_allowlist"), arena.toBuffer());
+  const userVaultPda = (owner: PublicKey, mint: PublicKey) =>
+    pda(Buffer.from("user_vault"), owner.toBuffer(), mint.toBuffer());
+  const protocolVaultPda = (mint: PublicKey) => pda(Buffer.from("protocol_vault"), mint.toBuffer());
+
+  type EntryOptions = {
+    allowlist?: boolean; // Pass the arena's allowlist account
+    inviteSecret?: Buffer;
+    fromVault?: boolean; // Pay from the user vault balance instead of the wallet
+    omitFunding?: boolean; // Leave out the account the entry is paid from
+    referrer?: PublicKey; // Only bound on the wallet's first entry
+    publishTime?: number; // Price from the updates published at this time instead of the latest
+  };
+
+  // Enter `lobby`, depositing `mint` and pricing `asset` from a freshly posted update
+  const enterLobby = async (
+    { tierId, arenaType, arenaPda: arena, custom }: Lobby,
+    entrant: Keypair,
+    asset: number,
+    amount: BN,
+    mint: PublicKey,
+    startAssets: number[] = [], // Other represented assets, priced by the entrant that fills the arena
+    options: EntryOptions = {}
+  ) => {
+    const { allowlist, inviteSecret, fromVault, omitFunding, referrer, publishTime } = options;
+    const funding = fromVault
+      ? {
+          playerTokenAccount: null,
+          userVault: omitFunding ? null : userVaultPda(entrant.publicKey, mint),
+          protocolVault: protocolVaultPda(mint),
+        }
+      : {
+          playerTokenAccount: omitFunding ? null : await ata(entrant.publicKey, mint),
+          userVault: null,
+          protocolVault: null,
+        };
+    const enter = fromVault ? program.methods.enterArenaFromVault : program.methods.enterArena;
+    return withPriceUpdates(
+      await fetchPriceUpdates([...new Set([asset, ...startAssets])].map((feedAsset) => FEEDS[feedAsset]), publishTime),
+      async (priceUpdate) => [
+        await enter(asset, amount, arenaType, tierId, custom ?? null, inviteSecret ?? null, referrer ?? null)
+          .accountsPartial({
+            globalState: globalStatePda,
+            stakeTier: stakeTierPda(tierId),
+            arena,
+            playerEntry: pda(Buffer.from("player_entry"), arena.toBuffer(), entrant.publicKey.toBuffer()),
+            player: entrant.publicKey,
+            assetConfig: assetConfigPda(asset),
+            mint,
+            ...funding,
+            arenaVault: pda(Buffer.from("arena_vault"), arena.toBuffer(), mint.toBuffer()),
+            arenaAllowlist: allowlist ? arenaAllowlistPda(arena) : null,
+            priceUpdate: priceUpdate(asset),
+          })
+          .remainingAccounts(feedPairs(startAssets, priceUpdate))
+          .instruction(),
+      ],
+      [entrant]
+    );
+  };
+
   before(async () => {
     // Derive PDAs
     [globalStatePda, globalStateBump] = PublicKey.findProgramAddressSync(
@@ -100,8 +501,6 @@ describe("Cryptarena SVM Protocol", () => {
         expect(globalState.admin.toString()).to.equal(admin.publicKey.toString());
         expect(globalState.treasury.toString()).to.equal(treasury.publicKey.toString());
         expect(globalState.arenaDuration.toNumber()).to.equal(DEFAULT_ARENA_DURATION);
-        expect(globalState.currentArenaId.toNumber()).to.equal(0);
-        expect(globalState.waitingArena).to.be.null;
         expect(globalState.isPaused).to.be.false;
         
         console.log("✓ Protocol initialized successfully");
@@ -120,13 +519,14 @@ describe("Cryptarena SVM Protocol", () => {
         const newDuration = 1200; // 20 minutes
 
         await program.methods
-          .updateSettings(new BN(newDuration), null, null)
+          .updateSettings(new BN(newDuration), null, null, null, null, null, null, null, null)
           .accounts({
             globalState: globalStatePda,
             admin: admin.publicKey,
           })
           .signers([admin])
           .rpc();
+        await executeSettings();
 
         const globalState = await program.account.globalState.fetch(globalStatePda);
         expect(globalState.arenaDuration.toNumber()).to.equal(newDuration);
@@ -141,26 +541,28 @@ describe("Cryptarena SVM Protocol", () => {
       try {
         // Pause
         await program.methods
-          .updateSettings(null, null, true)
+          .updateSettings(null, null, true, null, null, null, null, null, null)
           .accounts({
             globalState: globalStatePda,
             admin: admin.publicKey,
           })
           .signers([admin])
           .rpc();
+        await executeSettings();
 
         let globalState = await program.account.globalState.fetch(globalStatePda);
         expect(globalState.isPaused).to.be.true;
 
         // Unpause
         await program.methods
-          .updateSettings(null, null, false)
+          .updateSettings(null, null, false, null, null, null, null, null, null)
           .accounts({
             globalState: globalStatePda,
             admin: admin.publicKey,
           })
           .signers([admin])
           .rpc();
+        await executeSettings();
 
         globalState = await program.account.globalState.fetch(globalStatePda);
         expect(globalState.isPaused).to.be.false;
@@ -174,7 +576,7 @@ describe("Cryptarena SVM Protocol", () => {
     it("should reject settings update from non-admin", async () => {
       try {
         await program.methods
-          .updateSettings(new BN(100), null, null)
+          .updateSettings(new BN(100), null, null, null, null, null, null, null, null)
           .accounts({
             globalState: globalStatePda,
             admin: player1.publicKey,
@@ -191,12 +593,109 @@ describe("Cryptarena SVM Protocol", () => {
     });
   });
 
+  describe("Administration", () => {
+    const nominee = Keypair.generate();
+    const TEST_TIMELOCK = 5;
+
+    const proposeAdmin = (newAdmin: PublicKey, signer: Keypair) =>
+      program.methods
+        .proposeAdmin(newAdmin)
+        .accounts({ globalState: globalStatePda, admin: signer.publicKey })
+        .signers([signer])
+        .rpc();
+    const acceptAdmin = (signer: Keypair) =>
+      program.methods
+        .acceptAdmin()
+        .accounts({ globalState: globalStatePda, newAdmin: signer.publicKey })
+        .signers([signer])
+        .rpc();
+
+    it("should hold a queued settings change until the timelock elapses", async () => {
+      await expectError(cancelSettings(), "NoPendingSettings");
+      await expectError(executeSettings(), "NoPendingSettings");
+      await expectError(queueSettings({ settingsTimelock: new BN(-1) }), "InvalidTimelock");
+
+      // With no timelock yet, raising it applies right away
+      await updateSettings({ settingsTimelock: new BN(TEST_TIMELOCK) });
+      let globalState = await program.account.globalState.fetch(globalStatePda);
+      expect(globalState.settingsTimelock.toNumber()).to.equal(TEST_TIMELOCK);
+      const { lobbyTimeout } = globalState;
+
+      // A queued change waits out the timelock and can be discarded meanwhile
+      await queueSettings({ lobbyTimeout: lobbyTimeout.addn(1) });
+      globalState = await program.account.globalState.fetch(globalStatePda);
+      expect(globalState.pendingSettings.lobbyTimeout.value.toString()).to.equal(lobbyTimeout.addn(1).toString());
+      await expectError(executeSettings(), "TimelockNotElapsed");
+      await cancelSettings();
+      globalState = await program.account.globalState.fetch(globalStatePda);
+      expect(globalState.pendingSettings).to.be.null;
+      expect(globalState.lobbyTimeout.toString()).to.equal(lobbyTimeout.toString());
+
+      // Each field keeps its own timelock: a later change neither restarts nor waits for an earlier one
+      await queueSettings({ lobbyTimeout: lobbyTimeout.addn(1) });
+      const lobbyTimeoutAt = (await program.account.globalState.fetch(globalStatePda)).pendingSettings.lobbyTimeout
+        .executableAt;
+      await sleep(3000);
+      await queueSettings({ arenaDuration: globalState.arenaDuration });
+      let { pendingSettings } = await program.account.globalState.fetch(globalStatePda);
+      expect(pendingSettings.lobbyTimeout.executableAt.toString()).to.equal(lobbyTimeoutAt.toString());
+      expect(pendingSettings.arenaDuration.executableAt.toNumber()).to.be.greaterThan(lobbyTimeoutAt.toNumber());
+      await sleep((lobbyTimeoutAt.toNumber() + 2) * 1000 - Date.now());
+      await executeSettings();
+      globalState = await program.account.globalState.fetch(globalStatePda);
+      expect(globalState.lobbyTimeout.toString()).to.equal(lobbyTimeout.addn(1).toString());
+      expect(globalState.pendingSettings.lobbyTimeout).to.be.null;
+      expect(globalState.pendingSettings.arenaDuration).to.not.be.null;
+      await expectError(executeSettings(), "TimelockNotElapsed");
+      await cancelSettings();
+
+      // Lowering the timelock back to 0 is itself held for the current timelock
+      await queueSettings({ settingsTimelock: new BN(0), lobbyTimeout });
+      await expectError(executeSettings(), "TimelockNotElapsed");
+      ({ pendingSettings } = await program.account.globalState.fetch(globalStatePda));
+      await sleep((pendingSettings.settingsTimelock.executableAt.toNumber() + 2) * 1000 - Date.now());
+      await executeSettings();
+      globalState = await program.account.globalState.fetch(globalStatePda);
+      expect(globalState.settingsTimelock.toNumber()).to.equal(0);
+      expect(globalState.lobbyTimeout.toString()).to.equal(lobbyTimeout.toString());
+      expect(globalState.pendingSettings).to.be.null;
+
+      console.log("✓ Settings changes held for their own timelocks");
+    });
+
+    it("should hand the admin role over only once the nominee accepts", async () => {
+      await expectError(proposeAdmin(nominee.publicKey, nominee), "ConstraintHasOne");
+      await expectError(acceptAdmin(nominee), "Unauthorized");
+
+      await proposeAdmin(nominee.publicKey, admin);
+      let globalState = await program.account.globalState.fetch(globalStatePda);
+      expect(globalState.admin.toBase58()).to.equal(admin.publicKey.toBase58());
+      expect(globalState.pendingAdmin.toBase58()).to.equal(nominee.publicKey.toBase58());
+
+      await acceptAdmin(nominee);
+      globalState = await program.account.globalState.fetch(globalStatePda);
+      expect(globalState.admin.toBase58()).to.equal(nominee.publicKey.toBase58());
+      expect(globalState.pendingAdmin).to.be.null;
+      await expectError(executeSettings({}, admin), "ConstraintHasOne");
+
+      // Hand the role back for the remaining tests
+      await proposeAdmin(admin.publicKey, nominee);
+      await acceptAdmin(admin);
+      expect((await program.account.globalState.fetch(globalStatePda)).admin.toBase58()).to.equal(
+        admin.publicKey.toBase58()
+      );
+      console.log("✓ Admin role handed over in two steps");
+    });
+  });
+
   describe("User Vault", () => {
     let userVaultPda: PublicKey;
+    let vaultMint: PublicKey;
 
     before(async () => {
+      vaultMint = await createMint(provider.connection, admin, admin.publicKey, null, 6);
       [userVaultPda] = PublicKey.findProgramAddressSync(
-        [Buffer.from("user_vault"), player1.publicKey.toBuffer()],
+        [Buffer.from("user_vault"), player1.publicKey.toBuffer(), vaultMint.toBuffer()],
         program.programId
       );
     });
@@ -207,6 +706,7 @@ describe("Cryptarena SVM Protocol", () => {
           .initUserVault()
           .accounts({
             userVault: userVaultPda,
+            mint: vaultMint,
             user: player1.publicKey,
             systemProgram: SystemProgram.programId,
           })
@@ -216,6 +716,7 @@ describe("Cryptarena SVM Protocol", () => {
         const userVault = await program.account.userVault.fetch(userVaultPda);
         
         expect(userVault.owner.toString()).to.equal(player1.publicKey.toString());
+        expect(userVault.mint.toString()).to.equal(vaultMint.toString());
         expect(userVault.availableBalance.toNumber()).to.equal(0);
         
         console.log("✓ User vault initialized successfully");
@@ -283,27 +784,1753 @@ describe("Cryptarena SVM Protocol", () => {
   });
 
   describe("Reward Distribution", () => {
-    it("should calculate correct reward splits", () => {
-      const totalPool = 150_000_000; // $150 total
-      const treasuryFee = Math.floor((totalPool * 1000) / 10000); // 10%
-      const winnerPool = totalPool - treasuryFee;
-      
-      expect(treasuryFee).to.equal(15_000_000); // $15 to treasury
-      expect(winnerPool).to.equal(135_000_000); // $135 to winners
-      
-      // Single winner gets full pool
-      const singleWinnerReward = winnerPool;
-      expect(singleWinnerReward).to.equal(135_000_000);
-      
-      // Two winners split proportionally
-      const twoWinnersEachReward = Math.floor(winnerPool / 2);
-      expect(twoWinnersEachReward).to.equal(67_500_000);
-      
-      // Three winners split proportionally
-      const threeWinnersEachReward = Math.floor(winnerPool / 3);
-      expect(threeWinnersEachReward).to.equal(45_000_000);
-      
-      console.log("✓ Reward distribution calculations are correct");
+    // Ten entrants fill a custom arena across four assets; the admin creates it and earns its creator fee
+    const TEST_ARENA_DURATION = 60;
+    // Schedule in force while the arena is created: a full-arena override under a capped base rate
+    const FEE_SCHEDULE: FeeSchedule = {
+      baseFeeBps: new BN(1000),
+      playerCountFees: [
+        { playerCount: MAX_PLAYERS_PER_ARENA, feeBps: new BN(500) },
+        ...Array.from({ length: 3 }, () => ({ playerCount: 0, feeBps: new BN(0) })),
+      ],
+      maxFeeBps: new BN(800),
+      promoStart: new BN(0),
+      promoEnd: new BN(0),
+    };
+    const TREASURY_FEE_BPS = 500; // FEE_SCHEDULE's full-arena override
+    const CREATOR_FEE_BPS = 200;
+    const STAKES_USD = [11, 13, 17]; // Cycled across each asset's entrants, inside the $10-$20 bounds
+    const assets = [ASSET_JUP, ASSET_PYTH, ASSET_RAY, ASSET_W];
+    const ASSET_SLOTS = [3, 3, 2, 2]; // Entrants per asset, in `assets` order
+
+    const entrants = Array.from({ length: MAX_PLAYERS_PER_ARENA }, () => Keypair.generate());
+    const keeper = Keypair.generate(); // Settles the arena and earns the crank bounty
+    const referrer = Keypair.generate(); // Referred the entrant of `entries[REFERRED]`
+    const REFERRED = 1;
+    const entries: [Keypair, number, BN][] = [];
+    const mints: { [asset: number]: PublicKey } = {};
+    const custom = customArenaKey();
+    const lobby: Lobby = {
+      tierId: TEST_TIER_ID,
+      arenaType: ARENA_TYPES[0],
+      arenaPda: customArenaPda(custom),
+      custom,
+    };
+    const arenaPda = lobby.arenaPda;
+    const previousListings: { [asset: number]: AssetListing | null } = {};
+    let previousFeeSchedule: FeeSchedule;
+    let previousGracePeriod: BN | undefined;
+
+    // Helpers act on the shared arena unless given another one
+    const arenaVaultPda = (asset: number, mint = mints[asset], arena = arenaPda) =>
+      pda(Buffer.from("arena_vault"), arena.toBuffer(), mint.toBuffer());
+    const playerEntryPda = (player: PublicKey, arena = arenaPda) =>
+      pda(Buffer.from("player_entry"), arena.toBuffer(), player.toBuffer());
+    const tokenAccount = (owner: PublicKey, asset: number, mint = mints[asset]) => ata(owner, mint);
+    const tokenBalance = async (owner: PublicKey, asset: number) =>
+      new BN((await getAccount(provider.connection, await tokenAccount(owner, asset))).amount.toString());
+
+    // Entries deposit `mint`, which defaults to the asset's own test mint
+    const enterArena = (
+      entrant: Keypair,
+      asset: number,
+      amount: BN,
+      mint = mints[asset],
+      startAssets: number[] = [],
+      options: EntryOptions = {}
+    ) => enterLobby(lobby, entrant, asset, amount, mint, startAssets, options);
+
+    // Record end prices for `endAssets` from the updates Hermes holds at `publishTime`
+    const recordEndPrices = async (endAssets: number[], publishTime: number) =>
+      withPriceUpdates(await fetchPriceUpdates(endAssets.map((asset) => FEEDS[asset]), publishTime), async (priceUpdate) =>
+        Promise.all(
+          endAssets.map((asset) =>
+            program.methods
+              .updateEndPrices(asset)
+              .accountsPartial({
+                assetConfig: assetConfigPda(asset),
+                arena: arenaPda,
+                priceUpdate: priceUpdate(asset),
+                caller: admin.publicKey,
+              })
+              .instruction()
+          )
+        )
+      );
+
+    // Record end prices for `settleAssets` and resolve the arena in one transaction, cranked by the keeper.
+    // The players' profiles follow the price pairs, in `arena.players` order unless `players` is given.
+    const settleArena = async (settleAssets: number[], publishTime?: number, arena = arenaPda, players?: PublicKey[]) => {
+      if (!players) {
+        const { players: slots, playerCount } = await program.account.arena.fetch(arena);
+        players = slots.slice(0, playerCount);
+      }
+      const profiles = players.map((player) => ({
+        pubkey: playerProfilePda(player),
+        isSigner: false,
+        isWritable: true,
+      }));
+      return withPriceUpdates(
+        await fetchPriceUpdates(settleAssets.map((asset) => FEEDS[asset]), publishTime),
+        async (priceUpdate) => [
+          await program.methods
+            .settleArena()
+            .accountsPartial({
+              globalState: globalStatePda,
+              arena,
+              caller: keeper.publicKey,
+            })
+            .remainingAccounts([...feedPairs(settleAssets, priceUpdate), ...profiles])
+            .instruction(),
+        ],
+        [keeper]
+      );
+    };
+
+    const claimCrankBounty = async (claimant: Keypair, asset: number) =>
+      program.methods
+        .claimCrankBounty(asset)
+        .accountsPartial({
+          arena: arenaPda,
+          arenaVault: arenaVaultPda(asset),
+          keeperTokenAccount: await tokenAccount(claimant.publicKey, asset),
+          keeper: claimant.publicKey,
+        })
+        .signers([claimant])
+        .rpc();
+
+    const claimCreatorFee = async (claimant: Keypair, asset: number) =>
+      program.methods
+        .claimCreatorFee(asset)
+        .accountsPartial({
+          arena: arenaPda,
+          arenaVault: arenaVaultPda(asset),
+          creatorTokenAccount: await tokenAccount(claimant.publicKey, asset),
+          creator: claimant.publicKey,
+        })
+        .signers([claimant])
+        .rpc();
+
+    const treasuryVaultPda = (asset: number) => pda(Buffer.from("treasury_vault"), mints[asset].toBuffer());
+
+    const collectArenaFee = (asset: number) =>
+      program.methods
+        .collectArenaFee(asset)
+        .accountsPartial({
+          globalState: globalStatePda,
+          arena: arenaPda,
+          mint: mints[asset],
+          arenaVault: arenaVaultPda(asset),
+          treasuryVault: treasuryVaultPda(asset),
+          caller: admin.publicKey,
+        })
+        .signers([admin])
+        .rpc();
+
+    const claimReward = async (player: Keypair, asset: number, arena = arenaPda) =>
+      program.methods
+        .claimReward(asset)
+        .accountsPartial({
+          globalState: globalStatePda,
+          arena,
+          playerEntry: playerEntryPda(player.publicKey, arena),
+          arenaVault: arenaVaultPda(asset, mints[asset], arena),
+          playerTokenAccount: await tokenAccount(player.publicKey, asset),
+          player: player.publicKey,
+        })
+        .signers([player])
+        .rpc();
+
+    // Closing is permissionless: the admin cranks it, rent goes back to whoever paid it
+    const closePlayerEntry = (player: PublicKey, arena = arenaPda) =>
+      program.methods
+        .closePlayerEntry()
+        .accountsPartial({
+          arena,
+          playerEntry: playerEntryPda(player, arena),
+          player,
+          caller: admin.publicKey,
+        })
+        .signers([admin])
+        .rpc();
+
+    const closeArenaVault = (asset: number, rentReceiver: PublicKey, mint = mints[asset]) =>
+      program.methods
+        .closeArenaVault(asset)
+        .accountsPartial({
+          globalState: globalStatePda,
+          arena: arenaPda,
+          mint,
+          arenaVault: arenaVaultPda(asset, mint),
+          treasuryVault: treasuryVaultPda(asset),
+          rentReceiver,
+          caller: admin.publicKey,
+        })
+        .signers([admin])
+        .rpc();
+
+    const closeArena = (rentPayer: PublicKey) =>
+      program.methods
+        .closeArena()
+        .accountsPartial({
+          arena: arenaPda,
+          arenaAllowlist: null,
+          rentPayer,
+          caller: admin.publicKey,
+        })
+        .signers([admin])
+        .rpc();
+
+    // A season covering this arena's end, paying its top two players in the first asset's mint
+    const seasonId = new BN(Date.now());
+    const SEASON_PRIZE = new BN(1_000_000);
+    const SEASON_PAYOUT_BPS = [6000, 4000];
+    const seasonPda = pda(Buffer.from("season"), seasonId.toArrayLike(Buffer, "le", 8));
+    const seasonVaultPda = pda(Buffer.from("season_vault"), seasonPda.toBuffer());
+    const seasonScorePda = (player: PublicKey) =>
+      pda(Buffer.from("season_score"), seasonPda.toBuffer(), player.toBuffer());
+
+    const createSeason = (id: BN, startTimestamp: number, endTimestamp: number, payoutBps: number[]) =>
+      program.methods
+        .createSeason(id, new BN(startTimestamp), new BN(endTimestamp), payoutBps)
+        .accountsPartial({
+          globalState: globalStatePda,
+          season: pda(Buffer.from("season"), id.toArrayLike(Buffer, "le", 8)),
+          mint: mints[assets[0]],
+          admin: admin.publicKey,
+        })
+        .signers([admin])
+        .rpc();
+
+    const recordSeasonScore = (player: PublicKey) =>
+      program.methods
+        .recordSeasonScore()
+        .accountsPartial({
+          season: seasonPda,
+          arena: arenaPda,
+          playerEntry: playerEntryPda(player),
+          seasonScore: seasonScorePda(player),
+          caller: admin.publicKey,
+        })
+        .signers([admin])
+        .rpc();
+
+    // Count the scores of `players` into the season leaderboard
+    const rankSeasonScores = (players: PublicKey[]) =>
+      program.methods
+        .rankSeasonScores()
+        .accountsPartial({ season: seasonPda })
+        .remainingAccounts(players.map((player) => ({ pubkey: seasonScorePda(player), isSigner: false, isWritable: true })))
+        .rpc();
+
+    // `paid` holds one token account per paid rank, best first
+    const closeSeason = (paid: PublicKey[]) =>
+      program.methods
+        .closeSeason()
+        .accountsPartial({
+          globalState: globalStatePda,
+          season: seasonPda,
+          mint: mints[assets[0]],
+          seasonVault: seasonVaultPda,
+          treasuryVault: treasuryVaultPda(assets[0]),
+          rentPayer: admin.publicKey,
+          caller: admin.publicKey,
+        })
+        .remainingAccounts(paid.map((pubkey) => ({ pubkey, isSigner: false, isWritable: true })))
+        .signers([admin])
+        .rpc();
+
+    // Permissionless, cranked by the keeper
+    const closeSeasonScore = (player: PublicKey, rentPayer = admin.publicKey) =>
+      program.methods
+        .closeSeasonScore()
+        .accountsPartial({
+          season: seasonPda,
+          seasonScore: seasonScorePda(player),
+          rentPayer,
+          caller: keeper.publicKey,
+        })
+        .signers([keeper])
+        .rpc();
+
+    const forceCloseSeason = (signer: Keypair) =>
+      program.methods
+        .forceCloseSeason()
+        .accountsPartial({
+          globalState: globalStatePda,
+          season: seasonPda,
+          mint: mints[assets[0]],
+          seasonVault: seasonVaultPda,
+          treasuryVault: treasuryVaultPda(assets[0]),
+          rentPayer: admin.publicKey,
+          admin: signer.publicKey,
+        })
+        .signers([signer])
+        .rpc();
+
+    before(async () => {
+      await setStakeTier(TEST_TIER_ID, MIN_ENTRY_USD, MAX_ENTRY_USD);
+
+      // The arena snapshots the schedule at creation; the shared one is restored afterwards
+      previousFeeSchedule = (await program.account.globalState.fetch(globalStatePda)).feeSchedule;
+      await expectError(
+        queueSettings({ feeSchedule: { ...FEE_SCHEDULE, maxFeeBps: new BN(10001) } }),
+        "InvalidFeeSchedule"
+      );
+      await expectError(
+        queueSettings({ feeSchedule: { ...FEE_SCHEDULE, promoStart: new BN(2), promoEnd: new BN(1) } }),
+        "InvalidFeeSchedule"
+      );
+      await updateSettings({ feeSchedule: FEE_SCHEDULE });
+
+      const prices = await fetchLatestPrices(assets.map((asset) => FEEDS[asset]));
+      for (const [rank, asset] of assets.entries()) {
+        // Each asset is listed under a fresh mint; the previous listing is restored afterwards
+        mints[asset] = await createMint(provider.connection, admin, admin.publicKey, null, 6);
+        previousListings[asset] = await program.account.assetConfig.fetchNullable(assetConfigPda(asset));
+        await setAssetConfig(asset, { mint: mints[asset] });
+        for (let slot = 0; slot < ASSET_SLOTS[rank]; slot++) {
+          const amount = amountForUsd(STAKES_USD[slot], prices[FEEDS[asset]]);
+          entries.push([entrants[entries.length], asset, new BN(amount.toString())]);
+        }
+      }
+
+      await fund(entrants);
+      for (const [entrant, asset, amount] of entries) {
+        await mintTo(
+          provider.connection,
+          admin,
+          mints[asset],
+          await tokenAccount(entrant.publicKey, asset),
+          admin,
+          BigInt(amount.toString())
+        );
+      }
+    });
+
+    after(async () => {
+      for (const [asset, previous] of Object.entries(previousListings)) {
+        await restoreAssetConfig(Number(asset), previous, mints[asset]);
+      }
+      if (previousFeeSchedule) {
+        await updateSettings({ feeSchedule: previousFeeSchedule, oracleGracePeriod: previousGracePeriod });
+      }
+    });
+
+    it("should only open custom arenas within the configured bounds", async () => {
+      const { maxCreatorFeeBps, crankBountyBps, minCustomDuration, maxCustomDuration } =
+        await program.account.globalState.fetch(globalStatePda);
+      const open = (duration: number, maxPlayers: number, creatorFeeBps: number) =>
+        createArena(custom, TEST_TIER_ID, lobby.arenaType, duration, maxPlayers, creatorFeeBps);
+
+      await expectError(open(minCustomDuration.toNumber() - 1, MAX_PLAYERS_PER_ARENA, CREATOR_FEE_BPS), "InvalidDuration");
+      await expectError(open(maxCustomDuration.toNumber() + 1, MAX_PLAYERS_PER_ARENA, CREATOR_FEE_BPS), "InvalidDuration");
+      await expectError(open(TEST_ARENA_DURATION, 1, CREATOR_FEE_BPS), "InvalidPlayerCapacity");
+      await expectError(open(TEST_ARENA_DURATION, MAX_PLAYERS_PER_ARENA + 1, CREATOR_FEE_BPS), "InvalidPlayerCapacity");
+      await expectError(
+        open(TEST_ARENA_DURATION, MAX_PLAYERS_PER_ARENA, maxCreatorFeeBps.toNumber() + 1),
+        "CreatorFeeTooHigh"
+      );
+
+      // The bounds themselves must be ordered, and fees taken from the pool can never exceed it
+      const setBounds = (minDuration: number, maxDuration: number, creatorFeeBps: number) =>
+        program.methods
+          .setCustomArenaBounds(new BN(minDuration), new BN(maxDuration), new BN(creatorFeeBps))
+          .accountsPartial({ globalState: globalStatePda, admin: admin.publicKey })
+          .signers([admin])
+          .rpc();
+      await expectError(setBounds(0, maxCustomDuration.toNumber(), maxCreatorFeeBps.toNumber()), "InvalidDuration");
+      await expectError(setBounds(120, 60, maxCreatorFeeBps.toNumber()), "InvalidDuration");
+      await expectError(
+        setBounds(
+          minCustomDuration.toNumber(),
+          maxCustomDuration.toNumber(),
+          10000 - FEE_SCHEDULE.maxFeeBps.toNumber() - crankBountyBps.toNumber() + 1
+        ),
+        "CreatorFeeTooHigh"
+      );
+
+      // Entering a custom arena that was never created does not open it
+      const [entrant, asset, amount] = entries[0];
+      await expectError(enterArena(entrant, asset, amount), "ArenaNotWaiting");
+
+      const created = await cpiEvent(
+        await open(TEST_ARENA_DURATION, MAX_PLAYERS_PER_ARENA, CREATOR_FEE_BPS),
+        "ArenaCreated"
+      );
+      const arena = await program.account.arena.fetch(arenaPda);
+      expect(created.arena.toBase58()).to.equal(arenaPda.toBase58());
+      expect(created.creator.toBase58()).to.equal(admin.publicKey.toBase58());
+      expect(created.creatorFeeBps.toNumber()).to.equal(CREATOR_FEE_BPS);
+      // A full arena is charged the schedule's override, not the base rate or its cap
+      expect(created.treasuryFeeBps.toNumber()).to.equal(TREASURY_FEE_BPS);
+      expect(arena.status).to.equal(1); // Waiting
+      expect(arena.creator.toBase58()).to.equal(admin.publicKey.toBase58());
+      expect(arena.tierId).to.equal(TEST_TIER_ID);
+      expect(arena.maxPlayers).to.equal(MAX_PLAYERS_PER_ARENA);
+      expect(arena.duration.toNumber()).to.equal(TEST_ARENA_DURATION);
+      expect(arena.creatorFeeBps.toNumber()).to.equal(CREATOR_FEE_BPS);
+
+      // Entries must match the arena's tier and type
+      await expectError(
+        enterLobby({ ...lobby, arenaType: ARENA_TYPES[1] }, entrant, asset, amount, mints[asset]),
+        "ArenaMismatch"
+      );
+      console.log(`✓ Custom arena ${custom.nonce.toString()} opened within bounds`);
+    });
+
+    it("should hold entries in the lobby until it fills", async () => {
+      const [referred, referredAsset, referredAmount] = entries[REFERRED];
+      await expectError(
+        enterArena(referred, referredAsset, referredAmount, mints[referredAsset], [], { referrer: referred.publicKey }),
+        "SelfReferral"
+      );
+
+      for (const [index, [entrant, asset, amount]] of entries.slice(0, -1).entries()) {
+        await enterArena(entrant, asset, amount, mints[asset], [], index === REFERRED ? { referrer: referrer.publicKey } : {});
+      }
+
+      // The referrer's cut of the entry's treasury fee is fixed when it enters
+      const { referralShareBps } = await program.account.globalState.fetch(globalStatePda);
+      const referralFee = referredAmount.muln(TREASURY_FEE_BPS).divn(10000).mul(referralShareBps).divn(10000);
+      const referredEntry = await program.account.playerEntry.fetch(playerEntryPda(referred.publicKey));
+      expect(referredEntry.referrer.toBase58()).to.equal(referrer.publicKey.toBase58());
+      expect(referredEntry.referralFee.toString()).to.equal(referralFee.toString());
+      expect((await program.account.playerEntry.fetch(playerEntryPda(entries[0][0].publicKey))).referralFee.toNumber()).to.equal(0);
+
+      const arena = await program.account.arena.fetch(arenaPda);
+      expect(arena.assetReferralFees[referredAsset].toString()).to.equal(referralFee.toString());
+      expect(arena.status).to.equal(1); // Waiting
+      expect(arena.playerCount).to.equal(entries.length - 1);
+      expect(arena.openEntries).to.equal(entries.length - 1);
+      await expectError(closePlayerEntry(entries[0][0].publicKey), "ArenaNotFinished");
+      console.log(`✓ ${arena.playerCount} entrants waiting in arena ${arena.id.toNumber()}`);
+    });
+
+    it("should reject a deposit in a mint other than the asset's registered one", async () => {
+      const [entrant, asset, amount] = entries[entries.length - 1];
+      const otherAsset = assets.find((other) => other !== asset);
+      await expectError(enterArena(entrant, asset, amount, mints[otherAsset]), "InvalidMint");
+
+      const arena = await program.account.arena.fetch(arenaPda);
+      expect(arena.playerCount).to.equal(entries.length - 1);
+      console.log("✓ Deposit in another asset's mint rejected");
+    });
+
+    it("should require a start price for every represented asset to fill the lobby", async () => {
+      const [entrant, asset, amount] = entries[entries.length - 1];
+      const otherAssets = assets.filter((other) => other !== asset);
+      const absent = FEEDS.findIndex((_, feedAsset) => !assets.includes(feedAsset));
+
+      await expectError(enterArena(entrant, asset, amount), "MissingStartPrice");
+      await expectError(
+        enterArena(entrant, asset, amount, mints[asset], [...otherAssets, absent]),
+        "AssetNotInArena"
+      );
+      // A second pair for an asset, its own included, would overwrite the first start price
+      await expectError(
+        enterArena(entrant, asset, amount, mints[asset], [...otherAssets, otherAssets[0]]),
+        "DuplicateStartPrice"
+      );
+      await expectError(enterArena(entrant, asset, amount, mints[asset], [asset, ...otherAssets]), "DuplicateStartPrice");
+      // Still fresh enough to price an entry, but too old to open the arena window on
+      const stale = Math.floor(Date.now() / 1000) - MAX_PRICE_AGE / 2;
+      await expectError(
+        enterArena(entrant, asset, amount, mints[asset], otherAssets, { publishTime: stale }),
+        "StartPriceOutsideWindow"
+      );
+
+      const arena = await program.account.arena.fetch(arenaPda);
+      expect(arena.status).to.equal(1); // Waiting
+      expect(arena.playerCount).to.equal(entries.length - 1);
+      console.log("✓ Filling entry without a fresh start price per asset rejected");
+    });
+
+    it("should start the arena when the last entrant fills the lobby", async () => {
+      const [entrant, asset, amount] = entries[entries.length - 1];
+      const signatures = await enterArena(
+        entrant,
+        asset,
+        amount,
+        mints[asset],
+        assets.filter((other) => other !== asset)
+      );
+
+      const arena = await program.account.arena.fetch(arenaPda);
+      const entered = await cpiEvent(signatures, "PlayerEntered");
+      expect(entered.player.toBase58()).to.equal(entrant.publicKey.toBase58());
+      expect(entered.amount.toString()).to.equal(amount.toString());
+      expect(entered.fromVault).to.be.false;
+      expect(entered.playerCount).to.equal(MAX_PLAYERS_PER_ARENA);
+      const started = await cpiEvent(signatures, "ArenaStarted");
+      expect(started.endTimestamp.toString()).to.equal(arena.endTimestamp.toString());
+      expect(started.totalPool.toString()).to.equal(arena.totalPool.toString());
+
+      // Each entrant's profile is created with its first entry
+      const profile = await fetchProfile(entrant.publicKey);
+      expect(profile.player.toBase58()).to.equal(entrant.publicKey.toBase58());
+      expect(profile.arenasPlayed).to.equal(1);
+      expect(profile.totalStaked.toString()).to.equal(entered.usdValue.toString());
+      expect(profile.assetEntries[asset]).to.equal(1);
+      expect(profile.favoriteAsset).to.equal(asset);
+      expect(arena.status).to.equal(2); // Active
+      expect(arena.playerCount).to.equal(MAX_PLAYERS_PER_ARENA);
+      expect(arena.endTimestamp.sub(arena.startTimestamp).toNumber()).to.equal(TEST_ARENA_DURATION);
+      // Every represented asset is priced in the activating transaction, and only those
+      arena.startPrices.forEach((startPrice, feedAsset) =>
+        expect(startPrice.gtn(0), `asset ${feedAsset} start price`).to.equal(assets.includes(feedAsset))
+      );
+      // The creator fee is only carved out once the arena resolves
+      await expectError(claimCreatorFee(admin, asset), "ArenaNotEnded");
+      console.log(`✓ Arena ${arena.id.toNumber()} started`);
+    });
+
+    it("should open a season covering the arena's end and take prize funding", async () => {
+      const arena = await program.account.arena.fetch(arenaPda);
+      const start = arena.startTimestamp.toNumber();
+      const end = arena.endTimestamp.toNumber() + 30;
+
+      // Throwaway ids for the rejected seasons; nothing is created under them
+      await expectError(createSeason(seasonId.addn(1), end, start, SEASON_PAYOUT_BPS), "InvalidSeasonWindow");
+      await expectError(createSeason(seasonId.addn(1), start, end, [6000, 3000]), "InvalidPayoutSplit");
+      await expectError(createSeason(seasonId.addn(1), start, end, [10000, ...new Array(10).fill(0)]), "InvalidPayoutSplit");
+
+      const created = await cpiEvent(await createSeason(seasonId, start, end, SEASON_PAYOUT_BPS), "SeasonCreated");
+      expect(created.payoutBps).to.deep.equal(SEASON_PAYOUT_BPS);
+
+      const funderAccount = await tokenAccount(admin.publicKey, assets[0]);
+      await mintTo(provider.connection, admin, mints[assets[0]], funderAccount, admin, BigInt(SEASON_PRIZE.toString()));
+      const fundSeason = (amount: BN) =>
+        program.methods
+          .fundSeason(amount)
+          .accountsPartial({
+            season: seasonPda,
+            seasonVault: seasonVaultPda,
+            funderTokenAccount: funderAccount,
+            funder: admin.publicKey,
+          })
+          .signers([admin])
+          .rpc();
+      await expectError(fundSeason(new BN(0)), "InvalidDepositAmount");
+      await fundSeason(SEASON_PRIZE);
+
+      const season = await program.account.season.fetch(seasonPda);
+      expect(season.winnerCount).to.equal(SEASON_PAYOUT_BPS.length);
+      expect(season.participants).to.equal(0);
+      expect((await getAccount(provider.connection, seasonVaultPda)).amount.toString()).to.equal(SEASON_PRIZE.toString());
+      console.log(`✓ Season ${seasonId.toString()} opened with a ${SEASON_PRIZE.toString()} prize`);
+    });
+
+    it("should settle the arena from every asset's end price in one transaction", async () => {
+      await expectError(settleArena(assets), "ArenaDurationNotComplete");
+
+      let arena = await program.account.arena.fetch(arenaPda);
+      const waitMs = (arena.endTimestamp.toNumber() + 5) * 1000 - Date.now();
+      if (waitMs > 0) {
+        console.log(`  Waiting ${Math.ceil(waitMs / 1000)}s for arena ${arena.id.toNumber()} to end...`);
+        await sleep(waitMs);
+      }
+
+      // End prices are pinned to their publish time, not to when the crank runs
+      const endTimestamp = arena.endTimestamp.toNumber();
+      const [recorded, ...settled] = assets;
+      await expectError(recordEndPrices([recorded], endTimestamp - 1), "EndPriceOutsideWindow");
+      await expectError(recordEndPrices([recorded], endTimestamp + END_PRICE_WINDOW + 1), "EndPriceOutsideWindow");
+      await recordEndPrices([recorded], endTimestamp);
+      await expectError(recordEndPrices([recorded], endTimestamp + 1), "EndPriceAlreadySet");
+
+      // Settlement may skip an asset already recorded, but not one still missing its end price within
+      // the oracle grace period
+      await expectError(settleArena(settled.slice(0, -1), endTimestamp), "MissingEndPrice");
+
+      // The arena settles at the fees it snapshotted, whatever the global settings say by then
+      const { crankBountyBps } = await program.account.globalState.fetch(globalStatePda);
+      const noOverrides = FEE_SCHEDULE.playerCountFees.map(() => ({ playerCount: 0, feeBps: new BN(0) }));
+      await updateSettings({
+        feeSchedule: { ...FEE_SCHEDULE, playerCountFees: noOverrides },
+        crankBountyBps: crankBountyBps.addn(10),
+      });
+      // Every player's profile must follow the price pairs
+      const { players, playerCount } = await program.account.arena.fetch(arenaPda);
+      await expectError(
+        settleArena(settled, endTimestamp, arenaPda, players.slice(1, playerCount)),
+        "InvalidRemainingAccounts"
+      );
+      const resolved = await cpiEvent(await settleArena(settled, endTimestamp), "ArenaResolved");
+      await updateSettings({ feeSchedule: FEE_SCHEDULE, crankBountyBps });
+
+      arena = await program.account.arena.fetch(arenaPda);
+      expect(resolved.status).to.equal(arena.status);
+      expect(resolved.crankRecipient.toBase58()).to.equal(keeper.publicKey.toBase58());
+      expect(resolved.crankBounty.toString()).to.equal(arena.crankBounty.toString());
+      expect(arena.crankBountyBps.toString()).to.equal(crankBountyBps.toString());
+      expect(arena.crankBounty.toString()).to.equal(arena.totalPool.mul(crankBountyBps).divn(10000).toString());
+      expect(arena.feeSchedule.maxFeeBps.toString()).to.equal(FEE_SCHEDULE.maxFeeBps.toString());
+      expect(arena.feeSchedule.playerCountFees[0].feeBps.toString()).to.equal(TREASURY_FEE_BPS.toString());
+      expect(resolved.treasuryFee.toString()).to.equal(arena.totalPool.muln(TREASURY_FEE_BPS).divn(10000).toString());
+      for (const asset of assets) {
+        expect(arena.endPrices[asset].gtn(0)).to.be.true;
+        const publishTime = arena.endPricePublishTimes[asset].toNumber();
+        expect(publishTime).to.be.within(endTimestamp, endTimestamp + END_PRICE_WINDOW);
+      }
+      // A tie suspends the arena instead of naming a winner
+      expect(arena.status).to.be.oneOf([3, 4]); // Ended, Suspended
+      await expectError(settleArena(settled, endTimestamp), "ArenaNotActive");
+
+      // Resolution records the losses, so a loser never has to close their entry for them to count
+      for (const [entrant, asset] of entries) {
+        const profile = await fetchProfile(entrant.publicKey);
+        const lost = arena.status === 3 && asset !== arena.winningAsset;
+        expect(profile.losses).to.equal(lost ? 1 : 0);
+        if (lost) {
+          expect(profile.currentStreak).to.equal(0);
+          expect(profile.streakTimestamp.toNumber()).to.equal(endTimestamp);
+        }
+      }
+
+      // Winners and refunded players keep their entries until they have been paid
+      const [unpaid] = entries.find(([, asset]) => arena.status === 4 || asset === arena.winningAsset);
+      await expectError(closePlayerEntry(unpaid.publicKey), "EntryNotSettled");
+      console.log(`✓ Arena ${arena.id.toNumber()} resolved, winning asset ${arena.winningAsset}`);
+    });
+
+    it("should hold each asset's deposits in a vault of its own mint", async () => {
+      const arena = await program.account.arena.fetch(arenaPda);
+
+      for (const asset of assets) {
+        const deposited = entries
+          .filter(([, entryAsset]) => entryAsset === asset)
+          .reduce((sum, [, , amount]) => sum.add(amount), new BN(0));
+        const vault = await getAccount(provider.connection, arenaVaultPda(asset));
+        expect(arena.assetMints[asset].toBase58()).to.equal(mints[asset].toBase58());
+        expect(vault.mint.toBase58()).to.equal(mints[asset].toBase58());
+        expect(vault.owner.toBase58()).to.equal(arenaPda.toBase58());
+        expect(vault.amount.toString()).to.equal(deposited.toString());
+        expect(arena.assetAmounts[asset].toString()).to.equal(deposited.toString());
+      }
+
+      console.log(`✓ ${assets.length} assets held in their own mint vaults`);
+    });
+
+    it("should credit every entry of the ended arena to the season once", async function () {
+      const arena = await program.account.arena.fetch(arenaPda);
+      if (arena.status !== 3) {
+        await expectError(recordSeasonScore(entries[0][0].publicKey), "ArenaNotEnded");
+        this.skip();
+      }
+
+      // The arena ended within the season, so only its players may close their entries until credited
+      const loser = entries.find(([, asset]) => asset !== arena.winningAsset);
+      if (loser) {
+        await expectError(closePlayerEntry(loser[0].publicKey), "SeasonScoreNotRecorded");
+      }
+
+      for (const [entrant, asset] of entries) {
+        const recorded = await cpiEvent(await recordSeasonScore(entrant.publicKey), "SeasonScoreRecorded");
+        const score = await program.account.seasonScore.fetch(seasonScorePda(entrant.publicKey));
+        expect(score.points.toString()).to.equal(recorded.points.toString());
+        expect(score.arenasPlayed).to.equal(1);
+        if (asset === arena.winningAsset) {
+          // Participation and win points, plus up to 100 for the margin of victory
+          expect(score.wins).to.equal(1);
+          expect(score.points.toNumber()).to.be.within(110, 210);
+        } else {
+          expect(score.wins).to.equal(0);
+          expect(score.points.toNumber()).to.equal(10);
+        }
+      }
+      await expectError(recordSeasonScore(entries[0][0].publicKey), "AlreadyCredited");
+
+      const season = await program.account.season.fetch(seasonPda);
+      expect(season.participants).to.equal(entries.length);
+      console.log(`✓ ${entries.length} entries credited to season ${seasonId.toString()}`);
+    });
+
+    it("should pay winners pro-rata to their USD stake from every asset vault", async function () {
+      const arena = await program.account.arena.fetch(arenaPda);
+      if (arena.status === 4) {
+        console.log("  Arena tied and was suspended; no winners to pay");
+        this.skip();
+      }
+      expect(arena.status).to.equal(3); // Ended
+
+      const winners = entries.filter(([, asset]) => asset === arena.winningAsset).map(([entrant]) => entrant);
+      const winningUsd = arena.assetUsdTotals[arena.winningAsset];
+
+      for (const asset of assets) {
+        // The treasury fee, crank bounty and creator fee stay in the vault; winners split the rest in the vault's mint
+        const retained = arena.assetTreasuryFees[asset]
+          .add(arena.assetReferralFees[asset])
+          .add(arena.assetCrankBounties[asset])
+          .add(arena.assetCreatorFees[asset]);
+        const distributable = arena.assetAmounts[asset].sub(retained);
+
+        let paid = new BN(0);
+        for (const [index, winner] of winners.entries()) {
+          const entry = await program.account.playerEntry.fetch(playerEntryPda(winner.publicKey));
+          const before = await tokenBalance(winner.publicKey, asset);
+          const claimed = await cpiEvent(await claimReward(winner, asset), "RewardClaimed");
+
+          const received = (await tokenBalance(winner.publicKey, asset)).sub(before);
+          // The last winner to claim from a vault also receives the rounding dust
+          const expected = index === winners.length - 1
+            ? distributable.sub(paid)
+            : distributable.mul(entry.usdValue).div(winningUsd);
+          expect(received.toString()).to.equal(expected.toString());
+          expect(claimed.amount.toString()).to.equal(received.toString());
+          paid = paid.add(received);
+        }
+
+        const vault = await getAccount(provider.connection, arenaVaultPda(asset));
+        expect(paid.toString()).to.equal(distributable.toString());
+        expect(vault.amount.toString()).to.equal(retained.toString());
+      }
+
+      for (const winner of winners) {
+        expect((await program.account.playerEntry.fetch(playerEntryPda(winner.publicKey))).rewardClaimed).to.be.true;
+
+        // The win is recorded once, however many vaults the winner claimed from
+        const profile = await fetchProfile(winner.publicKey);
+        expect(profile.wins).to.equal(1);
+        expect(profile.currentStreak).to.equal(1);
+        expect(profile.bestStreak).to.equal(1);
+        expect(profile.totalWon.gtn(0)).to.be.true;
+      }
+
+      console.log(`✓ ${winners.length} winners of asset ${arena.winningAsset} paid from ${assets.length} vaults`);
+    });
+
+    it("should pay the crank bounty only to the keeper that settled the arena", async function () {
+      const arena = await program.account.arena.fetch(arenaPda);
+      if (arena.status !== 3) {
+        this.skip();
+      }
+      const { crankBountyBps, maxCreatorFeeBps } = await program.account.globalState.fetch(globalStatePda);
+      expect(arena.crankRecipient.toBase58()).to.equal(keeper.publicKey.toBase58());
+      expect(arena.crankBounty.toString()).to.equal(arena.totalPool.mul(crankBountyBps).divn(10000).toString());
+
+      // Bounty, highest treasury fee and highest creator fee together can never exceed the pool
+      await expectError(
+        updateSettings({ crankBountyBps: new BN(10000).sub(FEE_SCHEDULE.maxFeeBps).sub(maxCreatorFeeBps).addn(1) }),
+        "InvalidCrankBounty"
+      );
+
+      for (const asset of assets) {
+        const bounty = arena.assetAmounts[asset].mul(crankBountyBps).divn(10000);
+        expect(arena.assetCrankBounties[asset].toString()).to.equal(bounty.toString());
+
+        await expectError(claimCrankBounty(admin, asset), "Unauthorized");
+        const claimed = await cpiEvent(await claimCrankBounty(keeper, asset), "CrankBountyClaimed");
+        expect(claimed.amount.toString()).to.equal(bounty.toString());
+        expect((await tokenBalance(keeper.publicKey, asset)).toString()).to.equal(bounty.toString());
+        await expectError(claimCrankBounty(keeper, asset), "CrankBountyAlreadyClaimed");
+
+        // Only the treasury, referral and creator fees are left behind
+        const vault = await getAccount(provider.connection, arenaVaultPda(asset));
+        const left = arena.assetTreasuryFees[asset].add(arena.assetReferralFees[asset]).add(arena.assetCreatorFees[asset]);
+        expect(vault.amount.toString()).to.equal(left.toString());
+      }
+
+      console.log(`✓ Keeper paid a ${crankBountyBps.toNumber()} bps crank bounty from ${assets.length} vaults`);
+    });
+
+    it("should pay the creator fee only to the arena's creator", async function () {
+      const arena = await program.account.arena.fetch(arenaPda);
+      if (arena.status !== 3) {
+        this.skip();
+      }
+      expect(arena.creatorFee.toString()).to.equal(arena.totalPool.muln(CREATOR_FEE_BPS).divn(10000).toString());
+
+      for (const asset of assets) {
+        const fee = arena.assetAmounts[asset].muln(CREATOR_FEE_BPS).divn(10000);
+        expect(arena.assetCreatorFees[asset].toString()).to.equal(fee.toString());
+
+        // The keeper already holds a token account in this mint from its bounty
+        await expectError(claimCreatorFee(keeper, asset), "Unauthorized");
+        const before = await tokenBalance(admin.publicKey, asset);
+        const claimed = await cpiEvent(await claimCreatorFee(admin, asset), "CreatorFeeClaimed");
+        expect(claimed.amount.toString()).to.equal(fee.toString());
+        expect((await tokenBalance(admin.publicKey, asset)).sub(before).toString()).to.equal(fee.toString());
+        await expectError(claimCreatorFee(admin, asset), "CreatorFeeAlreadyClaimed");
+
+        const vault = await getAccount(provider.connection, arenaVaultPda(asset));
+        expect(vault.amount.toString()).to.equal(arena.assetTreasuryFees[asset].add(arena.assetReferralFees[asset]).toString());
+      }
+
+      console.log(`✓ Creator paid a ${CREATOR_FEE_BPS} bps fee from ${assets.length} vaults`);
+    });
+
+    it("should reject repeat claims and claims on a losing asset", async function () {
+      const arena = await program.account.arena.fetch(arenaPda);
+      if (arena.status !== 3) {
+        this.skip();
+      }
+      const [winner] = entries.find(([, asset]) => asset === arena.winningAsset);
+      const [loser] = entries.find(([, asset]) => asset !== arena.winningAsset);
+
+      await expectError(claimReward(winner, arena.winningAsset), "RewardAlreadyClaimed");
+      await expectError(claimReward(loser, arena.winningAsset), "NotAWinner");
+
+      // Treasury fees and the referral are still owed, so nothing but settled entries can be closed yet
+      expect(arena.outstandingClaims).to.equal(assets.length + 1);
+      await expectError(closeArenaVault(arena.winningAsset, arena.vaultPayers[arena.winningAsset]), "OutstandingClaims");
+      await expectError(closeArena(arena.rentPayer), "OutstandingClaims");
+
+      console.log("✓ Repeat and losing claims rejected");
+    });
+
+    it("should sweep each vault's recorded treasury fee into its treasury vault", async function () {
+      const arena = await program.account.arena.fetch(arenaPda);
+      if (arena.status !== 3) {
+        this.skip();
+      }
+      expect(arena.treasuryFee.toString()).to.equal(arena.totalPool.muln(TREASURY_FEE_BPS).divn(10000).toString());
+
+      for (const asset of assets) {
+        // Referrers' cuts were set aside at entry and are credited separately
+        const fee = arena.assetAmounts[asset].muln(TREASURY_FEE_BPS).divn(10000).sub(arena.assetReferralFees[asset]);
+        expect(arena.assetTreasuryFees[asset].toString()).to.equal(fee.toString());
+
+        // Each mint is fresh, so its treasury vault holds exactly this arena's fee
+        const collected = await cpiEvent(await collectArenaFee(asset), "TreasuryFeeCollected");
+        expect(collected.amount.toString()).to.equal(fee.toString());
+        const treasuryVault = await getAccount(provider.connection, treasuryVaultPda(asset));
+        expect(treasuryVault.amount.toString()).to.equal(fee.toString());
+        expect(treasuryVault.owner.toBase58()).to.equal(globalStatePda.toBase58());
+
+        // Winners, the keeper and the creator have claimed, so only referral cuts remain in the arena vault
+        expect((await getAccount(provider.connection, arenaVaultPda(asset))).amount.toString()).to.equal(
+          arena.assetReferralFees[asset].toString()
+        );
+        await expectError(collectArenaFee(asset), "TreasuryFeeAlreadyCollected");
+      }
+      expect((await program.account.arena.fetch(arenaPda)).treasuryCollected).to.be.true;
+
+      console.log(`✓ Treasury fees swept from ${assets.length} vaults`);
+    });
+
+    it("should credit the referrer's cut to their balance and let only them claim it", async function () {
+      const arena = await program.account.arena.fetch(arenaPda);
+      if (arena.status !== 3) {
+        this.skip();
+      }
+      const [referred, asset] = entries[REFERRED];
+      const referralFee = arena.assetReferralFees[asset];
+      const referralVault = pda(Buffer.from("referral_vault"), mints[asset].toBuffer());
+      const referrerBalancePda = pda(Buffer.from("referrer_balance"), referrer.publicKey.toBuffer(), mints[asset].toBuffer());
+
+      const creditReferral = (player: PublicKey) =>
+        program.methods
+          .creditReferral()
+          .accountsPartial({
+            globalState: globalStatePda,
+            arena: arenaPda,
+            playerEntry: playerEntryPda(player),
+            mint: mints[asset],
+            arenaVault: arenaVaultPda(asset),
+            referralVault,
+            referrerBalance: referrerBalancePda,
+            caller: admin.publicKey,
+          })
+          .signers([admin])
+          .rpc();
+      const claimReferralRewards = async (claimant: Keypair) =>
+        program.methods
+          .claimReferralRewards()
+          .accountsPartial({
+            globalState: globalStatePda,
+            referrerBalance: referrerBalancePda,
+            referralVault,
+            referrerTokenAccount: await tokenAccount(claimant.publicKey, asset),
+            referrer: claimant.publicKey,
+          })
+          .signers([claimant])
+          .rpc();
+
+      // The entry stays open until its referral is paid, and the cut is bounded by the whole fee
+      await expectError(closePlayerEntry(referred.publicKey), "EntryNotSettled");
+      await expectError(creditReferral(entries[0][0].publicKey), "NoReferralFee");
+      await expectError(queueSettings({ referralShareBps: new BN(10001) }), "InvalidReferralShare");
+
+      const credited = await cpiEvent(await creditReferral(referred.publicKey), "ReferralCredited");
+      expect(credited.amount.toString()).to.equal(referralFee.toString());
+      await expectError(creditReferral(referred.publicKey), "NoReferralFee");
+      const referrerBalance = await program.account.referrerBalance.fetch(referrerBalancePda);
+      expect(referrerBalance.balance.toString()).to.equal(referralFee.toString());
+      expect((await getAccount(provider.connection, referralVault)).amount.toString()).to.equal(referralFee.toString());
+
+      await expectError(claimReferralRewards(keeper), "ConstraintSeeds");
+      await claimReferralRewards(referrer);
+      expect((await tokenBalance(referrer.publicKey, asset)).toString()).to.equal(referralFee.toString());
+      await expectError(claimReferralRewards(referrer), "NoReferralFee");
+
+      // Winners, keeper, creator, treasury and referrer together drain every vault exactly
+      for (const vaultAsset of assets) {
+        expect((await getAccount(provider.connection, arenaVaultPda(vaultAsset))).amount.toString()).to.equal("0");
+      }
+      console.log(`✓ Referrer paid ${referralFee.toString()} of the treasury fee`);
+    });
+
+    it("should only transfer treasury vault funds to the treasury wallet", async function () {
+      const arena = await program.account.arena.fetch(arenaPda);
+      if (!arena.treasuryCollected) {
+        this.skip();
+      }
+      const { treasury: treasuryWallet } = await program.account.globalState.fetch(globalStatePda);
+      const [asset] = assets;
+      const fee = arena.assetTreasuryFees[asset];
+
+      const transferTreasury = (destination: PublicKey) =>
+        program.methods
+          .transferTreasury(fee)
+          .accountsPartial({
+            globalState: globalStatePda,
+            treasuryVault: treasuryVaultPda(asset),
+            destination,
+            admin: admin.publicKey,
+          })
+          .signers([admin])
+          .rpc();
+
+      await expectError(transferTreasury(await tokenAccount(Keypair.generate().publicKey, asset)), "InvalidTreasury");
+
+      const destination = (
+        await getOrCreateAssociatedTokenAccount(provider.connection, admin, mints[asset], treasuryWallet, true)
+      ).address;
+      const transferred = await cpiEvent(await transferTreasury(destination), "TreasuryTransferred");
+      expect(transferred.destination.toBase58()).to.equal(destination.toBase58());
+      expect(transferred.amount.toString()).to.equal(fee.toString());
+      expect((await getAccount(provider.connection, destination)).amount.toString()).to.equal(fee.toString());
+      expect((await getAccount(provider.connection, treasuryVaultPda(asset))).amount.toString()).to.equal("0");
+
+      console.log("✓ Treasury funds only leave for the treasury wallet");
+    });
+
+    it("should rank the season's scores in batches and pay its leaderboard", async function () {
+      let season = await program.account.season.fetch(seasonPda);
+      if (season.participants === 0) {
+        this.skip();
+      }
+
+      // Best first, ties broken by ascending player key
+      const scores = await Promise.all(
+        entries.map(async ([{ publicKey: player }]) => ({
+          player,
+          points: (await program.account.seasonScore.fetch(seasonScorePda(player))).points,
+        }))
+      );
+      const ranking = scores
+        .sort((a, b) => b.points.cmp(a.points) || Buffer.compare(a.player.toBuffer(), b.player.toBuffer()))
+        .map(({ player }) => player);
+      const paid = await Promise.all(
+        ranking.slice(0, SEASON_PAYOUT_BPS.length).map((player) => tokenAccount(player, assets[0]))
+      );
+
+      const waitMs = (season.endTimestamp.toNumber() + 2) * 1000 - Date.now();
+      if (waitMs > 0) {
+        await expectError(rankSeasonScores(ranking), "SeasonNotEnded");
+        await expectError(closeSeason(paid), "SeasonNotEnded");
+        await sleep(waitMs);
+      }
+      // The admin's fallback only opens long after the season ends
+      await expectError(forceCloseSeason(keeper), "ConstraintHasOne");
+      await expectError(forceCloseSeason(admin), "ForceCloseTooEarly");
+
+      // Scores are ranked in any order over several batches, each only once
+      const players = entries.map(([{ publicKey }]) => publicKey);
+      const half = Math.ceil(players.length / 2);
+      await expectError(rankSeasonScores([]), "InvalidRemainingAccounts");
+      await rankSeasonScores(players.slice(half));
+      await expectError(closeSeason(paid), "RankingIncomplete");
+      await expectError(rankSeasonScores([players[half]]), "AlreadyRanked");
+      const ranked = await cpiEvent(await rankSeasonScores(players.slice(0, half)), "SeasonScoresRanked");
+      expect(ranked.rankedParticipants).to.equal(players.length);
+      season = await program.account.season.fetch(seasonPda);
+      expect(season.status).to.equal(2); // Ranking
+      for (const [rank, player] of ranking.slice(0, paid.length).entries()) {
+        expect(season.leaders[rank].toBase58()).to.equal(player.toBase58());
+      }
+
+      await expectError(closeSeason(paid.slice(1)), "InvalidRemainingAccounts");
+      await expectError(closeSeasonScore(players[0]), "SeasonNotClosed");
+      await expectError(closeSeason([paid[1], paid[0]]), "Unauthorized");
+
+      const before = await Promise.all(ranking.slice(0, paid.length).map((player) => tokenBalance(player, assets[0])));
+      const closed = await cpiEvent(await closeSeason(paid), "SeasonClosed");
+      for (const [rank, player] of ranking.slice(0, paid.length).entries()) {
+        const payout = SEASON_PRIZE.muln(SEASON_PAYOUT_BPS[rank]).divn(10000);
+        expect((await tokenBalance(player, assets[0])).sub(before[rank]).toString()).to.equal(payout.toString());
+        expect(closed.winners[rank].toBase58()).to.equal(player.toBase58());
+      }
+      expect(await provider.connection.getAccountInfo(seasonVaultPda)).to.be.null;
+
+      season = await program.account.season.fetch(seasonPda);
+      expect(season.status).to.equal(1); // Closed
+      await expectError(closeSeason(paid), "SeasonNotActive");
+      await expectError(rankSeasonScores(players), "SeasonNotActive");
+
+      // Scores of the closed season return their rent to whoever recorded them
+      await expectError(closeSeasonScore(players[0], keeper.publicKey), "InvalidRentReceiver");
+      const rentBefore = await provider.connection.getBalance(admin.publicKey);
+      for (const player of players) {
+        const scoreClosed = await cpiEvent(await closeSeasonScore(player), "SeasonScoreClosed");
+        expect(scoreClosed.player.toBase58()).to.equal(player.toBase58());
+        expect(scoreClosed.rentPayer.toBase58()).to.equal(admin.publicKey.toBase58());
+        expect(await program.account.seasonScore.fetchNullable(seasonScorePda(player))).to.be.null;
+      }
+      expect(await provider.connection.getBalance(admin.publicKey)).to.be.above(rentBefore);
+      console.log(`✓ Season ${seasonId.toString()} paid its top ${paid.length} of ${ranking.length} players`);
+    });
+
+    it("should close a paid-out arena and return each account's rent to its payer", async function () {
+      let arena = await program.account.arena.fetch(arenaPda);
+      if (!arena.treasuryCollected) {
+        this.skip();
+      }
+      expect(arena.outstandingClaims).to.equal(0);
+      expect(arena.rentPayer.toBase58()).to.equal(admin.publicKey.toBase58()); // The custom arena's creator
+      await expectError(closeArena(arena.rentPayer), "ArenaAccountsOpen");
+
+      for (const [entrant, asset] of entries) {
+        const before = await provider.connection.getBalance(entrant.publicKey);
+        const closed = await cpiEvent(await closePlayerEntry(entrant.publicKey), "PlayerEntryClosed");
+        expect(closed.player.toBase58()).to.equal(entrant.publicKey.toBase58());
+        expect(await program.account.playerEntry.fetchNullable(playerEntryPda(entrant.publicKey))).to.be.null;
+        expect(await provider.connection.getBalance(entrant.publicKey)).to.be.above(before);
+
+        // The loss was recorded at resolution, not again on close
+        const profile = await fetchProfile(entrant.publicKey);
+        expect(profile.losses).to.equal(asset === arena.winningAsset ? 0 : 1);
+        expect(profile.wins + profile.losses).to.equal(profile.arenasPlayed);
+      }
+
+      // Tokens sent to a settled vault are swept to the treasury rather than blocking its close
+      const DUST = 7n;
+      await mintTo(provider.connection, admin, mints[assets[0]], arenaVaultPda(assets[0]), admin, DUST);
+
+      // A vault is closed under the mint the arena holds, even once its asset is listed under another
+      const [relisted, otherAsset] = assets;
+      await setAssetConfig(relisted, { mint: mints[otherAsset] });
+      await expectError(closeArenaVault(relisted, arena.vaultPayers[relisted], mints[otherAsset]), "InvalidMint");
+
+      for (const asset of assets) {
+        const payer = arena.vaultPayers[asset];
+        expect(entries.some(([entrant, entryAsset]) => entryAsset === asset && entrant.publicKey.equals(payer))).to.be.true;
+        await expectError(closeArenaVault(asset, keeper.publicKey), "InvalidRentReceiver");
+
+        const before = await provider.connection.getBalance(payer);
+        const treasuryBefore = await getAccount(provider.connection, treasuryVaultPda(asset));
+        const closed = await cpiEvent(await closeArenaVault(asset, payer), "ArenaVaultClosed");
+        expect(closed.mint.toBase58()).to.equal(mints[asset].toBase58());
+        const swept = asset === assets[0] ? DUST : 0n;
+        expect(closed.sweptAmount.toString()).to.equal(swept.toString());
+        const treasuryAfter = await getAccount(provider.connection, treasuryVaultPda(asset));
+        expect(treasuryAfter.amount - treasuryBefore.amount).to.equal(swept);
+        expect(await provider.connection.getAccountInfo(arenaVaultPda(asset))).to.be.null;
+        expect(await provider.connection.getBalance(payer)).to.be.above(before);
+      }
+      await setAssetConfig(relisted, { mint: mints[relisted] });
+
+      arena = await program.account.arena.fetch(arenaPda);
+      expect(arena.openEntries).to.equal(0);
+      await expectError(closeArena(keeper.publicKey), "ConstraintHasOne");
+      const closed = await cpiEvent(await closeArena(arena.rentPayer), "ArenaClosed");
+      expect(closed.rentPayer.toBase58()).to.equal(admin.publicKey.toBase58());
+      expect(await program.account.arena.fetchNullable(arenaPda)).to.be.null;
+
+      console.log(`✓ Arena ${arena.id.toNumber()}, its ${entries.length} entries and ${assets.length} vaults closed`);
+    });
+
+    it("should void an asset still missing its end price after the grace period and refund its players", async () => {
+      // A head-to-head arena whose second asset never gets an end price
+      const [pricedAsset, voidAsset] = assets;
+      const voidKey = customArenaKey();
+      const voidLobby: Lobby = { ...lobby, arenaPda: customArenaPda(voidKey), custom: voidKey };
+      const voidArena = voidLobby.arenaPda;
+      const [backer, stranded] = [Keypair.generate(), Keypair.generate()];
+      const prices = await fetchLatestPrices([pricedAsset, voidAsset].map((asset) => FEEDS[asset]));
+      const stakes: [Keypair, number, BN][] = [backer, stranded].map((entrant, idx) => {
+        const asset = [pricedAsset, voidAsset][idx];
+        return [entrant, asset, new BN(amountForUsd(STAKES_USD[0], prices[FEEDS[asset]]).toString())];
+      });
+      await fund([backer, stranded]);
+      for (const [entrant, asset, amount] of stakes) {
+        await mintTo(provider.connection, admin, mints[asset], await tokenAccount(entrant.publicKey, asset), admin, BigInt(amount.toString()));
+      }
+
+      previousGracePeriod = (await program.account.globalState.fetch(globalStatePda)).oracleGracePeriod;
+      await expectError(queueSettings({ oracleGracePeriod: new BN(-1) }), "InvalidDuration");
+      await updateSettings({ oracleGracePeriod: new BN(3600) });
+
+      await createArena(voidKey, TEST_TIER_ID, voidLobby.arenaType, TEST_ARENA_DURATION, stakes.length, 0);
+      await enterLobby(voidLobby, backer, pricedAsset, stakes[0][2], mints[pricedAsset]);
+      await enterLobby(voidLobby, stranded, voidAsset, stakes[1][2], mints[voidAsset], [pricedAsset]);
+
+      let arena = await program.account.arena.fetch(voidArena);
+      const waitMs = (arena.endTimestamp.toNumber() + 5) * 1000 - Date.now();
+      if (waitMs > 0) {
+        console.log(`  Waiting ${Math.ceil(waitMs / 1000)}s for arena ${arena.id.toNumber()} to end...`);
+        await sleep(waitMs);
+      }
+
+      // Within the grace period a late feed may still report, so settlement waits for it
+      const endTimestamp = arena.endTimestamp.toNumber();
+      await expectError(settleArena([pricedAsset], endTimestamp, voidArena), "MissingEndPrice");
+
+      // Once it has passed the missing asset is voided and the arena resolves without it
+      await updateSettings({ oracleGracePeriod: new BN(0) });
+      const resolved = await cpiEvent(await settleArena([pricedAsset], endTimestamp, voidArena), "ArenaResolved");
+      arena = await program.account.arena.fetch(voidArena);
+      expect(resolved.voidAssetsBitmap).to.equal(1 << voidAsset);
+      expect(arena.voidAssetsBitmap).to.equal(1 << voidAsset);
+      expect(arena.status).to.equal(3); // Ended
+      expect(arena.winningAsset).to.equal(pricedAsset);
+      expect(arena.assetTreasuryFees[voidAsset].toString()).to.equal("0");
+      expect(arena.assetCrankBounties[voidAsset].toString()).to.equal("0");
+      // Fees are taken from the priced asset's stake alone, at the schedule's capped base rate
+      const settledPool = arena.totalPool.sub(arena.assetUsdTotals[voidAsset]);
+      expect(arena.treasuryFee.toString()).to.equal(settledPool.mul(FEE_SCHEDULE.maxFeeBps).divn(10000).toString());
+
+      // The voided vault pays no winnings, and its entries are neither losses nor settled until refunded
+      await expectError(claimReward(backer, voidAsset, voidArena), "AssetVoided");
+      expect((await fetchProfile(stranded.publicKey)).losses).to.equal(0);
+      await expectError(closePlayerEntry(stranded.publicKey, voidArena), "EntryNotSettled");
+
+      const strandedEntry = playerEntryPda(stranded.publicKey, voidArena);
+      const before = await tokenBalance(stranded.publicKey, voidAsset);
+      const refunded = await cpiEvent(
+        await program.methods
+          .withdrawSuspended()
+          .accountsPartial({
+            arena: voidArena,
+            playerEntry: strandedEntry,
+            playerProfile: playerProfilePda(stranded.publicKey),
+            arenaVault: arenaVaultPda(voidAsset, mints[voidAsset], voidArena),
+            playerTokenAccount: await tokenAccount(stranded.publicKey, voidAsset),
+            player: stranded.publicKey,
+          })
+          .signers([stranded])
+          .rpc(),
+        "EntryRefunded"
+      );
+      expect(refunded.amount.toString()).to.equal(stakes[1][2].toString());
+      expect((await tokenBalance(stranded.publicKey, voidAsset)).sub(before).toString()).to.equal(stakes[1][2].toString());
+      expect((await program.account.playerEntry.fetch(strandedEntry)).rewardClaimed).to.be.true;
+      const voidVault = await getAccount(provider.connection, arenaVaultPda(voidAsset, mints[voidAsset], voidArena));
+      expect(voidVault.amount.toString()).to.equal("0");
+
+      // The winner's single claim covers every vault that still pays out
+      await claimReward(backer, pricedAsset, voidArena);
+      const backerEntry = playerEntryPda(backer.publicKey, voidArena);
+      expect((await program.account.playerEntry.fetch(backerEntry)).rewardClaimed).to.be.true;
+
+      // The arena ended after every season, so no score is waiting and the crank closes the entry
+      const globalState = await program.account.globalState.fetch(globalStatePda);
+      expect(arena.endTimestamp.gte(globalState.seasonsEnd)).to.be.true;
+      const backerBefore = await provider.connection.getBalance(backer.publicKey);
+      const closed = await cpiEvent(await closePlayerEntry(backer.publicKey, voidArena), "PlayerEntryClosed");
+      expect(closed.player.toBase58()).to.equal(backer.publicKey.toBase58());
+      expect(await program.account.playerEntry.fetchNullable(backerEntry)).to.be.null;
+      expect(await provider.connection.getBalance(backer.publicKey)).to.be.above(backerBefore);
+      console.log(`✓ Asset ${voidAsset} voided in arena ${arena.id.toNumber()} and refunded in full`);
+    });
+  });
+
+  describe("Waiting Lobbies", () => {
+    // Three entrants wait in an empty lobby; the first leaves and the lobby times out before it fills
+    const TEST_LOBBY_TIMEOUT = 30;
+    const ASSET = ASSET_SOL;
+    const STAKE_USD = 12;
+
+    const entrants = Array.from({ length: 3 }, () => Keypair.generate());
+    const [leaver, ...stayers] = entrants;
+    const latecomer = Keypair.generate();
+    const tierEntrant = Keypair.generate(); // Plays in a second tier alongside the test tier
+    const OTHER_TIER_ID = TEST_TIER_ID + 1;
+    let mint: PublicKey;
+    let amount: BN;
+    let lobby: Lobby;
+    let arenaPda: PublicKey;
+    let otherLobby: Lobby | null = null;
+    let previousTimeout: BN | null = null;
+    let previousListing: AssetListing | null = null;
+
+    const arenaVaultPda = (arena = arenaPda) => pda(Buffer.from("arena_vault"), arena.toBuffer(), mint.toBuffer());
+    const playerEntryPda = (player: PublicKey, arena = arenaPda) =>
+      pda(Buffer.from("player_entry"), arena.toBuffer(), player.toBuffer());
+    const tokenBalance = async (owner: PublicKey) =>
+      new BN((await getAccount(provider.connection, await ata(owner, mint))).amount.toString());
+
+    const leaveArena = async (player: Keypair) =>
+      program.methods
+        .leaveArena()
+        .accountsPartial({
+          arena: arenaPda,
+          playerEntry: playerEntryPda(player.publicKey),
+          arenaVault: arenaVaultPda(),
+          playerTokenAccount: await ata(player.publicKey, mint),
+          player: player.publicKey,
+        })
+        .signers([player])
+        .rpc();
+
+    const expireArena = ({ tierId, arenaPda: arena }: Lobby = lobby) =>
+      program.methods
+        .expireArena()
+        .accountsPartial({
+          globalState: globalStatePda,
+          stakeTier: stakeTierPda(tierId),
+          arena,
+          caller: admin.publicKey,
+        })
+        .signers([admin])
+        .rpc();
+
+    // Anyone may crank a refund; it is always paid to the entry's player
+    const refundExpired = async (player: PublicKey, arena = arenaPda) =>
+      program.methods
+        .refundExpired()
+        .accountsPartial({
+          arena,
+          playerEntry: playerEntryPda(player, arena),
+          playerProfile: playerProfilePda(player),
+          arenaVault: arenaVaultPda(arena),
+          playerTokenAccount: await ata(player, mint),
+          caller: admin.publicKey,
+        })
+        .signers([admin])
+        .rpc();
+
+    before(async function () {
+      await setStakeTier(TEST_TIER_ID, MIN_ENTRY_USD, MAX_ENTRY_USD);
+      lobby = await freeLobby(TEST_TIER_ID);
+      if (!lobby) {
+        console.log("  Both lobbies have waiting players; skipping waiting lobby tests");
+        this.skip();
+      }
+      arenaPda = lobby.arenaPda;
+      const globalState = await program.account.globalState.fetch(globalStatePda);
+
+      // The asset listed under a fresh mint and a short lobby timeout, both restored afterwards
+      mint = await createMint(provider.connection, admin, admin.publicKey, null, 6);
+      previousListing = await program.account.assetConfig.fetchNullable(assetConfigPda(ASSET));
+      await setAssetConfig(ASSET, { mint });
+      previousTimeout = globalState.lobbyTimeout;
+      await updateSettings({ lobbyTimeout: new BN(TEST_LOBBY_TIMEOUT) });
+
+      const prices = await fetchLatestPrices([FEEDS[ASSET]]);
+      amount = new BN(amountForUsd(STAKE_USD, prices[FEEDS[ASSET]]).toString());
+      await fund([...entrants, latecomer, tierEntrant]);
+      for (const entrant of [...entrants, latecomer, tierEntrant]) {
+        const tokenAccount = await ata(entrant.publicKey, mint);
+        await mintTo(provider.connection, admin, mint, tokenAccount, admin, BigInt(amount.toString()));
+      }
+    });
+
+    after(async () => {
+      if (previousTimeout) {
+        await updateSettings({ lobbyTimeout: previousTimeout });
+      }
+      if (mint) {
+        await restoreAssetConfig(ASSET, previousListing, mint);
+      }
+    });
+
+    it("should only accept asset listings from the admin within the registry's bounds", async () => {
+      await expectError(setAssetConfig(MAX_ASSETS, { mint }), "InvalidAsset");
+      await expectError(setAssetConfig(ASSET, { mint, maxAge: new BN(0) }), "InvalidMaxAge");
+      await expectError(setAssetConfig(ASSET, { mint, maxConfidenceBps: 0 }), "InvalidConfidenceBound");
+      await expectError(setAssetConfig(ASSET, { mint, maxConfidenceBps: 10001 }), "InvalidConfidenceBound");
+      await expectError(setAssetConfig(ASSET, { mint }, entrants[0]), "ConstraintHasOne");
+
+      // The listing carries the feed, the mint and the mint's decimals
+      const assetConfig = await program.account.assetConfig.fetch(assetConfigPda(ASSET));
+      expect(Buffer.from(assetConfig.feedId).toString("hex")).to.equal(FEEDS[ASSET]);
+      expect(assetConfig.mint.toBase58()).to.equal(mint.toBase58());
+      expect(assetConfig.decimals).to.equal(6);
+      expect(assetConfig.maxConfidenceBps).to.equal(DEFAULT_MAX_CONFIDENCE_BPS);
+      expect(assetConfig.enabled).to.be.true;
+      console.log("✓ Invalid and non-admin listings rejected");
+    });
+
+    it("should turn away entries on a delisted asset", async () => {
+      await setAssetConfig(ASSET, { mint, enabled: false });
+      try {
+        await expectError(enterLobby(lobby, entrants[0], ASSET, amount, mint), "AssetDisabled");
+      } finally {
+        await setAssetConfig(ASSET, { mint });
+      }
+      expect(await program.account.arena.fetchNullable(arenaPda)).to.be.null;
+      console.log("✓ Entry on a disabled asset rejected");
+    });
+
+    it("should reject an entry priced with a confidence interval wider than the asset's bound", async function () {
+      // The tightest bound still admits a feed quoting under 1bps of confidence
+      const { price, conf } = (await fetchLatestPrices([FEEDS[ASSET]]))[FEEDS[ASSET]];
+      if (conf * 10000n <= price) {
+        console.log("  Feed confidence is under 1bps; skipping");
+        this.skip();
+      }
+
+      await setAssetConfig(ASSET, { mint, maxConfidenceBps: 1 });
+      try {
+        await expectError(enterLobby(lobby, entrants[0], ASSET, amount, mint), "ConfidenceTooWide");
+      } finally {
+        await setAssetConfig(ASSET, { mint });
+      }
+      expect(await program.account.arena.fetchNullable(arenaPda)).to.be.null;
+      console.log("✓ Entry with a too-wide confidence interval rejected");
+    });
+
+    it("should only accept stake tiers from the admin with a valid range", async () => {
+      await expectError(setStakeTier(OTHER_TIER_ID, 0, 2_000_000), "InvalidStakeTier");
+      await expectError(setStakeTier(OTHER_TIER_ID, 2_000_000, 1_000_000), "InvalidStakeTier");
+      await expectError(setStakeTier(OTHER_TIER_ID, 1_000_000, 2_000_000, true, entrants[0]), "ConstraintHasOne");
+
+      // Back-to-back changes to two tiers are queued on each tier and applied independently
+      const testTier = await program.account.stakeTier.fetch(stakeTierPda(TEST_TIER_ID));
+      await queueStakeTier(OTHER_TIER_ID, 1_000_000, 2_000_000);
+      await queueStakeTier(TEST_TIER_ID, testTier.minEntryUsd.toNumber(), testTier.maxEntryUsd.toNumber(), testTier.enabled);
+      await expectError(executeSettings(), "NoPendingSettings");
+      await executeSettings({ stakeTier: stakeTierPda(OTHER_TIER_ID) });
+      const stakeTier = await program.account.stakeTier.fetch(stakeTierPda(OTHER_TIER_ID));
+      expect(stakeTier.minEntryUsd.toNumber()).to.equal(1_000_000);
+      expect(stakeTier.maxEntryUsd.toNumber()).to.equal(2_000_000);
+      expect(stakeTier.enabled).to.be.true;
+      expect(stakeTier.pendingChange).to.be.null;
+      expect((await program.account.stakeTier.fetch(stakeTierPda(TEST_TIER_ID))).pendingChange).to.not.be.null;
+
+      await executeSettings({ stakeTier: stakeTierPda(TEST_TIER_ID) });
+      const updatedTestTier = await program.account.stakeTier.fetch(stakeTierPda(TEST_TIER_ID));
+      expect(updatedTestTier.pendingChange).to.be.null;
+      expect(updatedTestTier.minEntryUsd.toString()).to.equal(testTier.minEntryUsd.toString());
+      expect(updatedTestTier.currentArenaId.toString()).to.equal(testTier.currentArenaId.toString());
+      await expectError(executeSettings({ stakeTier: stakeTierPda(TEST_TIER_ID) }), "NoPendingSettings");
+
+      // A tier's queued change can also be discarded through its account
+      await queueStakeTier(OTHER_TIER_ID, 1_000_000, 3_000_000);
+      await cancelSettings({ stakeTier: stakeTierPda(OTHER_TIER_ID) });
+      expect((await program.account.stakeTier.fetch(stakeTierPda(OTHER_TIER_ID))).maxEntryUsd.toNumber()).to.equal(2_000_000);
+      console.log("✓ Invalid and non-admin stake tiers rejected, back-to-back tier changes applied to their own accounts");
+    });
+
+    it("should keep each tier's entry range, lobbies and arena ids separate", async function () {
+      // A $12 entry is outside the $1-$2 tier
+      otherLobby = await freeLobby(OTHER_TIER_ID);
+      if (!otherLobby) {
+        this.skip();
+      }
+      await expectError(enterLobby(otherLobby, tierEntrant, ASSET, amount, mint), "InvalidEntryAmount");
+
+      // Widening the tier keeps its lobby; the entry then opens an arena under the tier's own seeds
+      await setStakeTier(OTHER_TIER_ID, MIN_ENTRY_USD, MAX_ENTRY_USD);
+      otherLobby = await freeLobby(OTHER_TIER_ID);
+      await enterLobby(otherLobby, tierEntrant, ASSET, amount, mint);
+
+      const otherArena = await program.account.arena.fetch(otherLobby.arenaPda);
+      const stakeTier = await program.account.stakeTier.fetch(stakeTierPda(OTHER_TIER_ID));
+      expect(otherArena.tierId).to.equal(OTHER_TIER_ID);
+      expect(otherArena.playerCount).to.equal(1);
+      expect(stakeTier.waitingArenas[otherArena.arenaType].toString()).to.equal(otherArena.id.toString());
+      expect(await program.account.arena.fetchNullable(arenaPda)).to.be.null; // The test tier's lobby is untouched
+
+      // A disabled tier takes no entries
+      await setStakeTier(OTHER_TIER_ID, MIN_ENTRY_USD, MAX_ENTRY_USD, false);
+      await expectError(enterLobby(otherLobby, latecomer, ASSET, amount, mint), "StakeTierDisabled");
+      console.log(`✓ Tier ${OTHER_TIER_ID} arena ${otherArena.id.toNumber()} kept apart from tier ${TEST_TIER_ID}`);
+    });
+
+    it("should reject expiring a lobby before its timeout", async () => {
+      for (const entrant of entrants) {
+        await enterLobby(lobby, entrant, ASSET, amount, mint);
+      }
+      expect((await program.account.arena.fetch(arenaPda)).status).to.equal(1); // Waiting
+
+      await expectError(expireArena(), "LobbyNotExpired");
+      console.log("✓ Lobby within its timeout cannot be expired");
+    });
+
+    it("should refund a player who leaves and keep the lobby compact", async () => {
+      const before = await tokenBalance(leaver.publicKey);
+      await leaveArena(leaver);
+
+      const refunded = (await tokenBalance(leaver.publicKey)).sub(before);
+      expect(refunded.toString()).to.equal(amount.toString());
+      expect(await program.account.playerEntry.fetchNullable(playerEntryPda(leaver.publicKey))).to.be.null;
+
+      // The remaining players shift down over the vacated slot
+      const arena = await program.account.arena.fetch(arenaPda);
+      expect(arena.playerCount).to.equal(stayers.length);
+      expect(arena.players.slice(0, stayers.length).map((player) => player.toBase58())).to.deep.equal(
+        stayers.map((stayer) => stayer.publicKey.toBase58())
+      );
+      expect(arena.players[stayers.length].equals(PublicKey.default)).to.be.true;
+      expect(arena.assetCounts[ASSET]).to.equal(stayers.length);
+      expect(arena.assetAmounts[ASSET].toString()).to.equal(amount.muln(stayers.length).toString());
+      console.log(`✓ Leaver refunded; ${arena.playerCount} players remain`);
+    });
+
+    it("should turn away entrants once the lobby has timed out", async () => {
+      const arena = await program.account.arena.fetch(arenaPda);
+      const waitMs = (arena.createdTimestamp.toNumber() + TEST_LOBBY_TIMEOUT + 5) * 1000 - Date.now();
+      if (waitMs > 0) {
+        console.log(`  Waiting ${Math.ceil(waitMs / 1000)}s for the lobby to time out...`);
+        await sleep(waitMs);
+      }
+
+      await expectError(enterLobby(lobby, latecomer, ASSET, amount, mint), "LobbyExpired");
+      console.log("✓ Timed-out lobby rejects new entrants");
+    });
+
+    it("should expire a timed-out lobby and free its slot", async () => {
+      const expired = await cpiEvent(await expireArena(), "ArenaExpired");
+
+      const arena = await program.account.arena.fetch(arenaPda);
+      expect(expired.arenaId.toString()).to.equal(arena.id.toString());
+      expect(expired.playerCount).to.equal(arena.playerCount);
+      const stakeTier = await program.account.stakeTier.fetch(stakeTierPda(TEST_TIER_ID));
+      expect(arena.status).to.equal(5); // Expired
+      expect(stakeTier.waitingArenas[arena.arenaType]).to.be.null;
+
+      // Players of an expired arena are refunded rather than leaving
+      await expectError(leaveArena(stayers[0]), "ArenaNotWaiting");
+      console.log(`✓ Arena ${arena.id.toNumber()} expired`);
+    });
+
+    it("should refund every entry of an expired arena to its player", async () => {
+      for (const entrant of stayers) {
+        const before = await tokenBalance(entrant.publicKey);
+        await refundExpired(entrant.publicKey);
+
+        const refunded = (await tokenBalance(entrant.publicKey)).sub(before);
+        expect(refunded.toString()).to.equal(amount.toString());
+        expect((await program.account.playerEntry.fetch(playerEntryPda(entrant.publicKey))).rewardClaimed).to.be.true;
+
+        // A refund counts with the ties and leaves the streak alone
+        const profile = await fetchProfile(entrant.publicKey);
+        expect(profile.arenasPlayed).to.equal(1);
+        expect(profile.ties).to.equal(1);
+        expect(profile.currentStreak).to.equal(0);
+      }
+      await expectError(refundExpired(stayers[0].publicKey), "AlreadyWithdrawn");
+
+      const vault = await getAccount(provider.connection, arenaVaultPda());
+      expect(vault.amount.toString()).to.equal("0");
+
+      // The other tier's lobby timed out alongside; expiring it frees that tier for the next run
+      if (otherLobby) {
+        await expireArena(otherLobby);
+        await refundExpired(tierEntrant.publicKey, otherLobby.arenaPda);
+      }
+      console.log(`✓ ${stayers.length} entries refunded in full`);
+    });
+  });
+
+  describe("Private Arenas", () => {
+    // One waiting arena behind an allowlist and one behind an invite code; their entrants leave afterwards
+    const ASSET = ASSET_BONK;
+    const STAKE_USD = 12;
+    const INVITE_SECRET = Buffer.from("cryptarena-invite");
+
+    const invited = Keypair.generate();
+    const outsider = Keypair.generate();
+    const inviteHolder = Keypair.generate();
+    const allowlistKey = customArenaKey();
+    const inviteKey = customArenaKey();
+    const lobbyFor = (custom: CustomArenaKey): Lobby => ({
+      tierId: TEST_TIER_ID,
+      arenaType: ARENA_TYPES[0],
+      arenaPda: customArenaPda(custom),
+      custom,
+    });
+    const allowlistLobby = lobbyFor(allowlistKey);
+    const inviteLobby = lobbyFor(inviteKey);
+    let mint: PublicKey;
+    let amount: BN;
+    let previousListing: AssetListing | null = null;
+
+    const tokenBalance = async (owner: PublicKey) =>
+      new BN((await getAccount(provider.connection, await ata(owner, mint))).amount.toString());
+
+    const setAllowlist = (players: PublicKey[], arena = allowlistLobby.arenaPda, signer = admin) =>
+      program.methods
+        .setArenaAllowlist(players)
+        .accountsPartial({
+          arena,
+          arenaAllowlist: arenaAllowlistPda(arena),
+          creator: signer.publicKey,
+        })
+        .signers([signer])
+        .rpc();
+
+    const leaveArena = async (player: Keypair, arena: PublicKey) =>
+      program.methods
+        .leaveArena()
+        .accountsPartial({
+          arena,
+          playerEntry: pda(Buffer.from("player_entry"), arena.toBuffer(), player.publicKey.toBuffer()),
+          arenaVault: pda(Buffer.from("arena_vault"), arena.toBuffer(), mint.toBuffer()),
+          playerTokenAccount: await ata(player.publicKey, mint),
+          player: player.publicKey,
+        })
+        .signers([player])
+        .rpc();
+
+    before(async () => {
+      await setStakeTier(TEST_TIER_ID, MIN_ENTRY_USD, MAX_ENTRY_USD);
+
+      // The asset is listed under a fresh mint; the previous listing is restored afterwards
+      mint = await createMint(provider.connection, admin, admin.publicKey, null, 6);
+      previousListing = await program.account.assetConfig.fetchNullable(assetConfigPda(ASSET));
+      await setAssetConfig(ASSET, { mint });
+
+      const prices = await fetchLatestPrices([FEEDS[ASSET]]);
+      amount = new BN(amountForUsd(STAKE_USD, prices[FEEDS[ASSET]]).toString());
+      await fund([invited, outsider, inviteHolder]);
+      for (const entrant of [invited, outsider, inviteHolder]) {
+        await mintTo(provider.connection, admin, mint, await ata(entrant.publicKey, mint), admin, BigInt(amount.toString()));
+      }
+    });
+
+    after(async () => {
+      if (mint) {
+        await restoreAssetConfig(ASSET, previousListing, mint);
+      }
+    });
+
+    it("should only admit allowlisted players to an allowlist arena", async () => {
+      await createArena(allowlistKey, TEST_TIER_ID, allowlistLobby.arenaType, 60, 3, 0, { allowlist: {} });
+
+      // Only the creator sets the allowlist
+      await expectError(setAllowlist([outsider.publicKey], allowlistLobby.arenaPda, outsider), "Unauthorized");
+      await setAllowlist([invited.publicKey]);
+      const allowlist = await program.account.arenaAllowlist.fetch(arenaAllowlistPda(allowlistLobby.arenaPda));
+      expect(allowlist.players.map((player) => player.toBase58())).to.deep.equal([invited.publicKey.toBase58()]);
+
+      await expectError(enterLobby(allowlistLobby, outsider, ASSET, amount, mint, [], { allowlist: true }), "NotInvited");
+      // The allowlist account must be passed even by an allowlisted player
+      await expectError(enterLobby(allowlistLobby, invited, ASSET, amount, mint), "NotInvited");
+      await enterLobby(allowlistLobby, invited, ASSET, amount, mint, [], { allowlist: true });
+
+      const arena = await program.account.arena.fetch(allowlistLobby.arenaPda);
+      expect(arena.accessPolicy).to.equal(1); // Allowlist
+      expect(arena.playerCount).to.equal(1);
+      console.log("✓ Allowlist arena admitted only its allowlisted player");
+    });
+
+    it("should only admit holders of the invite secret to an invite code arena", async () => {
+      await createArena(
+        inviteKey,
+        TEST_TIER_ID,
+        inviteLobby.arenaType,
+        60,
+        3,
+        0,
+        { inviteCode: {} },
+        inviteHashFor(INVITE_SECRET)
+      );
+
+      // Allowlists belong to allowlist arenas only
+      await expectError(setAllowlist([inviteHolder.publicKey], inviteLobby.arenaPda), "InvalidAccessPolicy");
+
+      await expectError(enterLobby(inviteLobby, inviteHolder, ASSET, amount, mint), "InvalidInviteSecret");
+      await expectError(
+        enterLobby(inviteLobby, inviteHolder, ASSET, amount, mint, [], { inviteSecret: Buffer.from("wrong") }),
+        "InvalidInviteSecret"
+      );
+      await enterLobby(inviteLobby, inviteHolder, ASSET, amount, mint, [], { inviteSecret: INVITE_SECRET });
+
+      const arena = await program.account.arena.fetch(inviteLobby.arenaPda);
+      expect(arena.accessPolicy).to.equal(2); // InviteCode
+      expect(arena.playerCount).to.equal(1);
+      console.log("✓ Invite code arena admitted only the secret's holder");
+    });
+
+    it("should refund private arena entrants that leave", async () => {
+      for (const [player, lobby] of [
+        [invited, allowlistLobby],
+        [inviteHolder, inviteLobby],
+      ] as [Keypair, Lobby][]) {
+        const before = await tokenBalance(player.publicKey);
+        await leaveArena(player, lobby.arenaPda);
+        expect((await tokenBalance(player.publicKey)).sub(before).toString()).to.equal(amount.toString());
+        expect((await program.account.arena.fetch(lobby.arenaPda)).playerCount).to.equal(0);
+      }
+      console.log("✓ Private arena entries refunded");
+    });
+  });
+
+  describe("Vault Entries", () => {
+    // A depositor funds their user vault and pays a custom arena entry from it, then leaves
+    const ASSET = ASSET_HNT;
+    const STAKE_USD = 12;
+    const OTHER_DEPOSIT = new BN(1_000_000);
+
+    const depositor = Keypair.generate();
+    const custom = customArenaKey();
+    const lobby: Lobby = { tierId: TEST_TIER_ID, arenaType: ARENA_TYPES[0], arenaPda: customArenaPda(custom), custom };
+    let mint: PublicKey;
+    let otherMint: PublicKey; // A second mint the depositor keeps a separate vault balance in
+    let amount: BN;
+    let previousListing: AssetListing | null = null;
+
+    const tokenBalance = async (account: PublicKey) =>
+      new BN((await getAccount(provider.connection, account)).amount.toString());
+
+    const initUserVault = (vaultMint: PublicKey) =>
+      program.methods
+        .initUserVault()
+        .accountsPartial({
+          userVault: userVaultPda(depositor.publicKey, vaultMint),
+          mint: vaultMint,
+          user: depositor.publicKey,
+        })
+        .signers([depositor])
+        .rpc();
+
+    const depositToVault = async (deposit: BN, vaultMint = mint) =>
+      program.methods
+        .depositToVault(deposit)
+        .accountsPartial({
+          globalState: globalStatePda,
+          userVault: userVaultPda(depositor.publicKey, vaultMint),
+          mint: vaultMint,
+          protocolVault: protocolVaultPda(vaultMint),
+          userTokenAccount: await ata(depositor.publicKey, vaultMint),
+          user: depositor.publicKey,
+        })
+        .signers([depositor])
+        .rpc();
+
+    const withdrawFromVault = (withdrawal: BN, vaultMint: PublicKey, userTokenAccount: PublicKey) =>
+      program.methods
+        .withdrawFromVault(withdrawal)
+        .accountsPartial({
+          globalState: globalStatePda,
+          userVault: userVaultPda(depositor.publicKey, vaultMint),
+          protocolVault: protocolVaultPda(vaultMint),
+          userTokenAccount,
+          user: depositor.publicKey,
+        })
+        .signers([depositor])
+        .rpc();
+
+    before(async () => {
+      await setStakeTier(TEST_TIER_ID, MIN_ENTRY_USD, MAX_ENTRY_USD);
+
+      // The asset is listed under a fresh mint, so its protocol vault only holds this test's deposits
+      mint = await createMint(provider.connection, admin, admin.publicKey, null, 6);
+      previousListing = await program.account.assetConfig.fetchNullable(assetConfigPda(ASSET));
+      await setAssetConfig(ASSET, { mint });
+
+      const prices = await fetchLatestPrices([FEEDS[ASSET]]);
+      amount = new BN(amountForUsd(STAKE_USD, prices[FEEDS[ASSET]]).toString());
+      await fund([depositor]);
+      await mintTo(provider.connection, admin, mint, await ata(depositor.publicKey, mint), admin, BigInt(amount.toString()));
+      otherMint = await createMint(provider.connection, admin, admin.publicKey, null, 6);
+      await mintTo(
+        provider.connection,
+        admin,
+        otherMint,
+        await ata(depositor.publicKey, otherMint),
+        admin,
+        BigInt(OTHER_DEPOSIT.toString())
+      );
+
+      await initUserVault(mint);
+      await createArena(custom, TEST_TIER_ID, lobby.arenaType, 60, 3, 0);
+    });
+
+    after(async () => {
+      if (mint) {
+        await restoreAssetConfig(ASSET, previousListing, mint);
+      }
+    });
+
+    it("should credit deposits to the user vault balance", async () => {
+      await expectError(depositToVault(new BN(0)), "InvalidDepositAmount");
+      const deposited = await cpiEvent(await depositToVault(amount.divn(2)), "UserVaultDeposited");
+      expect(deposited.availableBalance.toString()).to.equal(amount.divn(2).toString());
+
+      const userVault = await program.account.userVault.fetch(userVaultPda(depositor.publicKey, mint));
+      expect(userVault.mint.toBase58()).to.equal(mint.toBase58());
+      expect(userVault.availableBalance.toString()).to.equal(amount.divn(2).toString());
+      expect((await tokenBalance(protocolVaultPda(mint))).toString()).to.equal(amount.divn(2).toString());
+      console.log("✓ Deposit credited to the user vault");
+    });
+
+    it("should keep each mint's balance in its own user vault", async () => {
+      await initUserVault(otherMint);
+      await depositToVault(OTHER_DEPOSIT, otherMint);
+
+      const vault = await program.account.userVault.fetch(userVaultPda(depositor.publicKey, mint));
+      const otherVault = await program.account.userVault.fetch(userVaultPda(depositor.publicKey, otherMint));
+      expect(vault.availableBalance.toString()).to.equal(amount.divn(2).toString());
+      expect(otherVault.availableBalance.toString()).to.equal(OTHER_DEPOSIT.toString());
+
+      // Withdrawals leave in the vault's own mint and never exceed its balance
+      const otherWallet = await ata(depositor.publicKey, otherMint);
+      await expectError(withdrawFromVault(OTHER_DEPOSIT, otherMint, await ata(depositor.publicKey, mint)), "InvalidMint");
+      await expectError(withdrawFromVault(OTHER_DEPOSIT.addn(1), otherMint, otherWallet), "InsufficientBalance");
+      const withdrawn = await cpiEvent(await withdrawFromVault(OTHER_DEPOSIT, otherMint, otherWallet), "UserVaultWithdrawn");
+      expect(withdrawn.mint.toBase58()).to.equal(otherMint.toBase58());
+      expect(withdrawn.availableBalance.toString()).to.equal("0");
+
+      expect((await tokenBalance(otherWallet)).toString()).to.equal(OTHER_DEPOSIT.toString());
+      expect(
+        (await program.account.userVault.fetch(userVaultPda(depositor.publicKey, otherMint))).availableBalance.toString()
+      ).to.equal("0");
+      console.log("✓ User vault balances kept per mint");
+    });
+
+    it("should pay an entry from the user vault balance", async () => {
+      // Each entry path needs the account it is paid from
+      await expectError(
+        enterLobby(lobby, depositor, ASSET, amount, mint, [], { fromVault: true, omitFunding: true }),
+        "MissingFundingAccount"
+      );
+      await expectError(enterLobby(lobby, depositor, ASSET, amount, mint, [], { omitFunding: true }), "MissingFundingAccount");
+      await expectError(enterLobby(lobby, depositor, ASSET, amount, mint, [], { fromVault: true }), "InsufficientBalance");
+
+      await depositToVault(amount.sub(amount.divn(2)));
+      const walletBefore = await tokenBalance(await ata(depositor.publicKey, mint));
+      await enterLobby(lobby, depositor, ASSET, amount, mint, [], { fromVault: true });
+
+      const userVault = await program.account.userVault.fetch(userVaultPda(depositor.publicKey, mint));
+      expect(userVault.availableBalance.toString()).to.equal("0");
+      expect((await tokenBalance(protocolVaultPda(mint))).toString()).to.equal("0");
+      const arenaVault = pda(Buffer.from("arena_vault"), lobby.arenaPda.toBuffer(), mint.toBuffer());
+      expect((await tokenBalance(arenaVault)).toString()).to.equal(amount.toString());
+      expect((await tokenBalance(await ata(depositor.publicKey, mint))).toString()).to.equal(walletBefore.toString());
+      console.log("✓ Entry paid from the user vault");
+    });
+
+    it("should refund a vault-paid entry to the wallet when the player leaves", async () => {
+      const wallet = await ata(depositor.publicKey, mint);
+      const before = await tokenBalance(wallet);
+      const signature = await program.methods
+        .leaveArena()
+        .accountsPartial({
+          arena: lobby.arenaPda,
+          playerEntry: pda(Buffer.from("player_entry"), lobby.arenaPda.toBuffer(), depositor.publicKey.toBuffer()),
+          arenaVault: pda(Buffer.from("arena_vault"), lobby.arenaPda.toBuffer(), mint.toBuffer()),
+          playerTokenAccount: wallet,
+          player: depositor.publicKey,
+        })
+        .signers([depositor])
+        .rpc();
+
+      expect((await tokenBalance(wallet)).sub(before).toString()).to.equal(amount.toString());
+      const refunded = await cpiEvent(signature, "EntryRefunded");
+      expect(refunded.amount.toString()).to.equal(amount.toString());
+      expect(refunded.arenaStatus).to.equal(1); // Waiting
+
+      // Leaving a waiting arena takes the entry back off the profile
+      const profile = await fetchProfile(depositor.publicKey);
+      expect(profile.arenasPlayed).to.equal(0);
+      expect(profile.totalStaked.toString()).to.equal("0");
+      expect(profile.favoriteAsset).to.equal(255);
+      console.log("✓ Vault-paid entry refunded");
     });
   });
 });
@@ -312,6 +2539,111 @@ describe("Cryptarena Faucet", () => {
   const provider = anchor.AnchorProvider.env();
   anchor.setProvider(provider);
 
+  const program = anchor.workspace.CryptarenaFaucet as Program<CryptarenaFaucet>;
+  const admin = (provider.wallet as any).payer as Keypair;
+  const [faucetStatePda] = PublicKey.findProgramAddressSync([Buffer.from("faucet_state")], program.programId);
+
+  describe("Faucet Administration", () => {
+    const nominee = Keypair.generate();
+    const TEST_TIMELOCK = 5;
+
+    const adminCall = (method: "cancelSettings" | "executeSettings", signer = admin) =>
+      program.methods[method]()
+        .accounts({ faucetState: faucetStatePda, admin: signer.publicKey })
+        .signers([signer])
+        .rpc();
+    const updateSettingsTimelock = (timelock: number) =>
+      program.methods
+        .updateSettingsTimelock(new BN(timelock))
+        .accounts({ faucetState: faucetStatePda, admin: admin.publicKey })
+        .signers([admin])
+        .rpc();
+    const setActive = (isActive: boolean) =>
+      program.methods
+        .setActive(isActive)
+        .accounts({ faucetState: faucetStatePda, admin: admin.publicKey })
+        .signers([admin])
+        .rpc();
+    const proposeAdmin = (newAdmin: PublicKey, signer: Keypair) =>
+      program.methods
+        .proposeAdmin(newAdmin)
+        .accounts({ faucetState: faucetStatePda, admin: signer.publicKey })
+        .signers([signer])
+        .rpc();
+    const acceptAdmin = (signer: Keypair) =>
+      program.methods
+        .acceptAdmin()
+        .accounts({ faucetState: faucetStatePda, newAdmin: signer.publicKey })
+        .signers([signer])
+        .rpc();
+
+    before(async function () {
+      const faucetState = await program.account.faucetState.fetchNullable(faucetStatePda);
+      if (!faucetState?.admin.equals(admin.publicKey)) {
+        console.log("  Faucet is not administered by the test wallet; skipping faucet administration");
+        this.skip();
+      }
+    });
+
+    it("should hold a queued faucet change until the timelock elapses", async () => {
+      await expectError(adminCall("cancelSettings"), "NoPendingSettings");
+      await expectError(updateSettingsTimelock(-1), "InvalidTimelock");
+
+      // With no timelock yet, raising it applies right away
+      await updateSettingsTimelock(TEST_TIMELOCK);
+      await adminCall("executeSettings");
+      let faucetState = await program.account.faucetState.fetch(faucetStatePda);
+      expect(faucetState.settingsTimelock.toNumber()).to.equal(TEST_TIMELOCK);
+      const { isActive } = faucetState;
+
+      // Pausing or unpausing waits out the timelock and can be discarded meanwhile
+      await setActive(!isActive);
+      await expectError(adminCall("executeSettings"), "TimelockNotElapsed");
+      await adminCall("cancelSettings");
+      faucetState = await program.account.faucetState.fetch(faucetStatePda);
+      expect(faucetState.pendingSettings).to.be.null;
+      expect(faucetState.isActive).to.equal(isActive);
+
+      // Lowering the timelock back to 0 is itself held for the current timelock,
+      // and a change queued after it does not restart its timer
+      await updateSettingsTimelock(0);
+      await expectError(adminCall("executeSettings"), "TimelockNotElapsed");
+      const timelockAt = (await program.account.faucetState.fetch(faucetStatePda)).pendingSettings.settingsTimelock
+        .executableAt;
+      await sleep(4000);
+      await setActive(isActive);
+      const { pendingSettings } = await program.account.faucetState.fetch(faucetStatePda);
+      expect(pendingSettings.settingsTimelock.executableAt.toString()).to.equal(timelockAt.toString());
+      expect(pendingSettings.isActive.executableAt.toNumber()).to.be.greaterThan(timelockAt.toNumber());
+      await sleep((timelockAt.toNumber() + 2) * 1000 - Date.now());
+      await adminCall("executeSettings");
+      faucetState = await program.account.faucetState.fetch(faucetStatePda);
+      expect(faucetState.settingsTimelock.toNumber()).to.equal(0);
+      expect(faucetState.pendingSettings.settingsTimelock).to.be.null;
+      await adminCall("cancelSettings");
+
+      console.log("✓ Faucet changes held for their own timelocks");
+    });
+
+    it("should hand the faucet admin role over only once the nominee accepts", async () => {
+      await expectError(proposeAdmin(nominee.publicKey, nominee), "ConstraintHasOne");
+      await expectError(acceptAdmin(nominee), "Unauthorized");
+
+      await proposeAdmin(nominee.publicKey, admin);
+      await acceptAdmin(nominee);
+      let faucetState = await program.account.faucetState.fetch(faucetStatePda);
+      expect(faucetState.admin.toBase58()).to.equal(nominee.publicKey.toBase58());
+      expect(faucetState.pendingAdmin).to.be.null;
+
+      // Hand the role back
+      await proposeAdmin(admin.publicKey, nominee);
+      await acceptAdmin(admin);
+      faucetState = await program.account.faucetState.fetch(faucetStatePda);
+      expect(faucetState.admin.toBase58()).to.equal(admin.publicKey.toBase58());
+      console.log("✓ Faucet admin role handed over in two steps");
+    });
+  });
+
   describe("Faucet Configuration", () => {
     it("should enforce 6-hour cooldown between claims", () => {
       const CLAIM_COOLDOWN = 21600; // 6 hours in seconds